@@ -0,0 +1,527 @@
+use std::io::BufRead;
+use std::io::Write;
+
+lazy_static::lazy_static! {
+    static ref REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+    // how often WebSocket clients are checked for closed connections and pings
+    static ref POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+}
+
+// requests are handled by a fixed number of threads, so that many connections cannot exhaust the app
+const NUM_WORKERS: usize = 4;
+// longest request line or header (bytes), and most headers, accepted in a request
+const MAX_LINE_LENGTH: u64 = 8192;
+const MAX_HEADERS: usize = 100;
+// most bytes waiting to be sent to a WebSocket client before it is dropped for not reading them
+const MAX_UNSENT: usize = 1 << 20;
+
+fn default_address() -> String {
+    String::from("127.0.0.1:8080")
+}
+
+/// serve the current tracks as JSON over HTTP
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
+pub struct ApiConfiguration {
+    /// `host:port` on which to listen; use `0.0.0.0:port` to serve other computers on the network
+    #[serde(default = "default_address")]
+    pub address: String,
+}
+
+impl Default for ApiConfiguration {
+    fn default() -> Self {
+        Self {
+            address: default_address(),
+        }
+    }
+}
+
+custom_error::custom_error! {pub ApiError
+    BindFailure { address: String, message: String } = "cannot serve API on {address} - {message}",
+}
+
+// the latest state of a track
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct TrackSummary {
+    pub name: String,
+    pub num_packets: usize,
+    pub latest: Option<crate::location::BalloonLocation>,
+    /// latest smoothed ascent rate (m/s)
+    pub ascent_rate: Option<f64>,
+    /// latest ground speed (m/s)
+    pub ground_speed: Option<f64>,
+    /// course over the ground (degrees clockwise from north)
+    pub heading: Option<f64>,
+    pub max_altitude: Option<f64>,
+    pub ascending: bool,
+    pub descending: bool,
+    pub landed: bool,
+    /// estimated seconds until landing
+    pub time_to_ground: Option<i64>,
+    pub predicted_landing: Option<crate::location::BalloonLocation>,
+    pub prediction: Option<crate::location::track::LocationTrack>,
+}
+
+impl TrackSummary {
+    pub fn new(track: &crate::location::track::BalloonTrack) -> Self {
+        Self {
+            name: track.name.to_owned(),
+            num_packets: track.locations.len(),
+            latest: track.locations.last().cloned(),
+            ascent_rate: track.smoothed_ascent_rates().last().copied(),
+            ground_speed: crate::location::track::ground_speeds(track.kinematic_locations())
+                .last()
+                .copied(),
+            heading: track.heading(),
+            max_altitude: track.max_altitude(),
+            ascending: track.ascending(),
+            descending: track.descending(),
+            landed: track.landed(),
+            time_to_ground: track
+                .estimated_time_to_ground()
+                .map(|duration| duration.num_seconds()),
+            predicted_landing: track
+                .prediction
+                .as_ref()
+                .and_then(|prediction| prediction.last().cloned()),
+            prediction: track.prediction.to_owned(),
+        }
+    }
+}
+
+// a track with all of its locations
+#[derive(serde::Serialize)]
+struct TrackDetail<'a> {
+    #[serde(flatten)]
+    summary: &'a TrackSummary,
+    locations: &'a crate::location::track::LocationTrack,
+}
+
+type Snapshot = Vec<(TrackSummary, crate::location::track::LocationTrack)>;
+
+type LiveSocket = tungstenite::WebSocket<std::net::TcpStream>;
+
+enum Broadcast {
+    // a newly connected client, with the messages to start it off
+    Connect(Box<LiveSocket>, Vec<tungstenite::Message>),
+    Messages(Vec<tungstenite::Message>),
+}
+
+// serves `/tracks` and `/track/{name}` from a copy of the tracks, updated by the app after each tick,
+// and pushes new locations to WebSocket clients connected to `/live`
+pub struct ApiServer {
+    pub address: std::net::SocketAddr,
+    tracks: std::sync::Arc<std::sync::RwLock<Snapshot>>,
+    live: std::sync::mpsc::Sender<Broadcast>,
+}
+
+impl ApiServer {
+    pub fn start(configuration: &ApiConfiguration) -> Result<Self, ApiError> {
+        let listener = std::net::TcpListener::bind(&configuration.address).map_err(|error| {
+            ApiError::BindFailure {
+                address: configuration.address.to_owned(),
+                message: error.to_string(),
+            }
+        })?;
+        let address = listener
+            .local_addr()
+            .map_err(|error| ApiError::BindFailure {
+                address: configuration.address.to_owned(),
+                message: error.to_string(),
+            })?;
+
+        let tracks = std::sync::Arc::new(std::sync::RwLock::new(vec![]));
+        let (live, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || broadcast(receiver));
+        for _ in 0..NUM_WORKERS {
+            let listener = listener
+                .try_clone()
+                .map_err(|error| ApiError::BindFailure {
+                    address: configuration.address.to_owned(),
+                    message: error.to_string(),
+                })?;
+            let tracks = tracks.clone();
+            let live = live.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = handle(stream, &tracks, &live);
+                }
+            });
+        }
+
+        Ok(Self {
+            address,
+            tracks,
+            live,
+        })
+    }
+
+    // refresh the summary of every track; locations are only copied from tracks that have grown since the last update
+    pub fn update(&self, tracks: &[crate::location::track::BalloonTrack]) {
+        if let Ok(mut snapshot) = self.tracks.write() {
+            let mut previous: std::collections::HashMap<
+                String,
+                crate::location::track::LocationTrack,
+            > = snapshot
+                .drain(..)
+                .map(|(summary, locations)| (summary.name, locations))
+                .collect();
+            *snapshot = tracks
+                .iter()
+                .map(|track| {
+                    let locations = match previous.remove(&track.name) {
+                        Some(locations) if locations.len() == track.locations.len() => locations,
+                        _ => track.locations.to_owned(),
+                    };
+                    (TrackSummary::new(track), locations)
+                })
+                .collect();
+        }
+    }
+
+    // push the given new locations of the track, then its updated summary and prediction
+    pub fn push(
+        &self,
+        track: &crate::location::track::BalloonTrack,
+        locations: &[&crate::location::BalloonLocation],
+    ) {
+        let mut messages: Vec<tungstenite::Message> = locations
+            .iter()
+            .map(|location| {
+                tungstenite::Message::text(
+                    serde_json::json!({ "type": "location", "track": track.name, "location": location })
+                        .to_string(),
+                )
+            })
+            .collect();
+        messages.push(track_message(&TrackSummary::new(track)));
+        let _ = self.live.send(Broadcast::Messages(messages));
+    }
+}
+
+fn track_message(summary: &TrackSummary) -> tungstenite::Message {
+    tungstenite::Message::text(serde_json::json!({ "type": "track", "track": summary }).to_string())
+}
+
+// send messages to the WebSocket clients, in its own thread so that a slow client never holds up the app,
+// and read what the clients send, which answers their pings and drops clients that close the connection
+fn broadcast(receiver: std::sync::mpsc::Receiver<Broadcast>) {
+    let mut clients: Vec<LiveSocket> = vec![];
+    loop {
+        match receiver.recv_timeout(*POLL_INTERVAL) {
+            Ok(Broadcast::Connect(mut client, messages)) => {
+                if send(&mut client, &messages) {
+                    clients.push(*client);
+                }
+            }
+            Ok(Broadcast::Messages(messages)) => {
+                clients.retain_mut(|client| send(client, &messages));
+            }
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        clients.retain_mut(receive);
+    }
+}
+
+// queue the messages and send as much as the client will take, returning whether the client is still connected
+fn send(client: &mut LiveSocket, messages: &[tungstenite::Message]) -> bool {
+    for message in messages {
+        if !connected(client.write(message.to_owned())) {
+            return false;
+        }
+    }
+    connected(client.flush())
+}
+
+// read everything the client has sent, returning whether the client is still connected
+fn receive(client: &mut LiveSocket) -> bool {
+    loop {
+        match client.read() {
+            Ok(_) => continue,
+            Err(error) => {
+                if !connected(Err(error)) {
+                    return false;
+                }
+                // send any replies and messages still waiting
+                return connected(client.flush());
+            }
+        }
+    }
+}
+
+// clients are not blocking, so an operation that would block leaves the rest for later
+fn connected(result: tungstenite::Result<()>) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(tungstenite::Error::Io(error)) => error.kind() == std::io::ErrorKind::WouldBlock,
+        Err(_) => false,
+    }
+}
+
+fn handle(
+    stream: std::net::TcpStream,
+    tracks: &std::sync::RwLock<Snapshot>,
+    live: &std::sync::mpsc::Sender<Broadcast>,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(*REQUEST_TIMEOUT))?;
+    let mut reader = std::io::BufReader::new(stream);
+
+    let request_line = read_line(&mut reader)?;
+    let mut websocket_key = None;
+    let mut num_headers = 0;
+    loop {
+        let header = read_line(&mut reader)?;
+        if header.trim().is_empty() {
+            break;
+        }
+        num_headers += 1;
+        if num_headers > MAX_HEADERS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "too many headers",
+            ));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                websocket_key = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    if let (Some("GET"), Some("/live"), Some(key)) = (parts.next(), parts.next(), &websocket_key) {
+        let read_ahead = reader.buffer().to_vec();
+        let mut stream = reader.into_inner();
+        write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {:}\r\n\r\n",
+            tungstenite::handshake::derive_accept_key(key.as_bytes())
+        )?;
+        stream.set_nonblocking(true)?;
+        let client = tungstenite::WebSocket::from_partially_read(
+            stream,
+            read_ahead,
+            tungstenite::protocol::Role::Server,
+            Some(
+                tungstenite::protocol::WebSocketConfig::default().max_write_buffer_size(MAX_UNSENT),
+            ),
+        );
+        // start the client off with the current state of every track
+        let messages = tracks
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(summary, _)| track_message(summary))
+            .collect();
+        let _ = live.send(Broadcast::Connect(Box::new(client), messages));
+        return Ok(());
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => respond(path, &tracks.read().unwrap()),
+        (Some(_), Some(_)) => (
+            "405 Method Not Allowed",
+            error_body("only GET requests are supported"),
+        ),
+        _ => ("400 Bad Request", error_body("malformed request")),
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {:}\r\nContent-Type: application/json\r\nContent-Length: {:}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    )?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+// a line of the request, refusing lines longer than any reasonable request would send
+fn read_line(reader: &mut impl BufRead) -> std::io::Result<String> {
+    let mut line = String::new();
+    let length = std::io::Read::take(reader, MAX_LINE_LENGTH).read_line(&mut line)?;
+    if length as u64 == MAX_LINE_LENGTH && !line.ends_with('\n') {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "request line too long",
+        ));
+    }
+    Ok(line)
+}
+
+// status and JSON body for the given path
+fn respond(path: &str, tracks: &Snapshot) -> (&'static str, String) {
+    let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+    if path == "/tracks" {
+        let summaries: Vec<&TrackSummary> = tracks.iter().map(|(summary, _)| summary).collect();
+        return ("200 OK", serde_json::to_string(&summaries).unwrap());
+    }
+    if let Some(name) = path.strip_prefix("/track/") {
+        let name = percent_decode(name);
+        return match tracks.iter().find(|(summary, _)| summary.name == name) {
+            Some((summary, locations)) => (
+                "200 OK",
+                serde_json::to_string(&TrackDetail { summary, locations }).unwrap(),
+            ),
+            None => ("404 Not Found", error_body(&format!("no track {:}", name))),
+        };
+    }
+    (
+        "404 Not Found",
+        error_body("available paths are /tracks, /track/{name}, and /live (WebSocket)"),
+    )
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+// decode `%XX` escapes, i.e. in track names with spaces
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = vec![];
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            if let Some(byte) = value
+                .get(index + 1..index + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn get(address: &std::net::SocketAddr, path: &str) -> (String, serde_json::Value) {
+        let mut stream = std::net::TcpStream::connect(address).unwrap();
+        write!(stream, "GET {:} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (
+            head.lines().next().unwrap().to_string(),
+            serde_json::from_str(body).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_api() {
+        let server = ApiServer::start(&ApiConfiguration {
+            address: String::from("127.0.0.1:0"),
+        })
+        .unwrap();
+
+        let mut track = crate::location::track::BalloonTrack::new("NS 111".to_string());
+        track.push(
+            crate::location::BalloonLocation::from_aprs_frame(
+                r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu".as_bytes(),
+                None,
+            )
+            .unwrap(),
+        );
+        server.update(&[track]);
+
+        let (status, tracks) = get(&server.address, "/tracks");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(tracks[0]["name"], "NS 111");
+        assert_eq!(tracks[0]["num_packets"], 1);
+        assert_eq!(tracks[0]["latest"]["data"]["callsign"], "W3EAX-8");
+        assert!(tracks[0].get("locations").is_none());
+
+        let (status, track) = get(&server.address, "/track/NS%20111");
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(track["locations"].as_array().unwrap().len(), 1);
+        assert_eq!(track["landed"], false);
+
+        let (status, error) = get(&server.address, "/track/W3EAX-9");
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+        assert_eq!(error["error"], "no track W3EAX-9");
+
+        // requests with overlong lines are dropped without a response
+        let mut stream = std::net::TcpStream::connect(server.address).unwrap();
+        // the server may hang up before the whole request is written
+        let _ = write!(stream, "GET /{:} HTTP/1.1\r\n\r\n", "a".repeat(10000));
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        assert!(response.is_empty());
+    }
+
+    fn read_message(
+        socket: &mut tungstenite::WebSocket<
+            tungstenite::stream::MaybeTlsStream<std::net::TcpStream>,
+        >,
+    ) -> serde_json::Value {
+        serde_json::from_str(socket.read().unwrap().to_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_websocket() {
+        let server = ApiServer::start(&ApiConfiguration {
+            address: String::from("127.0.0.1:0"),
+        })
+        .unwrap();
+
+        let mut track = crate::location::track::BalloonTrack::new("W3EAX-8".to_string());
+        track.push(
+            crate::location::BalloonLocation::from_aprs_frame(
+                r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu".as_bytes(),
+                None,
+            )
+            .unwrap(),
+        );
+        let tracks = vec![track];
+        server.update(&tracks);
+        let mut track = tracks.into_iter().next().unwrap();
+
+        let (mut socket, _) =
+            tungstenite::connect(format!("ws://{:}/live", server.address)).unwrap();
+
+        // the client is registered before it is sent the current tracks
+        let message = read_message(&mut socket);
+        assert_eq!(message["type"], "track");
+        assert_eq!(message["track"]["num_packets"], 1);
+
+        track.push(
+            crate::location::BalloonLocation::from_aprs_frame(
+                r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:GiD:jcwO   /A=028365|!R|  /W3EAX,267,0,18'C,http://www.umd.edu".as_bytes(),
+                None,
+            )
+            .unwrap(),
+        );
+        server.push(&track, &[&track.locations[1]]);
+
+        let message = read_message(&mut socket);
+        assert_eq!(message["type"], "location");
+        assert_eq!(message["track"], "W3EAX-8");
+        assert_eq!(message["location"]["data"]["callsign"], "W3EAX-8");
+        let message = read_message(&mut socket);
+        assert_eq!(message["type"], "track");
+        assert_eq!(message["track"]["num_packets"], 2);
+
+        // the server answers the close of the connection
+        socket.close(None).unwrap();
+        loop {
+            match socket.read() {
+                Ok(_) => continue,
+                Err(error) => {
+                    assert!(matches!(error, tungstenite::Error::ConnectionClosed));
+                    break;
+                }
+            }
+        }
+    }
+}
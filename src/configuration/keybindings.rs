@@ -0,0 +1,369 @@
+use crossterm::event::KeyCode;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyAction {
+    Quit,
+    Refresh,
+    NextTab,
+    PreviousTab,
+    NextChart,
+    PreviousChart,
+    ScrollUp,
+    ScrollDown,
+    ToggleMap,
+    ToggleRaw,
+    CopyPosition,
+    CopyLanding,
+    PredictDescent,
+    ZoomIn,
+    ZoomOut,
+    PanLeft,
+    PanRight,
+    ResetView,
+    Filter,
+    Sort,
+    Pause,
+    Help,
+}
+
+fn default_quit() -> Vec<KeyCode> {
+    vec![KeyCode::Char('q'), KeyCode::Esc]
+}
+
+fn default_refresh() -> Vec<KeyCode> {
+    vec![KeyCode::Char('r'), KeyCode::Char(' ')]
+}
+
+fn default_next_tab() -> Vec<KeyCode> {
+    vec![KeyCode::Tab, KeyCode::Right]
+}
+
+fn default_prev_tab() -> Vec<KeyCode> {
+    vec![KeyCode::BackTab, KeyCode::Left]
+}
+
+fn default_next_chart() -> Vec<KeyCode> {
+    vec![KeyCode::Up]
+}
+
+fn default_prev_chart() -> Vec<KeyCode> {
+    vec![KeyCode::Down]
+}
+
+fn default_scroll_up() -> Vec<KeyCode> {
+    vec![KeyCode::Up]
+}
+
+fn default_scroll_down() -> Vec<KeyCode> {
+    vec![KeyCode::Down]
+}
+
+fn default_toggle_map() -> Vec<KeyCode> {
+    vec![KeyCode::Char('m')]
+}
+
+fn default_toggle_raw() -> Vec<KeyCode> {
+    vec![KeyCode::Char('w')]
+}
+
+fn default_copy_position() -> Vec<KeyCode> {
+    vec![KeyCode::Char('c')]
+}
+
+fn default_copy_landing() -> Vec<KeyCode> {
+    vec![KeyCode::Char('C')]
+}
+
+fn default_predict_descent() -> Vec<KeyCode> {
+    vec![KeyCode::Char('d')]
+}
+
+fn default_zoom_in() -> Vec<KeyCode> {
+    vec![KeyCode::Char('+'), KeyCode::Char('=')]
+}
+
+fn default_zoom_out() -> Vec<KeyCode> {
+    vec![KeyCode::Char('-')]
+}
+
+fn default_pan_left() -> Vec<KeyCode> {
+    vec![KeyCode::Char('[')]
+}
+
+fn default_pan_right() -> Vec<KeyCode> {
+    vec![KeyCode::Char(']')]
+}
+
+fn default_reset_view() -> Vec<KeyCode> {
+    vec![KeyCode::Char('0')]
+}
+
+fn default_filter() -> Vec<KeyCode> {
+    vec![KeyCode::Char('/')]
+}
+
+fn default_sort() -> Vec<KeyCode> {
+    vec![KeyCode::Char('s')]
+}
+
+fn default_pause() -> Vec<KeyCode> {
+    vec![KeyCode::Char('p')]
+}
+
+fn default_help() -> Vec<KeyCode> {
+    vec![KeyCode::Char('?')]
+}
+
+/// keys that trigger each action in the user interface, i.e. `quit: [q, esc]`
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct KeyBindings {
+    #[serde(default = "default_quit", with = "key_names")]
+    pub quit: Vec<KeyCode>,
+    #[serde(default = "default_refresh", with = "key_names")]
+    pub refresh: Vec<KeyCode>,
+    #[serde(default = "default_next_tab", with = "key_names")]
+    pub next_tab: Vec<KeyCode>,
+    #[serde(default = "default_prev_tab", with = "key_names")]
+    pub prev_tab: Vec<KeyCode>,
+    #[serde(default = "default_next_chart", with = "key_names")]
+    pub next_chart: Vec<KeyCode>,
+    #[serde(default = "default_prev_chart", with = "key_names")]
+    pub prev_chart: Vec<KeyCode>,
+    #[serde(default = "default_scroll_up", with = "key_names")]
+    pub scroll_up: Vec<KeyCode>,
+    #[serde(default = "default_scroll_down", with = "key_names")]
+    pub scroll_down: Vec<KeyCode>,
+    #[serde(default = "default_toggle_map", with = "key_names")]
+    pub toggle_map: Vec<KeyCode>,
+    #[serde(default = "default_toggle_raw", with = "key_names")]
+    pub toggle_raw: Vec<KeyCode>,
+    #[serde(default = "default_copy_position", with = "key_names")]
+    pub copy_position: Vec<KeyCode>,
+    #[serde(default = "default_copy_landing", with = "key_names")]
+    pub copy_landing: Vec<KeyCode>,
+    #[serde(default = "default_predict_descent", with = "key_names")]
+    pub predict_descent: Vec<KeyCode>,
+    #[serde(default = "default_zoom_in", with = "key_names")]
+    pub zoom_in: Vec<KeyCode>,
+    #[serde(default = "default_zoom_out", with = "key_names")]
+    pub zoom_out: Vec<KeyCode>,
+    #[serde(default = "default_pan_left", with = "key_names")]
+    pub pan_left: Vec<KeyCode>,
+    #[serde(default = "default_pan_right", with = "key_names")]
+    pub pan_right: Vec<KeyCode>,
+    #[serde(default = "default_reset_view", with = "key_names")]
+    pub reset_view: Vec<KeyCode>,
+    #[serde(default = "default_filter", with = "key_names")]
+    pub filter: Vec<KeyCode>,
+    #[serde(default = "default_sort", with = "key_names")]
+    pub sort: Vec<KeyCode>,
+    #[serde(default = "default_pause", with = "key_names")]
+    pub pause: Vec<KeyCode>,
+    #[serde(default = "default_help", with = "key_names")]
+    pub help: Vec<KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            quit: default_quit(),
+            refresh: default_refresh(),
+            next_tab: default_next_tab(),
+            prev_tab: default_prev_tab(),
+            next_chart: default_next_chart(),
+            prev_chart: default_prev_chart(),
+            scroll_up: default_scroll_up(),
+            scroll_down: default_scroll_down(),
+            toggle_map: default_toggle_map(),
+            toggle_raw: default_toggle_raw(),
+            copy_position: default_copy_position(),
+            copy_landing: default_copy_landing(),
+            predict_descent: default_predict_descent(),
+            zoom_in: default_zoom_in(),
+            zoom_out: default_zoom_out(),
+            pan_left: default_pan_left(),
+            pan_right: default_pan_right(),
+            reset_view: default_reset_view(),
+            filter: default_filter(),
+            sort: default_sort(),
+            pause: default_pause(),
+            help: default_help(),
+        }
+    }
+}
+
+impl KeyBindings {
+    // name, keys, and action of every binding
+    pub fn bindings(&self) -> Vec<(&'static str, &Vec<KeyCode>, KeyAction)> {
+        vec![
+            ("quit", &self.quit, KeyAction::Quit),
+            ("refresh", &self.refresh, KeyAction::Refresh),
+            ("next_tab", &self.next_tab, KeyAction::NextTab),
+            ("prev_tab", &self.prev_tab, KeyAction::PreviousTab),
+            ("next_chart", &self.next_chart, KeyAction::NextChart),
+            ("prev_chart", &self.prev_chart, KeyAction::PreviousChart),
+            ("scroll_up", &self.scroll_up, KeyAction::ScrollUp),
+            ("scroll_down", &self.scroll_down, KeyAction::ScrollDown),
+            ("toggle_map", &self.toggle_map, KeyAction::ToggleMap),
+            ("toggle_raw", &self.toggle_raw, KeyAction::ToggleRaw),
+            (
+                "copy_position",
+                &self.copy_position,
+                KeyAction::CopyPosition,
+            ),
+            ("copy_landing", &self.copy_landing, KeyAction::CopyLanding),
+            (
+                "predict_descent",
+                &self.predict_descent,
+                KeyAction::PredictDescent,
+            ),
+            ("zoom_in", &self.zoom_in, KeyAction::ZoomIn),
+            ("zoom_out", &self.zoom_out, KeyAction::ZoomOut),
+            ("pan_left", &self.pan_left, KeyAction::PanLeft),
+            ("pan_right", &self.pan_right, KeyAction::PanRight),
+            ("reset_view", &self.reset_view, KeyAction::ResetView),
+            ("filter", &self.filter, KeyAction::Filter),
+            ("sort", &self.sort, KeyAction::Sort),
+            ("pause", &self.pause, KeyAction::Pause),
+            ("help", &self.help, KeyAction::Help),
+        ]
+    }
+
+    // every action bound to the given key
+    pub fn actions(&self, key: &KeyCode) -> Vec<KeyAction> {
+        self.bindings()
+            .into_iter()
+            .filter(|(_, keys, _)| keys.contains(key))
+            .map(|(_, _, action)| action)
+            .collect()
+    }
+}
+
+// a single character, or the name of a special key such as `esc` or `up`
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut characters = name.chars();
+    if let (Some(character), None) = (characters.next(), characters.next()) {
+        return Some(KeyCode::Char(character));
+    }
+
+    let name = name.to_lowercase();
+    Some(match name.as_str() {
+        "space" => KeyCode::Char(' '),
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backtab" | "shift+tab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => match name.strip_prefix('f').map(|number| number.parse::<u8>()) {
+            Some(Ok(number)) if (1..=12).contains(&number) => KeyCode::F(number),
+            _ => return None,
+        },
+    })
+}
+
+pub fn key_name(key: &KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => String::from("space"),
+        KeyCode::Char(character) => character.to_string(),
+        KeyCode::Esc => String::from("esc"),
+        KeyCode::Enter => String::from("enter"),
+        KeyCode::Tab => String::from("tab"),
+        KeyCode::BackTab => String::from("backtab"),
+        KeyCode::Backspace => String::from("backspace"),
+        KeyCode::Delete => String::from("delete"),
+        KeyCode::Insert => String::from("insert"),
+        KeyCode::Left => String::from("left"),
+        KeyCode::Right => String::from("right"),
+        KeyCode::Up => String::from("up"),
+        KeyCode::Down => String::from("down"),
+        KeyCode::Home => String::from("home"),
+        KeyCode::End => String::from("end"),
+        KeyCode::PageUp => String::from("pageup"),
+        KeyCode::PageDown => String::from("pagedown"),
+        KeyCode::F(number) => format!("f{:}", number),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+mod key_names {
+    use serde::Deserialize;
+
+    pub fn serialize<S>(
+        keys: &[crossterm::event::KeyCode],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(keys.iter().map(super::key_name))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<crossterm::event::KeyCode>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let names: Vec<String> = Vec::deserialize(deserializer)?;
+        names
+            .iter()
+            .map(|name| {
+                super::parse_key(name).ok_or_else(|| {
+                    serde::de::Error::custom(format!("unrecognized key \"{:}\"", name))
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        for name in ["q", "esc", "space", "backtab", "pagedown", "f5", "?"] {
+            assert_eq!(key_name(&parse_key(name).unwrap()), name);
+        }
+        assert_eq!(parse_key("Esc"), Some(KeyCode::Esc));
+        assert_eq!(parse_key("f13"), None);
+        assert_eq!(parse_key("hyper"), None);
+    }
+
+    #[test]
+    fn test_keybindings() {
+        let keybindings: KeyBindings = serde_yaml::from_str(
+            r#"
+            next_tab: [l, tab]
+            prev_tab: [h]
+            scroll_up: [k]
+            scroll_down: [j]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keybindings.actions(&KeyCode::Char('l')),
+            vec![KeyAction::NextTab]
+        );
+        assert!(keybindings.actions(&KeyCode::Right).is_empty());
+        assert_eq!(
+            keybindings.actions(&KeyCode::Char('q')),
+            vec![KeyAction::Quit]
+        );
+        assert_eq!(
+            keybindings.actions(&KeyCode::Up),
+            vec![KeyAction::NextChart]
+        );
+
+        assert!(serde_yaml::from_str::<KeyBindings>("quit: [hyper]").is_err());
+    }
+}
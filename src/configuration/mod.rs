@@ -1,21 +1,495 @@
+pub mod keybindings;
 pub mod prediction;
+pub mod theme;
 
 fn default_name() -> String {
     String::from("unnamed_flight")
 }
 
+// each section is described once, in `TEMPLATE_DESCRIPTIONS`, which is written into the template
 #[derive(serde::Deserialize, Clone, Default, serde::Serialize)]
 pub struct RunConfiguration {
     #[serde(default = "default_name")]
     pub name: String,
     pub callsigns: Option<Vec<String>>,
     #[serde(default)]
+    pub primary_callsigns: Vec<String>,
+    #[serde(default)]
+    pub aliases: std::collections::BTreeMap<String, Vec<String>>,
+    #[serde(default)]
     pub time: TimeConfiguration,
     pub output_file: Option<std::path::PathBuf>,
+    pub resampled_output: Option<ResampledOutputConfiguration>,
     pub log_file: Option<std::path::PathBuf>,
+    pub state_file: Option<std::path::PathBuf>,
     #[serde(default)]
     pub connections: ConnectionConfiguration,
     pub prediction: Option<crate::configuration::prediction::PredictionConfiguration>,
+    #[serde(default)]
+    pub battery: BatteryConfiguration,
+    #[serde(default)]
+    pub outliers: OutlierConfiguration,
+    #[serde(default)]
+    pub duplicates: DuplicateConfiguration,
+    #[serde(default)]
+    pub ascent_rate: AscentRateConfiguration,
+    #[serde(default)]
+    pub landing: LandingConfiguration,
+    #[serde(default)]
+    pub float: FloatConfiguration,
+    #[serde(default)]
+    pub alerts: AlertConfiguration,
+    #[serde(default)]
+    pub kalman: KalmanConfiguration,
+    pub connectivity: Option<ConnectivityConfiguration>,
+    #[serde(default)]
+    pub http: HttpConfiguration,
+    #[cfg(feature = "api")]
+    pub api: Option<crate::api::ApiConfiguration>,
+    #[serde(
+        default,
+        deserialize_with = "crate::location::deserialize_optional_fixed_position"
+    )]
+    pub ground_station: Option<crate::location::Location>,
+    #[serde(default)]
+    pub keybindings: keybindings::KeyBindings,
+    #[serde(default)]
+    pub theme: theme::ThemeConfiguration,
+    #[serde(default)]
+    pub units: Units,
+    pub timezone: Option<chrono_tz::Tz>,
+}
+
+custom_error::custom_error! {pub ConfigurationError
+    ReadFailure { path: String, message: String } = "could not open config file {path}: {message}",
+    WriteFailure { path: String, message: String } = "could not write config file {path}: {message}",
+    Invalid { path: String, message: String } = "invalid configuration {path} - {message}",
+    UnsetVariable { name: String } = "environment variable `{name}` referenced in configuration is not set",
+}
+
+lazy_static::lazy_static! {
+    static ref ENVIRONMENT_VARIABLE_PATTERN: regex::Regex =
+        regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+}
+
+// description of each top-level section, written above it in the template
+const TEMPLATE_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("name", "name of this flight, used in the names of output and log files"),
+    ("callsigns", "callsigns of the payloads to track; `*` matches any characters and `?` a single character"),
+    ("primary_callsigns", "tracks to always show first in the tab bar, in this order"),
+    ("aliases", "track names, each with the callsigns whose packets are combined into that track"),
+    ("time", "only retrieve packets between `start` and `end`, reading connections every `interval` seconds"),
+    ("output_file", "GeoJSON (`.geojson`), CSV (`.csv`), or GPX (`.gpx`) file to which to write received packets"),
+    ("resampled_output", "CSV file to which to write every track interpolated to a regular interval"),
+    ("log_file", "text file (or directory) to which to append log messages"),
+    ("state_file", "YAML file in which to keep the selected tab, chart, and pause state between runs"),
+    ("connections", "sources of packets; uncomment and fill in the ones you use"),
+    ("prediction", "predict the flight path and landing location from a launch location and flight profile"),
+    ("battery", "warn when the battery voltage in telemetry drops below `minimum_voltage` (V) or declines faster than `maximum_decline_rate` (V/h)"),
+    ("outliers", "flag packets implying an impossible ground speed or ascent rate (m/s)"),
+    ("duplicates", "treat packets at the same coordinates (to `coordinate_precision` decimal places) and altitude as duplicates"),
+    ("ascent_rate", "smoothing of the ascent rate, and the rate (m/s) above which a balloon is ascending or descending"),
+    ("landing", "consider a balloon landed below `maximum_altitude` (m) and with little movement over `samples` packets, appending the prediction error to `error_file`"),
+    ("float", "consider a balloon floating above `minimum_altitude` (m) while its altitude stays level over `samples` packets"),
+    ("alerts", "alert when a balloon starts descending or is within `time_to_ground` seconds of landing"),
+    ("kalman", "smooth positions with a Kalman filter; the smoothed track drives the time to ground, heading, and predictions"),
+    ("connectivity", "skip online connections and predictions while the network is unreachable"),
+    ("http", "timeout (s), User-Agent, and proxy for every outgoing HTTP request"),
+    ("api", "serve the current tracks as JSON (and live over a WebSocket) on a local address"),
+    ("ground_station", "location from which to show bearing and distance to each balloon"),
+    ("keybindings", "keys that trigger each action in the user interface"),
+    ("theme", "colors of the user interface; `preset` is one of `default`, `high-contrast`, or `mono`"),
+    ("units", "units in which to display values; `metric` or `imperial`"),
+    ("timezone", "IANA timezone in which to display times, i.e. `America/New_York`; defaults to the local timezone"),
+];
+
+// commented-out examples, written above the default value of their section in the template
+const TEMPLATE_EXAMPLES: &[(&str, &str)] = &[
+    ("callsigns", "callsigns:\n  - W3EAX-8\n  - W3EAX-9"),
+    ("output_file", "output_file: flight.geojson"),
+    ("log_file", "log_file: flight_log.txt"),
+    (
+        "connections",
+        "connections:
+  text:
+    - path: ~/packets.txt
+    - port: /dev/ttyUSB0
+      baud_rate: 9600
+  sondehub: {}
+  aprs_fi:
+    api_key: 123456.abcdefhijklmnop
+  aprs_is:
+    callsign: KC3SKW",
+    ),
+    (
+        "prediction",
+        "prediction:
+  start:
+    coord:
+      x: -78.4987
+      y: 40.0157
+    altitude: 1000
+    time: 2022-03-05 10:36:00
+  profile:
+    ascent_rate: 6.5
+    burst_altitude: 25000
+    sea_level_descent_rate: 9
+  output_file: prediction.geojson",
+    ),
+    (
+        "ground_station",
+        "ground_station:
+  coord:
+    x: -76.9383
+    y: 38.9869",
+    ),
+];
+
+// the default configuration, with a description of each section and commented-out examples to fill in
+pub fn annotated_template() -> String {
+    let defaults = serde_yaml::to_string(&RunConfiguration::default()).unwrap();
+
+    let mut template = String::from(
+        "# PacketRaven configuration\n# every option is described at https://packetraven.readthedocs.io/en/latest/configuration.html\n",
+    );
+    for line in defaults.lines() {
+        // top-level keys are not indented
+        if let Some((key, _)) = line
+            .split_once(':')
+            .filter(|_| !line.starts_with([' ', '-']))
+        {
+            template.push('\n');
+            for (_, description) in TEMPLATE_DESCRIPTIONS
+                .iter()
+                .filter(|(name, _)| *name == key)
+            {
+                template += &format!("# {:}\n", description);
+            }
+            for (_, example) in TEMPLATE_EXAMPLES.iter().filter(|(name, _)| *name == key) {
+                template.push_str("#\n");
+                for example_line in example.lines() {
+                    template += &format!("# {:}\n", example_line);
+                }
+            }
+        }
+        template += line;
+        template.push('\n');
+    }
+    template
+}
+
+// read a configuration file, substituting `${NAME}` in string values with environment variables
+pub fn read_configuration(path: &std::path::Path) -> Result<RunConfiguration, ConfigurationError> {
+    let path_string = path.to_string_lossy().to_string();
+    let file = std::fs::File::open(path).map_err(|error| ConfigurationError::ReadFailure {
+        path: path_string.to_owned(),
+        message: error.to_string(),
+    })?;
+
+    let invalid = |error: serde_yaml::Error| ConfigurationError::Invalid {
+        path: path_string.to_owned(),
+        message: error.to_string(),
+    };
+    let mut value: serde_yaml::Value = serde_yaml::from_reader(file).map_err(invalid)?;
+    substitute_environment_variables(&mut value, &|name| std::env::var(name).ok())?;
+    serde_yaml::from_value(value).map_err(invalid)
+}
+
+fn substitute_environment_variables(
+    value: &mut serde_yaml::Value,
+    variable: &dyn Fn(&str) -> Option<String>,
+) -> Result<(), ConfigurationError> {
+    match value {
+        serde_yaml::Value::String(string) => {
+            let mut substituted = String::new();
+            let mut end = 0;
+            for captures in ENVIRONMENT_VARIABLE_PATTERN.captures_iter(string) {
+                let reference = captures.get(0).unwrap();
+                let name = &captures[1];
+                substituted += &string[end..reference.start()];
+                substituted +=
+                    &variable(name).ok_or_else(|| ConfigurationError::UnsetVariable {
+                        name: name.to_string(),
+                    })?;
+                end = reference.end();
+            }
+            if end > 0 {
+                substituted += &string[end..];
+                *string = substituted;
+            }
+        }
+        serde_yaml::Value::Sequence(values) => {
+            for value in values {
+                substitute_environment_variables(value, variable)?;
+            }
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            for (_, value) in mapping.iter_mut() {
+                substitute_environment_variables(value, variable)?;
+            }
+        }
+        serde_yaml::Value::Tagged(tagged) => {
+            substitute_environment_variables(&mut tagged.value, variable)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl RunConfiguration {
+    // terminal velocity of the payloads, from the prediction profile if it describes the payload
+    pub fn terminal_velocity(&self) -> crate::model::TerminalVelocity {
+        self.prediction
+            .as_ref()
+            .and_then(|prediction| prediction.predictions().0.profile.terminal_velocity())
+            .unwrap_or_default()
+    }
+
+    // range of altitudes (m) within which a balloon is at float, from the prediction's float profile if it gives one
+    pub fn float_uncertainty(&self) -> f64 {
+        self.prediction
+            .as_ref()
+            .and_then(|prediction| prediction.predictions().0.float.as_ref()?.uncertainty)
+            .unwrap_or(*crate::prediction::DEFAULT_FLOAT_UNCERTAINTY)
+    }
+
+    // problems that would prevent this configuration from running as intended
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+
+        if let (Some(start), Some(end)) = (self.time.start, self.time.end) {
+            if start > end {
+                errors.push(format!(
+                    "time.start ({:}) is after time.end ({:})",
+                    start.format(&crate::DATETIME_FORMAT),
+                    end.format(&crate::DATETIME_FORMAT)
+                ));
+            }
+        }
+        if self.time.interval <= chrono::Duration::zero() {
+            errors.push(String::from("time.interval must be positive"));
+        }
+        if self.http.timeout <= chrono::Duration::zero() {
+            errors.push(String::from("http.timeout must be positive"));
+        }
+        if let Some(connectivity) = &self.connectivity {
+            if connectivity.timeout <= chrono::Duration::zero() {
+                errors.push(String::from("connectivity.timeout must be positive"));
+            }
+        }
+        if let Some(user_agent) = &self.http.user_agent {
+            if let Err(error) = reqwest::header::HeaderValue::from_str(user_agent) {
+                errors.push(format!(
+                    "http.user_agent is not a valid header - {:}",
+                    error
+                ));
+            }
+        }
+        if let Some(proxy) = &self.http.proxy {
+            if let Err(error) = reqwest::Proxy::all(proxy) {
+                errors.push(format!("http.proxy is not a valid URL - {:}", error));
+            }
+        }
+        if let Some(resampled_output) = &self.resampled_output {
+            if resampled_output.interval <= chrono::Duration::zero() {
+                errors.push(String::from("resampled_output.interval must be positive"));
+            }
+        }
+        if let Some(prediction) = &self.prediction {
+            errors.extend(prediction.validate());
+        }
+        #[cfg(feature = "mqtt")]
+        if let Some(mqtt) = &self.connections.mqtt {
+            if mqtt.password.is_some() && mqtt.username.is_none() {
+                errors.push(String::from(
+                    "connections.mqtt.password requires connections.mqtt.username",
+                ));
+            }
+        }
+        for role in self.theme.unknown_roles() {
+            errors.push(format!("theme.{:} is not a color role", role));
+        }
+
+        #[cfg(any(feature = "aprsfi", feature = "sondehub"))]
+        let has_callsigns = |callsigns: &Option<Vec<String>>| {
+            [callsigns, &self.callsigns].iter().any(|callsigns| {
+                callsigns
+                    .as_ref()
+                    .is_some_and(|callsigns| !callsigns.is_empty())
+            })
+        };
+        // the APIs only look up exact callsigns
+        #[cfg(any(feature = "aprsfi", feature = "sondehub"))]
+        let has_patterns = |callsigns: &Option<Vec<String>>| {
+            callsigns
+                .as_ref()
+                .or(self.callsigns.as_ref())
+                .is_some_and(|callsigns| {
+                    callsigns
+                        .iter()
+                        .any(|callsign| crate::connection::is_callsign_pattern(callsign))
+                })
+        };
+        #[cfg(feature = "aprsfi")]
+        if let Some(aprs_fi) = &self.connections.aprs_fi {
+            if !has_callsigns(&aprs_fi.callsigns) {
+                errors.push(String::from(
+                    "connections.aprs_fi requires a list of callsigns (in `callsigns` or `connections.aprs_fi.callsigns`)",
+                ));
+            } else if has_patterns(&aprs_fi.callsigns) {
+                errors.push(String::from(
+                    "connections.aprs_fi does not support callsign wildcards; list each callsign",
+                ));
+            }
+        }
+        #[cfg(feature = "sondehub")]
+        if let Some(sondehub) = &self.connections.sondehub {
+            if !has_callsigns(&sondehub.callsigns) {
+                errors.push(String::from(
+                    "connections.sondehub requires a list of callsigns (in `callsigns` or `connections.sondehub.callsigns`)",
+                ));
+            } else if has_patterns(&sondehub.callsigns) {
+                errors.push(String::from(
+                    "connections.sondehub does not support callsign wildcards; list each callsign",
+                ));
+            }
+        }
+
+        let mut schedules: Vec<(String, &crate::connection::ConnectionSchedule)> = vec![];
+        #[cfg(feature = "sondehub")]
+        if let Some(sondehub) = &self.connections.sondehub {
+            schedules.push((String::from("connections.sondehub"), &sondehub.schedule));
+        }
+        #[cfg(feature = "aprsfi")]
+        if let Some(aprs_fi) = &self.connections.aprs_fi {
+            schedules.push((String::from("connections.aprs_fi"), &aprs_fi.schedule));
+        }
+        #[cfg(feature = "aprsis")]
+        if let Some(aprs_is) = &self.connections.aprs_is {
+            schedules.push((String::from("connections.aprs_is"), &aprs_is.schedule));
+        }
+        #[cfg(feature = "ais")]
+        if let Some(ais) = &self.connections.ais {
+            schedules.push((String::from("connections.ais"), &ais.schedule));
+        }
+        #[cfg(feature = "postgres")]
+        if let Some(database) = &self.connections.database {
+            schedules.push((String::from("connections.database"), &database.schedule));
+        }
+        for (index, text_stream) in self.connections.text.iter().flatten().enumerate() {
+            let schedule = match text_stream {
+                crate::connection::text::TextStream::AprsTextFile(file) => &file.schedule,
+                crate::connection::text::TextStream::GeoJsonFile(file) => &file.schedule,
+                #[cfg(feature = "serial")]
+                crate::connection::text::TextStream::AprsSerial(serial) => &serial.schedule,
+            };
+            schedules.push((format!("connections.text[{:}]", index), schedule));
+        }
+        for (name, schedule) in schedules {
+            if schedule
+                .interval
+                .is_some_and(|interval| interval <= chrono::Duration::zero())
+            {
+                errors.push(format!("{:}.interval must be positive", name));
+            }
+        }
+
+        if let Some(text_streams) = &self.connections.text {
+            for (index, text_stream) in text_streams.iter().enumerate() {
+                let paths = match text_stream {
+                    crate::connection::text::TextStream::AprsTextFile(file) => {
+                        let paths = file.path.expanded();
+                        if paths.is_empty() {
+                            errors.push(format!(
+                                "connections.text[{:}]: no files match {:}",
+                                index, file.path
+                            ));
+                        }
+                        paths
+                    }
+                    crate::connection::text::TextStream::GeoJsonFile(file) => {
+                        vec![file.path.to_owned()]
+                    }
+                    #[cfg(feature = "serial")]
+                    crate::connection::text::TextStream::AprsSerial(_) => continue,
+                };
+                for path in paths {
+                    if let Err(error) = readable(&path) {
+                        errors.push(format!(
+                            "connections.text[{:}]: cannot read {:} - {:}",
+                            index, path, error
+                        ));
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+// whether the given path is a readable file, or a URL
+fn readable(path: &str) -> Result<(), String> {
+    if std::path::Path::new(path).exists() {
+        std::fs::File::open(path)
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    } else if url::Url::parse(path).is_ok() {
+        Ok(())
+    } else {
+        Err(String::from("file does not exist"))
+    }
+}
+
+const FEET_PER_METER: f64 = 3.28084;
+const METERS_PER_MILE: f64 = 1609.344;
+const MILES_PER_HOUR_PER_METER_PER_SECOND: f64 = 2.23694;
+
+/// units in which to display values; values are always computed in SI units
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    // altitudes and short distances, given in meters
+    pub fn length(&self, meters: f64, precision: usize) -> String {
+        match self {
+            Self::Metric => format!("{:.*} m", precision, meters),
+            Self::Imperial => format!("{:.*} ft", precision, meters * FEET_PER_METER),
+        }
+    }
+
+    // long distances, given in meters
+    pub fn distance(&self, meters: f64, precision: usize) -> String {
+        match self {
+            Self::Metric => format!("{:.*} km", precision, meters / 1000.0),
+            Self::Imperial => format!("{:.*} mi", precision, meters / METERS_PER_MILE),
+        }
+    }
+
+    // ground speeds, given in meters per second
+    pub fn speed(&self, meters_per_second: f64, precision: usize) -> String {
+        match self {
+            Self::Metric => format!("{:.*} m/s", precision, meters_per_second),
+            Self::Imperial => format!(
+                "{:.*} mph",
+                precision,
+                meters_per_second * MILES_PER_HOUR_PER_METER_PER_SECOND
+            ),
+        }
+    }
+
+    // ascent rates, given in meters per second
+    pub fn vertical_speed(&self, meters_per_second: f64, precision: usize) -> String {
+        match self {
+            Self::Metric => format!("{:.*} m/s", precision, meters_per_second),
+            Self::Imperial => format!("{:.*} ft/s", precision, meters_per_second * FEET_PER_METER),
+        }
+    }
 }
 
 fn default_interval() -> chrono::Duration {
@@ -46,6 +520,325 @@ impl Default for TimeConfiguration {
     }
 }
 
+fn default_maximum_voltage_decline_rate() -> f64 {
+    0.5
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct BatteryConfiguration {
+    /// warn when battery voltage drops below this value (V)
+    pub minimum_voltage: Option<f64>,
+    /// warn when battery voltage declines faster than this rate (V/h)
+    #[serde(default = "default_maximum_voltage_decline_rate")]
+    pub maximum_decline_rate: f64,
+}
+
+impl Default for BatteryConfiguration {
+    fn default() -> Self {
+        Self {
+            minimum_voltage: None,
+            maximum_decline_rate: default_maximum_voltage_decline_rate(),
+        }
+    }
+}
+
+fn default_maximum_ground_speed() -> f64 {
+    150.0
+}
+
+fn default_maximum_ascent_rate() -> f64 {
+    60.0
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct OutlierConfiguration {
+    /// flag a location as an outlier when the ground speed to its neighbors exceeds this value (m/s)
+    #[serde(default = "default_maximum_ground_speed")]
+    pub maximum_ground_speed: f64,
+    /// flag a location as an outlier when the vertical speed to its neighbors exceeds this value (m/s)
+    #[serde(default = "default_maximum_ascent_rate")]
+    pub maximum_ascent_rate: f64,
+}
+
+impl Default for OutlierConfiguration {
+    fn default() -> Self {
+        Self {
+            maximum_ground_speed: default_maximum_ground_speed(),
+            maximum_ascent_rate: default_maximum_ascent_rate(),
+        }
+    }
+}
+
+fn default_duplicate_coordinate_precision() -> u8 {
+    4
+}
+
+fn default_duplicate_altitude_tolerance() -> f64 {
+    0.0001
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct DuplicateConfiguration {
+    /// number of decimal places (of degrees) to which coordinates must match; 4 decimal places is about 11 m
+    #[serde(default = "default_duplicate_coordinate_precision")]
+    pub coordinate_precision: u8,
+    /// maximum difference in altitude (m) between duplicate locations
+    #[serde(default = "default_duplicate_altitude_tolerance")]
+    pub altitude_tolerance: f64,
+}
+
+impl Default for DuplicateConfiguration {
+    fn default() -> Self {
+        Self {
+            coordinate_precision: default_duplicate_coordinate_precision(),
+            altitude_tolerance: default_duplicate_altitude_tolerance(),
+        }
+    }
+}
+
+fn default_smoothing_window() -> usize {
+    3
+}
+
+fn default_ascent_rate_threshold() -> f64 {
+    0.2
+}
+
+fn default_ascent_rate_samples() -> usize {
+    2
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct AscentRateConfiguration {
+    /// number of consecutive ascent rates to average when charting and detecting ascent / descent; `1` disables smoothing
+    #[serde(default = "default_smoothing_window")]
+    pub smoothing_window: usize,
+    /// ascent rate (m/s) above which the balloon is ascending, and below the negative of which it is descending
+    #[serde(default = "default_ascent_rate_threshold")]
+    pub threshold: f64,
+    /// number of latest ascent rates that must exceed the threshold
+    #[serde(default = "default_ascent_rate_samples")]
+    pub samples: usize,
+}
+
+impl Default for AscentRateConfiguration {
+    fn default() -> Self {
+        Self {
+            smoothing_window: default_smoothing_window(),
+            threshold: default_ascent_rate_threshold(),
+            samples: default_ascent_rate_samples(),
+        }
+    }
+}
+
+fn default_landing_maximum_altitude() -> f64 {
+    2000.0
+}
+
+fn default_landing_maximum_ground_speed() -> f64 {
+    1.0
+}
+
+fn default_landing_maximum_ascent_rate() -> f64 {
+    0.5
+}
+
+fn default_landing_samples() -> usize {
+    3
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct LandingConfiguration {
+    /// altitude (m) below which the balloon may have landed
+    #[serde(default = "default_landing_maximum_altitude")]
+    pub maximum_altitude: f64,
+    /// ground speed (m/s) below which the balloon is stationary
+    #[serde(default = "default_landing_maximum_ground_speed")]
+    pub maximum_ground_speed: f64,
+    /// vertical speed (m/s), ascending or descending, below which the balloon is stationary
+    #[serde(default = "default_landing_maximum_ascent_rate")]
+    pub maximum_ascent_rate: f64,
+    /// number of latest locations that must be stationary
+    #[serde(default = "default_landing_samples")]
+    pub samples: usize,
+    /// CSV file to which to append the predicted and actual landing of each balloon once it lands
+    pub error_file: Option<std::path::PathBuf>,
+}
+
+impl Default for LandingConfiguration {
+    fn default() -> Self {
+        Self {
+            maximum_altitude: default_landing_maximum_altitude(),
+            maximum_ground_speed: default_landing_maximum_ground_speed(),
+            maximum_ascent_rate: default_landing_maximum_ascent_rate(),
+            samples: default_landing_samples(),
+            error_file: None,
+        }
+    }
+}
+
+fn default_float_minimum_altitude() -> f64 {
+    5000.0
+}
+
+fn default_float_samples() -> usize {
+    3
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct FloatConfiguration {
+    /// altitude (m) above which a level balloon is floating rather than on the ground
+    #[serde(default = "default_float_minimum_altitude")]
+    pub minimum_altitude: f64,
+    /// number of latest locations that must stay level
+    #[serde(default = "default_float_samples")]
+    pub samples: usize,
+}
+
+impl Default for FloatConfiguration {
+    fn default() -> Self {
+        Self {
+            minimum_altitude: default_float_minimum_altitude(),
+            samples: default_float_samples(),
+        }
+    }
+}
+
+fn default_alerts_enabled() -> bool {
+    true
+}
+
+fn default_alert_time_to_ground() -> chrono::Duration {
+    chrono::Duration::minutes(5)
+}
+
+#[serde_with::serde_as]
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct AlertConfiguration {
+    /// whether to alert when a balloon starts descending and when it nears landing
+    #[serde(default = "default_alerts_enabled")]
+    pub enabled: bool,
+    /// whether to ring the terminal bell with each alert
+    #[serde(default = "default_alerts_enabled")]
+    pub bell: bool,
+    /// alert when the estimated time to ground drops below this duration
+    #[serde(default = "default_alert_time_to_ground")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub time_to_ground: chrono::Duration,
+}
+
+impl Default for AlertConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: default_alerts_enabled(),
+            bell: default_alerts_enabled(),
+            time_to_ground: default_alert_time_to_ground(),
+        }
+    }
+}
+
+fn default_resampled_output_interval() -> chrono::Duration {
+    chrono::Duration::seconds(10)
+}
+
+/// CSV file of every track, linearly interpolated to a regular interval
+#[serde_with::serde_as]
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct ResampledOutputConfiguration {
+    pub path: std::path::PathBuf,
+    #[serde(default = "default_resampled_output_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub interval: chrono::Duration,
+}
+
+fn default_kalman_position_noise() -> f64 {
+    15.0
+}
+
+fn default_kalman_altitude_noise() -> f64 {
+    10.0
+}
+
+fn default_kalman_acceleration_noise() -> f64 {
+    0.05
+}
+
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct KalmanConfiguration {
+    /// whether to smooth the track with a Kalman filter, for velocities and the prediction start location
+    #[serde(default)]
+    pub enabled: bool,
+    /// standard deviation (m) of reported horizontal positions
+    #[serde(default = "default_kalman_position_noise")]
+    pub position_noise: f64,
+    /// standard deviation (m) of reported altitudes
+    #[serde(default = "default_kalman_altitude_noise")]
+    pub altitude_noise: f64,
+    /// standard deviation (m/s²) of changes in velocity between packets
+    #[serde(default = "default_kalman_acceleration_noise")]
+    pub acceleration_noise: f64,
+}
+
+impl Default for KalmanConfiguration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            position_noise: default_kalman_position_noise(),
+            altitude_noise: default_kalman_altitude_noise(),
+            acceleration_noise: default_kalman_acceleration_noise(),
+        }
+    }
+}
+
+fn default_connectivity_address() -> String {
+    String::from("api.v2.sondehub.org:443")
+}
+
+fn default_connectivity_timeout() -> chrono::Duration {
+    chrono::Duration::seconds(2)
+}
+
+fn default_request_timeout() -> chrono::Duration {
+    chrono::Duration::seconds(10)
+}
+
+/// settings for every outgoing HTTP request (to APRS.fi, SondeHub, the prediction API, and remote files)
+#[serde_with::serde_as]
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct HttpConfiguration {
+    /// seconds to wait for a response before giving up on a request
+    #[serde(default = "default_request_timeout")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub timeout: chrono::Duration,
+    /// `User-Agent` header, in place of `packetraven/{version}`
+    pub user_agent: Option<String>,
+    /// URL of a proxy through which to send every request, i.e. `http://proxy.example.edu:3128`;
+    /// otherwise, `HTTP_PROXY` and `HTTPS_PROXY` are used if set
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpConfiguration {
+    fn default() -> Self {
+        Self {
+            timeout: default_request_timeout(),
+            user_agent: None,
+            proxy: None,
+        }
+    }
+}
+
+/// if present, skip online connections and predictions while the network is unreachable
+#[serde_with::serde_as]
+#[derive(PartialEq, Debug, serde::Deserialize, Clone, serde::Serialize)]
+pub struct ConnectivityConfiguration {
+    /// `host:port` to which to open a TCP connection when checking connectivity
+    #[serde(default = "default_connectivity_address")]
+    pub address: String,
+    #[serde(default = "default_connectivity_timeout")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub timeout: chrono::Duration,
+}
+
 #[derive(Default, serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
 pub struct ConnectionConfiguration {
     pub text: Option<Vec<crate::connection::text::TextStream>>,
@@ -54,8 +847,19 @@ pub struct ConnectionConfiguration {
     pub sondehub: Option<crate::connection::sondehub::SondeHubQuery>,
     #[cfg(feature = "aprsfi")]
     pub aprs_fi: Option<crate::connection::aprs_fi::AprsFiQuery>,
+    #[cfg(feature = "aprsis")]
+    pub aprs_is: Option<crate::connection::aprs_is::AprsIsStream>,
+    /// vessels to follow over AIS
+    #[cfg(feature = "ais")]
+    pub ais: Option<crate::connection::ais::AisStream>,
     #[cfg(feature = "postgres")]
     pub database: Option<crate::connection::postgres::DatabaseCredentials>,
+    /// broker to which to publish new locations
+    #[cfg(feature = "mqtt")]
+    pub mqtt: Option<crate::connection::mqtt::MqttPublisher>,
+    /// bucket to which to write new locations
+    #[cfg(feature = "influxdb")]
+    pub influxdb: Option<crate::connection::influxdb::InfluxDbOutput>,
 }
 
 #[cfg(test)]
@@ -63,6 +867,235 @@ mod tests {
     use super::*;
     use chrono::offset::TimeZone;
 
+    #[test]
+    fn test_units() {
+        assert_eq!(Units::Metric.length(1000.0, 2), "1000.00 m");
+        assert_eq!(Units::Imperial.length(1000.0, 0), "3281 ft");
+        assert_eq!(Units::Metric.distance(1500.0, 1), "1.5 km");
+        assert_eq!(Units::Imperial.distance(1609.344, 2), "1.00 mi");
+        assert_eq!(Units::Metric.speed(10.0, 1), "10.0 m/s");
+        assert_eq!(Units::Imperial.speed(10.0, 1), "22.4 mph");
+        assert_eq!(Units::Imperial.vertical_speed(-5.0, 1), "-16.4 ft/s");
+
+        let configuration: RunConfiguration = serde_yaml::from_str("units: imperial").unwrap();
+        assert_eq!(configuration.units, Units::Imperial);
+        let configuration: RunConfiguration = serde_yaml::from_str("name: test").unwrap();
+        assert_eq!(configuration.units, Units::Metric);
+    }
+
+    #[test]
+    fn test_timezone() {
+        let configuration: RunConfiguration =
+            serde_yaml::from_str("timezone: America/New_York").unwrap();
+        assert_eq!(configuration.timezone, Some(chrono_tz::America::New_York));
+
+        let time = chrono::Utc
+            .with_ymd_and_hms(2023, 8, 16, 14, 0, 0)
+            .unwrap()
+            .with_timezone(&chrono::Local);
+        assert_eq!(
+            crate::utilities::display_time(&time, configuration.timezone, "%H:%M %Z"),
+            "10:00 EDT"
+        );
+        assert_eq!(
+            crate::utilities::display_time(&time, Some(chrono_tz::UTC), "%H:%M %Z"),
+            "14:00 UTC"
+        );
+
+        assert!(serde_yaml::from_str::<RunConfiguration>("timezone: Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_ground_station() {
+        let configuration: RunConfiguration = serde_yaml::from_str(
+            r#"
+ground_station:
+  coord:
+    x: -76.9383
+    y: 38.9869
+"#,
+        )
+        .unwrap();
+        let ground_station = configuration.ground_station.unwrap();
+        assert_eq!(
+            ground_station.coord,
+            geo::coord! { x: -76.9383, y: 38.9869 }
+        );
+        assert_eq!(ground_station.altitude, None);
+
+        let configuration: RunConfiguration = serde_yaml::from_str("name: test").unwrap();
+        assert!(configuration.ground_station.is_none());
+
+        // other locations still require a time
+        assert!(serde_yaml::from_str::<crate::location::Location>(
+            "coord:\n  x: -76.9383\n  y: 38.9869"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_environment_variables() {
+        let variable = |name: &str| match name {
+            "APRS_FI_API_KEY" => Some(String::from("123456.abcdef")),
+            "FLIGHT" => Some(String::from("NS-111")),
+            _ => None,
+        };
+
+        let mut value: serde_yaml::Value = serde_yaml::from_str(
+            r#"
+name: ${FLIGHT}_chase
+callsigns:
+  - W3EAX-8
+connections:
+  aprs_fi:
+    api_key: ${APRS_FI_API_KEY}
+"#,
+        )
+        .unwrap();
+        substitute_environment_variables(&mut value, &variable).unwrap();
+        let configuration: RunConfiguration = serde_yaml::from_value(value).unwrap();
+        assert_eq!(configuration.name, "NS-111_chase");
+        #[cfg(feature = "aprsfi")]
+        assert_eq!(
+            configuration.connections.aprs_fi.unwrap().api_key,
+            "123456.abcdef"
+        );
+
+        let mut value: serde_yaml::Value = serde_yaml::from_str("name: ${UNSET_VARIABLE}").unwrap();
+        assert_eq!(
+            substitute_environment_variables(&mut value, &variable)
+                .unwrap_err()
+                .to_string(),
+            "environment variable `UNSET_VARIABLE` referenced in configuration is not set"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let configuration: RunConfiguration = serde_yaml::from_str(&format!(
+            r#"
+connections:
+  text:
+    - path: {:}/data/aprs/W3EAX-8_raw_NS-111.txt
+    - path: http://bpp.umd.edu/archives/Launches/NS-111_2022_07_31/APRS/W3EAX-8%20raw.txt
+"#,
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        assert_eq!(configuration.validate(), Vec::<String>::new());
+
+        let configuration: RunConfiguration = serde_yaml::from_str(
+            r#"
+time:
+  start: 2022-03-06 00:00:00
+  end: 2022-03-05 00:00:00
+connections:
+  text:
+    - path: nonexistent.txt
+"#,
+        )
+        .unwrap();
+        let errors = configuration.validate();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("time.start"));
+        assert!(errors[1].starts_with("connections.text[0]: cannot read nonexistent.txt"));
+
+        let configuration: RunConfiguration = serde_yaml::from_str(&format!(
+            r#"
+connections:
+  text:
+    - path: {:}/data/aprs/W3EAX-8_raw_NS-111.txt
+      interval: -5
+    - path: {:}/data/aprs/W3EAX-8_raw_NS-111.txt
+      interval: 0
+"#,
+            env!("CARGO_MANIFEST_DIR"),
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        assert_eq!(
+            configuration.validate(),
+            vec![
+                "connections.text[0].interval must be positive",
+                "connections.text[1].interval must be positive"
+            ]
+        );
+
+        let configuration: RunConfiguration = serde_yaml::from_str(
+            r#"
+callsigns:
+  - W3EAX-*
+connections:
+  aprs_fi:
+    api_key: 123456.abcdefhijklmnop
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            configuration.validate(),
+            vec!["connections.aprs_fi does not support callsign wildcards; list each callsign"]
+        );
+
+        let configuration: RunConfiguration = serde_yaml::from_str(
+            r#"
+http:
+  timeout: -5
+  user_agent: "balloon\ttracker\n"
+  proxy: not a proxy
+connectivity:
+  timeout: 0
+"#,
+        )
+        .unwrap();
+        let errors = configuration.validate();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0], "http.timeout must be positive");
+        assert_eq!(errors[1], "connectivity.timeout must be positive");
+        assert!(errors[2].starts_with("http.user_agent is not a valid header"));
+        assert!(errors[3].starts_with("http.proxy is not a valid URL"));
+    }
+
+    #[test]
+    fn test_annotated_template() {
+        let template = annotated_template();
+
+        // still valid YAML, with the default values
+        let configuration: RunConfiguration = serde_yaml::from_str(&template).unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&configuration).unwrap(),
+            serde_yaml::to_string(&RunConfiguration::default()).unwrap()
+        );
+
+        // every section is described
+        let defaults: serde_yaml::Mapping =
+            serde_yaml::from_value(serde_yaml::to_value(RunConfiguration::default()).unwrap())
+                .unwrap();
+        for key in defaults.keys() {
+            let key = key.as_str().unwrap();
+            assert!(
+                TEMPLATE_DESCRIPTIONS.iter().any(|(name, _)| *name == key),
+                "no description of `{:}` in the template",
+                key
+            );
+        }
+
+        // each example parses once uncommented
+        for (_, example) in TEMPLATE_EXAMPLES {
+            serde_yaml::from_str::<RunConfiguration>(example).unwrap();
+        }
+        assert!(template.contains("\n#   aprs_fi:\n#     api_key: 123456.abcdefhijklmnop\n"));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        match read_configuration(std::path::Path::new("nonexistent.yaml")) {
+            Err(error) => assert!(error
+                .to_string()
+                .starts_with("could not open config file nonexistent.yaml: ")),
+            Ok(_) => panic!(),
+        }
+    }
+
     #[test]
     fn test_example_1() {
         let path = format!(
@@ -102,17 +1135,31 @@ mod tests {
                     String::from("123456.abcdefhijklmnop"),
                     None,
                 )),
+                #[cfg(feature = "aprsis")]
+                aprs_is: None,
+                #[cfg(feature = "ais")]
+                ais: None,
                 #[cfg(feature = "sondehub")]
                 sondehub: Some(crate::connection::sondehub::SondeHubQuery::default()),
                 text: Some(vec![crate::connection::text::TextStream::AprsSerial(
                     crate::connection::text::serial::AprsSerial {
                         port: "COM3".to_string(),
-                        baud_rate: 9600,
-                        callsigns: None
+                        baud_rate: crate::connection::text::serial::BaudRate::Fixed(9600),
+                        detected_baud_rate: None,
+                        mode: crate::connection::text::serial::SerialMode::Text,
+                        callsigns: None,
+                        schedule: Default::default(),
+                        beacon: None,
+                        last_beacon: None,
+                        reader: Default::default(),
                     }
                 )]),
                 #[cfg(feature = "postgres")]
                 database: None,
+                #[cfg(feature = "mqtt")]
+                mqtt: None,
+                #[cfg(feature = "influxdb")]
+                influxdb: None,
             }
         );
     }
@@ -195,10 +1242,14 @@ mod tests {
                         ascent_rate: 6.5,
                         burst_altitude: 25000.0,
                         sea_level_descent_rate: 9.0,
+                        descent_model: None,
                         descent_only: false,
                     },
                     float: None,
                     api_url: None,
+                    fallback_api_url: None,
+                    coordinate_precision: None,
+                    landing_zone: false,
                     output_file: Some(std::path::PathBuf::from(
                         "example_3_prediction.geojson".to_string()
                     ))
@@ -1,13 +1,110 @@
 use serde_with::serde_as;
 
+lazy_static::lazy_static! {
+    // relative uncertainty of the ascent and descent rates when predicting a landing zone
+    static ref RATE_UNCERTAINTY: f64 = 0.1;
+}
+
 #[derive(serde::Deserialize, Clone, serde::Serialize)]
 #[serde(untagged)]
 pub enum PredictionConfiguration {
     Single(Prediction),
     Cloud {
         default: Prediction,
-        perturbations: std::collections::HashMap<String, Prediction>,
+        /// ensemble members, each varying the profile of the default prediction
+        perturbations: Vec<Perturbation>,
     },
+    /// named profiles predicted side by side; the first is the default
+    Profiles(#[serde(deserialize_with = "non_empty")] Vec<Prediction>),
+}
+
+fn non_empty<'de, D>(deserializer: D) -> Result<Vec<Prediction>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let profiles: Vec<Prediction> = serde::Deserialize::deserialize(deserializer)?;
+    if profiles.is_empty() {
+        Err(serde::de::Error::custom("expected at least one prediction"))
+    } else {
+        Ok(profiles)
+    }
+}
+
+impl PredictionConfiguration {
+    // the prediction to display and write to file, along with any perturbed ensemble members
+    pub fn predictions(&self) -> (&Prediction, Vec<Prediction>) {
+        match self {
+            Self::Single(prediction) => (prediction, prediction.landing_zone_perturbations()),
+            Self::Cloud {
+                default,
+                perturbations,
+            } => (
+                default,
+                perturbations
+                    .iter()
+                    .enumerate()
+                    .map(|(index, perturbation)| default.perturbed(perturbation, index))
+                    .collect(),
+            ),
+            Self::Profiles(profiles) => (&profiles[0], profiles[0].landing_zone_perturbations()),
+        }
+    }
+
+    // additional named profiles, predicted alongside the default
+    pub fn profiles(&self) -> &[Prediction] {
+        match self {
+            Self::Profiles(profiles) => &profiles[1..],
+            _ => &[],
+        }
+    }
+
+    // problems with the profile of every configured prediction
+    pub fn validate(&self) -> Vec<String> {
+        match self {
+            Self::Single(prediction) => prediction.profile.validate("prediction.profile"),
+            Self::Cloud {
+                default,
+                perturbations,
+            } => {
+                let mut errors = default.profile.validate("prediction.default.profile");
+                for (index, perturbation) in perturbations.iter().enumerate() {
+                    for (name, value) in [
+                        ("ascent_rate", perturbation.ascent_rate),
+                        ("burst_altitude", perturbation.burst_altitude),
+                        (
+                            "sea_level_descent_rate",
+                            perturbation.sea_level_descent_rate,
+                        ),
+                    ] {
+                        if value.is_some_and(|value| value <= 0.0) {
+                            errors.push(format!(
+                                "prediction.perturbations[{:}].{:} must be positive",
+                                index, name
+                            ));
+                        }
+                    }
+                }
+                errors
+            }
+            Self::Profiles(profiles) => profiles
+                .iter()
+                .enumerate()
+                .flat_map(|(index, prediction)| {
+                    prediction
+                        .profile
+                        .validate(&format!("prediction[{:}].profile", index))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn default_prediction_mut(&mut self) -> &mut Prediction {
+        match self {
+            Self::Single(prediction) => prediction,
+            Self::Cloud { default, .. } => default,
+            Self::Profiles(profiles) => &mut profiles[0],
+        }
+    }
 }
 
 fn default_name() -> String {
@@ -21,41 +118,131 @@ pub struct Prediction {
     pub float: Option<FloatProfile>,
     pub output_file: Option<std::path::PathBuf>,
     pub api_url: Option<String>,
+    /// Tawhiri-compatible API to query when `api_url` returns an error
+    pub fallback_api_url: Option<String>,
+    /// number of decimal places of the start coordinates sent to the prediction API (descent-only predictions use at least 6)
+    pub coordinate_precision: Option<usize>,
+    /// also predict with perturbed profiles, to show the area in which the balloon is likely to land
+    #[serde(default)]
+    pub landing_zone: bool,
     #[serde(default = "default_name")]
     pub name: String,
 }
 
 impl Prediction {
-    pub fn to_tawhiri_query(&self) -> crate::prediction::tawhiri::TawhiriQuery {
-        let profile = match &self.float {
+    // copy of this prediction with the given profile values replaced
+    pub fn perturbed(&self, perturbation: &Perturbation, index: usize) -> Self {
+        let mut perturbed = self.to_owned();
+        perturbed.name = perturbation
+            .name
+            .to_owned()
+            .unwrap_or_else(|| format!("{:}_{:}", self.name, index + 1));
+        perturbed.output_file = None;
+        if let Some(ascent_rate) = perturbation.ascent_rate {
+            perturbed.profile.ascent_rate = ascent_rate;
+        }
+        if let Some(burst_altitude) = perturbation.burst_altitude {
+            perturbed.profile.burst_altitude = burst_altitude;
+        }
+        if let Some(sea_level_descent_rate) = perturbation.sea_level_descent_rate {
+            perturbed.profile.sea_level_descent_rate = sea_level_descent_rate;
+            perturbed.profile.descent_model = None;
+        }
+        perturbed
+    }
+
+    pub fn flight_profile(&self) -> crate::prediction::FlightProfile {
+        match &self.float {
             Some(float) => crate::prediction::FlightProfile::new_float(
                 self.profile.ascent_rate,
                 Some(float.altitude),
                 float.duration,
                 float.uncertainty,
                 self.profile.burst_altitude,
-                self.profile.sea_level_descent_rate,
+                self.profile.descent_rate(),
             ),
             None => crate::prediction::FlightProfile::new_standard(
                 self.profile.ascent_rate,
                 self.profile.burst_altitude,
-                self.profile.sea_level_descent_rate,
+                self.profile.descent_rate(),
             ),
-        };
+        }
+    }
+
+    // the uncertainty perturbations, if a landing zone was requested
+    fn landing_zone_perturbations(&self) -> Vec<Self> {
+        if self.landing_zone {
+            self.uncertainty_perturbations()
+        } else {
+            vec![]
+        }
+    }
+
+    // predictions at the edges of the expected uncertainty of the burst altitude and ascent / descent rates
+    pub fn uncertainty_perturbations(&self) -> Vec<Self> {
+        let burst_uncertainty = self.flight_profile().float_uncertainty;
+        let rate_uncertainty = *RATE_UNCERTAINTY;
 
-        crate::prediction::tawhiri::TawhiriQuery::new(
+        [
+            ("high_burst", None, Some(burst_uncertainty), None),
+            ("low_burst", None, Some(-burst_uncertainty), None),
+            ("fast_ascent", Some(rate_uncertainty), None, None),
+            ("slow_ascent", Some(-rate_uncertainty), None, None),
+            ("fast_descent", None, None, Some(rate_uncertainty)),
+            ("slow_descent", None, None, Some(-rate_uncertainty)),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(index, (name, ascent, burst, descent))| {
+            self.perturbed(
+                &Perturbation {
+                    name: Some(format!("{:}_{:}", self.name, name)),
+                    ascent_rate: ascent.map(|change| self.profile.ascent_rate * (1.0 + change)),
+                    burst_altitude: burst.map(|change| self.profile.burst_altitude + change),
+                    sea_level_descent_rate: descent
+                        .map(|change| self.profile.descent_rate() * (1.0 + change)),
+                },
+                index,
+            )
+        })
+        .collect()
+    }
+
+    pub fn to_tawhiri_query(&self) -> crate::prediction::tawhiri::TawhiriQuery {
+        let mut query = crate::prediction::tawhiri::TawhiriQuery::new(
             &self.start,
-            &profile,
+            &self.flight_profile(),
             None,
             None,
             None,
             false,
             None,
-        )
+        );
+        if let Some(api_url) = &self.api_url {
+            query.query.api_url = api_url.to_owned();
+        }
+        if let Some(coordinate_precision) = self.coordinate_precision {
+            query.coordinate_precision = coordinate_precision;
+        }
+
+        query
     }
 }
 
-fn default_sea_level_descent_rate() -> f64 {
+/// profile values that differ from the default prediction
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
+pub struct Perturbation {
+    pub name: Option<String>,
+    pub ascent_rate: Option<f64>,
+    pub burst_altitude: Option<f64>,
+    pub sea_level_descent_rate: Option<f64>,
+}
+
+lazy_static::lazy_static! {
+    static ref DEFAULT_DRAG_COEFFICIENT: f64 = 1.5;
+}
+
+pub fn default_sea_level_descent_rate() -> f64 {
     -crate::model::FreefallEstimate::new(0.0).ascent_rate
 }
 
@@ -63,16 +250,149 @@ fn default_descent_only() -> bool {
     false
 }
 
+/// descent under parachute, which is faster in the thinner air at altitude
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum DescentModel {
+    /// mass of the falling payload divided by its drag coefficient and area (kg/m^2)
+    BallisticCoefficient { ballistic_coefficient: f64 },
+    /// descent rates observed at altitude, i.e. from a previous flight
+    Table { descent_rates: Vec<DescentRate> },
+    /// mass (kg) of the payload, and area (m^2) and drag coefficient of its parachute;
+    /// the drag coefficient defaults to that of a typical round parachute
+    Payload {
+        payload_mass: f64,
+        drag_area: f64,
+        drag_coefficient: Option<f64>,
+    },
+}
+
+#[derive(serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
+pub struct DescentRate {
+    pub altitude: f64,
+    pub descent_rate: f64,
+}
+
+impl DescentModel {
+    // terminal velocity from the drag of the payload, if described by its drag
+    pub fn terminal_velocity(&self) -> Option<crate::model::TerminalVelocity> {
+        match self {
+            Self::BallisticCoefficient {
+                ballistic_coefficient,
+            } => Some(crate::model::TerminalVelocity::from_ballistic_coefficient(
+                *ballistic_coefficient,
+            )),
+            Self::Payload {
+                payload_mass,
+                drag_area,
+                drag_coefficient,
+            } => Some(crate::model::TerminalVelocity::from_payload(
+                *payload_mass,
+                drag_coefficient.unwrap_or(*DEFAULT_DRAG_COEFFICIENT),
+                *drag_area,
+            )),
+            Self::Table { .. } => None,
+        }
+    }
+
+    // descent rate (m/s) at sea level, which is the only descent parameter that Tawhiri accepts
+    pub fn sea_level_descent_rate(&self) -> Option<f64> {
+        match self {
+            Self::BallisticCoefficient { .. } | Self::Payload { .. } => self
+                .terminal_velocity()
+                .map(|terminal_velocity| terminal_velocity.sea_level_descent_rate),
+            Self::Table { descent_rates } => {
+                if descent_rates.is_empty() {
+                    None
+                } else {
+                    Some(
+                        descent_rates
+                            .iter()
+                            .map(|entry| {
+                                crate::model::sea_level_equivalent(
+                                    entry.descent_rate.abs(),
+                                    entry.altitude,
+                                )
+                            })
+                            .sum::<f64>()
+                            / descent_rates.len() as f64,
+                    )
+                }
+            }
+        }
+    }
+}
+
 #[derive(serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
 pub struct StandardProfile {
     pub ascent_rate: f64,
     pub burst_altitude: f64,
     #[serde(default = "default_sea_level_descent_rate")]
     pub sea_level_descent_rate: f64,
+    /// takes precedence over `sea_level_descent_rate`
+    pub descent_model: Option<DescentModel>,
     #[serde(default = "default_descent_only")]
     pub descent_only: bool,
 }
 
+impl StandardProfile {
+    // sea-level descent rate from the descent model, if any, or as configured
+    pub fn descent_rate(&self) -> f64 {
+        self.descent_model
+            .as_ref()
+            .and_then(|model| model.sea_level_descent_rate())
+            .unwrap_or(self.sea_level_descent_rate)
+    }
+
+    // terminal velocity from the drag of the payload, if the descent model describes it
+    pub fn terminal_velocity(&self) -> Option<crate::model::TerminalVelocity> {
+        self.descent_model.as_ref()?.terminal_velocity()
+    }
+
+    // problems with the profile, named by the path of the profile in the configuration
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = vec![];
+        let mut values = vec![];
+        match &self.descent_model {
+            Some(DescentModel::BallisticCoefficient {
+                ballistic_coefficient,
+            }) => values.push((
+                String::from("ballistic_coefficient"),
+                *ballistic_coefficient,
+            )),
+            Some(DescentModel::Table { descent_rates }) => {
+                for (index, entry) in descent_rates.iter().enumerate() {
+                    values.push((
+                        format!("descent_rates[{:}].descent_rate", index),
+                        entry.descent_rate,
+                    ));
+                }
+            }
+            Some(DescentModel::Payload {
+                payload_mass,
+                drag_area,
+                drag_coefficient,
+            }) => {
+                values.push((String::from("payload_mass"), *payload_mass));
+                values.push((String::from("drag_area"), *drag_area));
+                if let Some(drag_coefficient) = drag_coefficient {
+                    values.push((String::from("drag_coefficient"), *drag_coefficient));
+                }
+            }
+            None => {}
+        }
+        for (name, value) in values {
+            if value <= 0.0 {
+                errors.push(format!(
+                    "{:}.descent_model.{:} must be positive",
+                    path, name
+                ));
+            }
+        }
+        errors
+    }
+}
+
 #[serde_as]
 #[derive(serde::Deserialize, PartialEq, Debug, Clone, serde::Serialize)]
 pub struct FloatProfile {
@@ -81,3 +401,252 @@ pub struct FloatProfile {
     #[serde_as(as = "serde_with::DurationSeconds<i64>")]
     pub duration: chrono::Duration,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud() {
+        let data = r#"
+        default:
+          start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 6.5
+            burst_altitude: 25000
+            sea_level_descent_rate: 9
+          output_file: prediction.geojson
+        perturbations:
+          - name: early_burst
+            burst_altitude: 22000
+          - ascent_rate: 5.5
+            sea_level_descent_rate: 7
+        "#;
+        let configuration: PredictionConfiguration = serde_yaml::from_str(data).unwrap();
+
+        let (default, perturbations) = configuration.predictions();
+        assert_eq!(default.profile.burst_altitude, 25000.0);
+        assert_eq!(perturbations.len(), 2);
+
+        assert_eq!(perturbations[0].name, "early_burst");
+        assert_eq!(perturbations[0].profile.burst_altitude, 22000.0);
+        assert_eq!(perturbations[0].profile.ascent_rate, 6.5);
+        assert_eq!(perturbations[0].output_file, None);
+
+        assert_eq!(perturbations[1].name, "prediction_2");
+        assert_eq!(perturbations[1].profile.ascent_rate, 5.5);
+        assert_eq!(perturbations[1].profile.sea_level_descent_rate, 7.0);
+        assert_eq!(perturbations[1].profile.burst_altitude, 25000.0);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let data = r#"
+        - name: nominal
+          start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 6.5
+            burst_altitude: 25000
+            sea_level_descent_rate: 9
+          output_file: prediction.geojson
+        - name: heavy
+          start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 5
+            burst_altitude: 28000
+            sea_level_descent_rate: 12
+        "#;
+        let mut configuration: PredictionConfiguration = serde_yaml::from_str(data).unwrap();
+
+        let (default, perturbations) = configuration.predictions();
+        assert_eq!(default.name, "nominal");
+        assert!(perturbations.is_empty());
+
+        let profiles = configuration.profiles();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "heavy");
+        assert_eq!(profiles[0].profile.burst_altitude, 28000.0);
+
+        assert_eq!(configuration.default_prediction_mut().name, "nominal");
+
+        assert!(serde_yaml::from_str::<PredictionConfiguration>("[]").is_err());
+    }
+
+    #[test]
+    fn test_descent_model() {
+        let data = r#"
+        ascent_rate: 5
+        burst_altitude: 25000
+        descent_model:
+          ballistic_coefficient: 4
+        "#;
+        let profile: StandardProfile = serde_yaml::from_str(data).unwrap();
+        assert!((profile.descent_rate() - 8.0).abs() < 0.01);
+
+        let data = r#"
+        ascent_rate: 5
+        burst_altitude: 25000
+        sea_level_descent_rate: 9
+        descent_model:
+          descent_rates:
+            - altitude: 0
+              descent_rate: 6
+            - altitude: 11000
+              descent_rate: 22
+        "#;
+        let mut profile: StandardProfile = serde_yaml::from_str(data).unwrap();
+        // 22 m/s in the thin air at the tropopause is about 12 m/s at sea level
+        assert!((profile.descent_rate() - 9.0).abs() < 0.1);
+
+        profile.descent_model = Some(DescentModel::Table {
+            descent_rates: vec![],
+        });
+        assert_eq!(profile.descent_rate(), 9.0);
+
+        let data = r#"
+        ascent_rate: 5
+        burst_altitude: 25000
+        descent_model:
+          payload_mass: 1.5
+          drag_area: 0.25
+        "#;
+        let mut profile: StandardProfile = serde_yaml::from_str(data).unwrap();
+        assert!((profile.descent_rate() - 8.0).abs() < 0.01);
+        assert!(profile.terminal_velocity().is_some());
+        assert!(profile.validate("prediction.profile").is_empty());
+
+        profile.descent_model = Some(DescentModel::Payload {
+            payload_mass: 1.5,
+            drag_area: -0.25,
+            drag_coefficient: None,
+        });
+        assert_eq!(
+            profile.validate("prediction.profile"),
+            vec!["prediction.profile.descent_model.drag_area must be positive"]
+        );
+
+        profile.descent_model = Some(DescentModel::BallisticCoefficient {
+            ballistic_coefficient: 0.0,
+        });
+        assert_eq!(
+            profile.validate("prediction.profile"),
+            vec!["prediction.profile.descent_model.ballistic_coefficient must be positive"]
+        );
+        profile.descent_model = Some(DescentModel::Table {
+            descent_rates: vec![
+                DescentRate {
+                    altitude: 20000.0,
+                    descent_rate: 27.0,
+                },
+                DescentRate {
+                    altitude: 1000.0,
+                    descent_rate: -6.5,
+                },
+            ],
+        });
+        assert_eq!(
+            profile.validate("prediction.profile"),
+            vec!["prediction.profile.descent_model.descent_rates[1].descent_rate must be positive"]
+        );
+
+        // a payload without a parachute is not a descent model
+        assert!(serde_yaml::from_str::<DescentModel>("payload_mass: 1.5").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let data = r#"
+        - start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 5
+            burst_altitude: 25000
+        - start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 5
+            burst_altitude: 25000
+            descent_model:
+              payload_mass: 0
+              drag_area: 0.25
+          name: heavy
+        "#;
+        let configuration: PredictionConfiguration = serde_yaml::from_str(data).unwrap();
+        assert_eq!(
+            configuration.validate(),
+            vec!["prediction[1].profile.descent_model.payload_mass must be positive"]
+        );
+
+        let data = r#"
+        default:
+          start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 5
+            burst_altitude: 25000
+        perturbations:
+          - ascent_rate: 4
+          - burst_altitude: -1
+        "#;
+        let configuration: PredictionConfiguration = serde_yaml::from_str(data).unwrap();
+        assert_eq!(
+            configuration.validate(),
+            vec!["prediction.perturbations[1].burst_altitude must be positive"]
+        );
+    }
+
+    #[test]
+    fn test_landing_zone() {
+        let data = r#"
+          start:
+            coord:
+              x: -78.4987
+              y: 40.0157
+            time: 2022-03-05 10:36:00
+          profile:
+            ascent_rate: 5
+            burst_altitude: 25000
+            sea_level_descent_rate: 9
+          landing_zone: true
+        "#;
+        let configuration: PredictionConfiguration = serde_yaml::from_str(data).unwrap();
+
+        let (_, perturbations) = configuration.predictions();
+        let profiles: Vec<(f64, f64, f64)> = perturbations
+            .iter()
+            .map(|prediction| {
+                (
+                    prediction.profile.ascent_rate,
+                    prediction.profile.burst_altitude,
+                    prediction.profile.sea_level_descent_rate,
+                )
+            })
+            .collect();
+        assert!(profiles.contains(&(5.0, 25500.0, 9.0)));
+        assert!(profiles.contains(&(5.0, 24500.0, 9.0)));
+        assert!(profiles.contains(&(5.5, 25000.0, 9.0)));
+        assert!(profiles.contains(&(4.5, 25000.0, 9.0)));
+        assert_eq!(perturbations.len(), 6);
+    }
+}
@@ -0,0 +1,270 @@
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Default,
+    /// brighter colors, for use in daylight
+    HighContrast,
+    /// shades of gray only
+    Mono,
+}
+
+/// color of each role in the user interface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub tab: Color,
+    pub track_tab: Color,
+    pub title: Color,
+    pub axis: Color,
+    pub telemetry: Color,
+    pub telemetry_secondary: Color,
+    pub prediction: Color,
+    pub prediction_secondary: Color,
+    pub filtered: Color,
+    pub landing: Color,
+    pub landing_zone: Color,
+    /// colors of additional prediction profiles, in order
+    pub profiles: [Color; 4],
+    pub ground_station: Color,
+    pub info: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub success: Color,
+    pub alert_text: Color,
+    pub alert_background: Color,
+}
+
+impl ThemePreset {
+    pub fn palette(&self) -> Palette {
+        match self {
+            ThemePreset::Default => Palette {
+                tab: Color::Cyan,
+                track_tab: Color::Green,
+                title: Color::Cyan,
+                axis: Color::DarkGray,
+                telemetry: Color::Blue,
+                telemetry_secondary: Color::Yellow,
+                prediction: Color::Red,
+                prediction_secondary: Color::LightRed,
+                filtered: Color::Green,
+                landing: Color::Magenta,
+                landing_zone: Color::LightMagenta,
+                profiles: [
+                    Color::Yellow,
+                    Color::Magenta,
+                    Color::Cyan,
+                    Color::LightGreen,
+                ],
+                ground_station: Color::Yellow,
+                info: Color::Blue,
+                warning: Color::Yellow,
+                error: Color::Red,
+                success: Color::Green,
+                alert_text: Color::White,
+                alert_background: Color::Red,
+            },
+            ThemePreset::HighContrast => Palette {
+                tab: Color::White,
+                track_tab: Color::LightGreen,
+                title: Color::White,
+                axis: Color::Gray,
+                telemetry: Color::LightCyan,
+                telemetry_secondary: Color::LightYellow,
+                prediction: Color::LightRed,
+                prediction_secondary: Color::LightMagenta,
+                filtered: Color::LightGreen,
+                landing: Color::LightMagenta,
+                landing_zone: Color::LightMagenta,
+                profiles: [
+                    Color::LightYellow,
+                    Color::LightMagenta,
+                    Color::LightCyan,
+                    Color::White,
+                ],
+                ground_station: Color::LightYellow,
+                info: Color::LightCyan,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                success: Color::LightGreen,
+                alert_text: Color::Black,
+                alert_background: Color::LightYellow,
+            },
+            ThemePreset::Mono => Palette {
+                tab: Color::Reset,
+                track_tab: Color::Reset,
+                title: Color::Reset,
+                axis: Color::DarkGray,
+                telemetry: Color::White,
+                telemetry_secondary: Color::Gray,
+                prediction: Color::Gray,
+                prediction_secondary: Color::DarkGray,
+                filtered: Color::White,
+                landing: Color::White,
+                landing_zone: Color::Gray,
+                profiles: [Color::Gray, Color::DarkGray, Color::White, Color::Gray],
+                ground_station: Color::White,
+                info: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                success: Color::Reset,
+                alert_text: Color::Black,
+                alert_background: Color::White,
+            },
+        }
+    }
+}
+
+/// colors of the user interface, from a preset with any of its roles overridden, i.e. `telemetry: lightblue`
+#[derive(PartialEq, Debug, Default, serde::Deserialize, Clone, serde::Serialize)]
+pub struct ThemeConfiguration {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    #[serde(default, flatten, with = "color_names")]
+    pub colors: std::collections::BTreeMap<String, Color>,
+}
+
+impl ThemeConfiguration {
+    pub fn palette(&self) -> Palette {
+        let mut palette = self.preset.palette();
+        for (role, color) in &self.colors {
+            if let Some(target) = palette.role_mut(role) {
+                *target = *color;
+            }
+        }
+        palette
+    }
+
+    // roles that do not exist in the palette
+    pub fn unknown_roles(&self) -> Vec<&str> {
+        let mut palette = self.preset.palette();
+        self.colors
+            .keys()
+            .filter(|role| palette.role_mut(role).is_none())
+            .map(|role| role.as_str())
+            .collect()
+    }
+}
+
+impl Palette {
+    // color of the additional prediction profile at the given index, cycling through the profile colors
+    pub fn profile(&self, index: usize) -> Color {
+        self.profiles[index % self.profiles.len()]
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "tab" => &mut self.tab,
+            "track_tab" => &mut self.track_tab,
+            "title" => &mut self.title,
+            "axis" => &mut self.axis,
+            "telemetry" => &mut self.telemetry,
+            "telemetry_secondary" => &mut self.telemetry_secondary,
+            "prediction" => &mut self.prediction,
+            "prediction_secondary" => &mut self.prediction_secondary,
+            "filtered" => &mut self.filtered,
+            "landing" => &mut self.landing,
+            "landing_zone" => &mut self.landing_zone,
+            "profile_1" => &mut self.profiles[0],
+            "profile_2" => &mut self.profiles[1],
+            "profile_3" => &mut self.profiles[2],
+            "profile_4" => &mut self.profiles[3],
+            "ground_station" => &mut self.ground_station,
+            "info" => &mut self.info,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "alert_text" => &mut self.alert_text,
+            "alert_background" => &mut self.alert_background,
+            _ => return None,
+        })
+    }
+}
+
+// a color name such as `lightblue`, an index from 0 to 255, or a hex code such as `#ff8000`
+pub fn color_name(color: &Color) -> String {
+    match color {
+        Color::Rgb(red, green, blue) => format!("#{:02x}{:02x}{:02x}", red, green, blue),
+        Color::Indexed(index) => index.to_string(),
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+mod color_names {
+    use serde::Deserialize;
+    use std::str::FromStr;
+
+    pub fn serialize<S>(
+        colors: &std::collections::BTreeMap<String, ratatui::style::Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(
+            colors
+                .iter()
+                .map(|(role, color)| (role, super::color_name(color))),
+        )
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<std::collections::BTreeMap<String, ratatui::style::Color>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let names: std::collections::BTreeMap<String, String> =
+            std::collections::BTreeMap::deserialize(deserializer)?;
+        names
+            .into_iter()
+            .map(|(role, name)| {
+                ratatui::style::Color::from_str(&name)
+                    .map(|color| (role, color))
+                    .map_err(|_| {
+                        serde::de::Error::custom(format!("unrecognized color \"{:}\"", name))
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_theme() {
+        let theme: ThemeConfiguration = serde_yaml::from_str(
+            r##"
+            preset: high-contrast
+            telemetry: blue
+            axis: "#ff8000"
+            "##,
+        )
+        .unwrap();
+
+        let palette = theme.palette();
+        assert_eq!(palette.telemetry, Color::Blue);
+        assert_eq!(palette.axis, Color::Rgb(255, 128, 0));
+        assert_eq!(palette.prediction, Color::LightRed);
+        assert!(theme.unknown_roles().is_empty());
+
+        assert_eq!(
+            ThemeConfiguration::default().palette(),
+            ThemePreset::Default.palette()
+        );
+
+        let theme: ThemeConfiguration = serde_yaml::from_str("telemetri: blue").unwrap();
+        assert_eq!(theme.unknown_roles(), vec!["telemetri"]);
+
+        assert!(serde_yaml::from_str::<ThemeConfiguration>("telemetry: bleu").is_err());
+        assert!(serde_yaml::from_str::<ThemeConfiguration>("preset: neon").is_err());
+
+        for color in [Color::LightRed, Color::Indexed(10), Color::Rgb(1, 2, 3)] {
+            assert_eq!(Color::from_str(&color_name(&color)).unwrap(), color);
+        }
+    }
+}
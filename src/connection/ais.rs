@@ -0,0 +1,428 @@
+lazy_static::lazy_static! {
+    // how often the reading thread checks whether it should stop
+    static ref READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+}
+
+// messages that report the position of a vessel
+const POSITION_MESSAGES: [&str; 3] = [
+    "PositionReport",
+    "StandardClassBPositionReport",
+    "ExtendedClassBPositionReport",
+];
+
+const KNOTS_TO_METERS_PER_SECOND: f64 = 0.514444;
+
+fn default_url() -> String {
+    String::from("wss://stream.aisstream.io/v0/stream")
+}
+
+fn default_bounding_box() -> [[f64; 2]; 2] {
+    [[-90.0, -180.0], [90.0, 180.0]]
+}
+
+// positions of the given vessels from https://aisstream.io, i.e. to follow a recovery vessel at sea
+// https://aisstream.io/documentation
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct AisStream {
+    pub api_key: String,
+    /// MMSI of each vessel to follow (up to 50)
+    pub mmsi: Vec<String>,
+    /// opposite corners of the area in which to follow vessels, as `[[latitude, longitude], [latitude, longitude]]`
+    #[serde(default = "default_bounding_box")]
+    pub bounding_box: [[f64; 2]; 2],
+    #[serde(default = "default_url")]
+    pub url: String,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+    #[serde(skip)]
+    session: Option<Session>,
+}
+
+type Received = Result<crate::location::BalloonLocation, crate::connection::ConnectionError>;
+
+// a subscription to the stream, read by its own thread so that waiting on the stream never holds up the app
+struct Session {
+    receiver: std::sync::mpsc::Receiver<Received>,
+    stopped: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl Session {
+    fn start(url: String, subscription: String) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let stopped = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let thread_stopped = stopped.clone();
+        std::thread::spawn(move || {
+            let connection = format!("AISStream {:}", url);
+            let mut socket = match subscribe(&url, &subscription) {
+                Ok(socket) => socket,
+                Err(message) => {
+                    let _ =
+                        sender.send(Err(crate::connection::ConnectionError::FailedToEstablish {
+                            connection,
+                            message,
+                        }));
+                    return;
+                }
+            };
+
+            let dropped = |message: String| {
+                Err(crate::connection::ConnectionError::ReadFailure {
+                    connection: connection.to_owned(),
+                    message,
+                })
+            };
+            while !thread_stopped.load(std::sync::atomic::Ordering::Relaxed) {
+                // pings are answered while reading
+                let message = match socket.read() {
+                    Ok(tungstenite::Message::Text(text)) => text.as_bytes().to_vec(),
+                    Ok(tungstenite::Message::Binary(bytes)) => bytes.to_vec(),
+                    Ok(tungstenite::Message::Close(_)) => {
+                        let _ = sender.send(dropped(String::from("server closed the connection")));
+                        return;
+                    }
+                    Ok(_) => continue,
+                    Err(tungstenite::Error::Io(error))
+                        if error.kind() == std::io::ErrorKind::WouldBlock
+                            || error.kind() == std::io::ErrorKind::TimedOut =>
+                    {
+                        continue;
+                    }
+                    Err(error) => {
+                        let _ = sender.send(dropped(error.to_string()));
+                        return;
+                    }
+                };
+
+                let received = match serde_json::from_slice::<AisStreamMessage>(&message) {
+                    Ok(AisStreamMessage::Error { error }) => {
+                        let _ = sender.send(dropped(error));
+                        return;
+                    }
+                    Ok(AisStreamMessage::Vessel(message)) => message.to_balloon_location(),
+                    Err(_) => None,
+                };
+                if let Some(location) = received {
+                    if sender.send(Ok(location)).is_err() {
+                        break;
+                    }
+                }
+            }
+            let _ = socket.close(None);
+        });
+
+        Self { receiver, stopped }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.stopped
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+// open the WebSocket and send the subscription, which must arrive within a few seconds of connecting
+fn subscribe(
+    url: &str,
+    subscription: &str,
+) -> Result<tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>>, String>
+{
+    let (mut socket, _) = tungstenite::connect(url).map_err(|error| error.to_string())?;
+    socket
+        .send(tungstenite::Message::text(subscription))
+        .map_err(|error| error.to_string())?;
+
+    let stream = match socket.get_ref() {
+        tungstenite::stream::MaybeTlsStream::Plain(stream) => stream,
+        tungstenite::stream::MaybeTlsStream::NativeTls(stream) => stream.get_ref(),
+        _ => return Err(String::from("unsupported TLS stream")),
+    };
+    stream
+        .set_read_timeout(Some(*READ_TIMEOUT))
+        .map_err(|error| error.to_string())?;
+
+    Ok(socket)
+}
+
+impl AisStream {
+    pub fn new(api_key: String, mmsi: Vec<String>) -> Self {
+        Self {
+            api_key,
+            mmsi,
+            bounding_box: default_bounding_box(),
+            url: default_url(),
+            schedule: Default::default(),
+            session: None,
+        }
+    }
+
+    fn subscription(&self) -> String {
+        serde_json::json!({
+            "APIKey": self.api_key,
+            "BoundingBoxes": [self.bounding_box],
+            "FiltersShipMMSI": self.mmsi,
+            "FilterMessageTypes": POSITION_MESSAGES,
+        })
+        .to_string()
+    }
+
+    // positions received since the last read
+    pub fn read_locations_from_aisstream(
+        &mut self,
+    ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
+        let url = self.url.to_owned();
+        let subscription = self.subscription();
+        let session = self
+            .session
+            .get_or_insert_with(|| Session::start(url, subscription));
+
+        let mut locations = vec![];
+        let mut dropped = None;
+        loop {
+            match session.receiver.try_recv() {
+                Ok(Ok(location)) => locations.push(location),
+                Ok(Err(error)) => {
+                    dropped = Some(error);
+                    break;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    dropped = Some(crate::connection::ConnectionError::ReadFailure {
+                        connection: format!("AISStream {:}", self.url),
+                        message: String::from("connection closed"),
+                    });
+                    break;
+                }
+            }
+        }
+
+        if let Some(error) = dropped {
+            // reconnect on the next read
+            self.session = None;
+            if locations.is_empty() {
+                return Err(error);
+            }
+        }
+
+        Ok(locations)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum AisStreamMessage {
+    Error { error: String },
+    Vessel(AisStreamVessel),
+}
+
+#[derive(serde::Deserialize)]
+struct AisStreamVessel {
+    #[serde(rename = "MessageType")]
+    message_type: String,
+    #[serde(rename = "MetaData")]
+    metadata: AisStreamMetadata,
+    /// the report itself, keyed by the message type
+    #[serde(rename = "Message")]
+    message: std::collections::HashMap<String, AisStreamReport>,
+}
+
+#[derive(serde::Deserialize)]
+struct AisStreamMetadata {
+    #[serde(rename = "MMSI")]
+    mmsi: u64,
+    #[serde(rename = "ShipName", default)]
+    ship_name: String,
+    latitude: f64,
+    longitude: f64,
+    /// i.e. `2022-12-29 18:22:32.318353 +0000 UTC`
+    time_utc: String,
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+struct AisStreamReport {
+    /// speed over ground (knots)
+    sog: Option<f64>,
+    /// course over ground (degrees clockwise from north)
+    cog: Option<f64>,
+    /// degrees clockwise from north, or 511 if not available
+    true_heading: Option<u16>,
+    navigational_status: Option<u8>,
+}
+
+impl AisStreamVessel {
+    fn to_balloon_location(&self) -> Option<crate::location::BalloonLocation> {
+        if !POSITION_MESSAGES.contains(&self.message_type.as_str()) {
+            return None;
+        }
+        let report = self.message.get(&self.message_type)?;
+
+        let time = chrono::DateTime::parse_from_str(
+            self.metadata.time_utc.trim_end_matches(" UTC"),
+            "%Y-%m-%d %H:%M:%S%.f %z",
+        )
+        .map(|time| time.with_timezone(&chrono::Local))
+        .unwrap_or_else(|_| chrono::Local::now());
+
+        let vessel_name = self.metadata.ship_name.trim();
+        let ais = crate::location::ais::AisData {
+            mmsi: self.metadata.mmsi.to_string(),
+            vessel_name: if vessel_name.is_empty() {
+                None
+            } else {
+                Some(vessel_name.to_string())
+            },
+            imo: None,
+            vesselclass: None,
+            navstat: report.navigational_status.map(|status| status.to_string()),
+            heading: report
+                .true_heading
+                .filter(|heading| *heading < 360)
+                .map(|heading| heading.to_string()),
+            length: None,
+            width: None,
+            draught: None,
+            ref_front: None,
+            ref_left: None,
+        };
+
+        let mut data = crate::location::BalloonData::new(
+            None,
+            None,
+            Some(ais),
+            None,
+            crate::location::LocationSource::AisStream,
+        );
+        // 360 and 102.3 knots mean not available
+        data.course = report.cog.filter(|course| *course < 360.0);
+        data.speed = report
+            .sog
+            .filter(|speed| *speed < 102.3)
+            .map(|speed| speed * KNOTS_TO_METERS_PER_SECOND);
+
+        Some(crate::location::BalloonLocation {
+            location: crate::location::Location {
+                time,
+                coord: geo::coord! { x: self.metadata.longitude, y: self.metadata.latitude },
+                altitude: None,
+            },
+            data,
+        })
+    }
+}
+
+impl Clone for AisStream {
+    fn clone(&self) -> Self {
+        Self {
+            api_key: self.api_key.to_owned(),
+            mmsi: self.mmsi.to_owned(),
+            bounding_box: self.bounding_box,
+            url: self.url.to_owned(),
+            schedule: self.schedule,
+            session: None,
+        }
+    }
+}
+
+impl PartialEq for AisStream {
+    fn eq(&self, other: &Self) -> bool {
+        self.api_key == other.api_key
+            && self.mmsi == other.mmsi
+            && self.bounding_box == other.bounding_box
+            && self.url == other.url
+            && self.schedule == other.schedule
+    }
+}
+
+impl std::fmt::Debug for AisStream {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("AisStream")
+            .field("mmsi", &self.mmsi)
+            .field("bounding_box", &self.bounding_box)
+            .field("url", &self.url)
+            .field("schedule", &self.schedule)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITION_REPORT: &str = r#"{"Message":{"PositionReport":{"Cog":308,"CommunicationState":81982,"Latitude":66.02695,"Longitude":12.253821666666665,"MessageID":1,"NavigationalStatus":15,"PositionAccuracy":true,"Raim":false,"RateOfTurn":4,"RepeatIndicator":0,"Sog":10,"Spare":0,"SpecialManoeuvreIndicator":0,"Timestamp":31,"TrueHeading":235,"UserID":259000420,"Valid":true}},"MessageType":"PositionReport","MetaData":{"MMSI":259000420,"MMSI_String":259000420,"ShipName":"AUGUSTSON           ","latitude":66.02695,"longitude":12.253821666666665,"time_utc":"2022-12-29 18:22:32.318353 +0000 UTC"}}"#;
+
+    #[test]
+    fn test_position_report() {
+        let location = match serde_json::from_str::<AisStreamMessage>(POSITION_REPORT).unwrap() {
+            AisStreamMessage::Vessel(vessel) => vessel.to_balloon_location().unwrap(),
+            AisStreamMessage::Error { .. } => panic!(),
+        };
+
+        assert_eq!(location.data.callsign, Some("259000420".to_string()));
+        let ais = location.data.ais.unwrap();
+        assert_eq!(ais.vessel_name, Some("AUGUSTSON".to_string()));
+        assert_eq!(ais.heading, Some("235".to_string()));
+        assert_eq!(location.location.coord.y, 66.02695);
+        assert_eq!(location.data.course, Some(308.0));
+        assert!((location.data.speed.unwrap() - 5.14444).abs() < 1e-6);
+        assert_eq!(location.location.time.timestamp_millis(), 1672338152318);
+
+        assert!(matches!(
+            serde_json::from_str::<AisStreamMessage>(r#"{"error": "Api Key Is Not Valid"}"#),
+            Ok(AisStreamMessage::Error { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stream() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut connection = AisStream::new("abc123".to_string(), vec!["259000420".to_string()]);
+        connection.url = format!("ws://127.0.0.1:{:}/v0/stream", port);
+
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            let subscription = socket.read().unwrap().into_text().unwrap();
+
+            socket
+                .send(tungstenite::Message::Ping(Default::default()))
+                .unwrap();
+            socket
+                .send(tungstenite::Message::binary(POSITION_REPORT.as_bytes()))
+                .unwrap();
+
+            let pong = socket.read().unwrap();
+            (
+                serde_json::from_str::<serde_json::Value>(&subscription).unwrap(),
+                pong,
+            )
+        });
+
+        let mut locations = vec![];
+        let mut attempts = 0;
+        while locations.is_empty() && attempts < 100 {
+            locations.extend(connection.read_locations_from_aisstream().unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            attempts += 1;
+        }
+
+        let (subscription, pong) = server.join().unwrap();
+        assert_eq!(subscription["APIKey"], "abc123");
+        assert_eq!(subscription["FiltersShipMMSI"][0], "259000420");
+        assert_eq!(subscription["BoundingBoxes"][0][1][1], 180.0);
+        assert!(pong.is_pong());
+
+        assert_eq!(locations.len(), 1);
+        assert_eq!(
+            locations[0].data.source,
+            crate::location::LocationSource::AisStream
+        );
+
+        let mut track = crate::location::track::BalloonTrack::new("259000420".to_string());
+        track.push(locations[0].to_owned());
+        assert_eq!(track.display_name(), "AUGUSTSON (259000420)");
+    }
+}
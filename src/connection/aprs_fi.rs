@@ -1,15 +1,34 @@
 use chrono::Timelike;
 
 lazy_static::lazy_static! {
-    static ref MINIMUM_ACCESS_INTERVAL: chrono::Duration = chrono::Duration::seconds(10);
+    pub static ref MINIMUM_ACCESS_INTERVAL: chrono::Duration = chrono::Duration::seconds(10);
+    // the API accepts at most 20 stations in the `name` parameter
+    static ref MAXIMUM_NAMES_PER_REQUEST: usize = 20;
+    // a status report applies to a location only if reported within this long of it
+    static ref STATUS_TIME_TOLERANCE: chrono::Duration = chrono::Duration::minutes(5);
+    // surface conditions change slowly, so weather stations are read in place of locations at most this often
+    static ref WEATHER_INTERVAL: chrono::Duration = chrono::Duration::minutes(10);
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+// query parameters of a single request
+type Parameters = Vec<(&'static str, String)>;
+
+#[derive(serde::Deserialize, Debug, Clone, serde::Serialize)]
 pub struct AprsFiQuery {
     pub api_key: String,
     pub callsigns: Option<Vec<String>>,
+    /// weather stations whose surface conditions to report in the log
+    #[serde(default)]
+    pub weather_stations: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+    /// index of the batch of callsigns to request next, for each type of entry
+    #[serde(skip)]
+    next_batches: std::collections::HashMap<String, usize>,
     #[serde(skip)]
-    last_access: Option<chrono::DateTime<chrono::Local>>,
+    last_weather: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(skip)]
+    notices: Vec<(String, log::Level)>,
 }
 
 impl AprsFiQuery {
@@ -17,21 +36,44 @@ impl AprsFiQuery {
         Self {
             api_key,
             callsigns: callsigns.map(|callsigns| callsigns.to_owned()),
-            last_access: None,
+            weather_stations: None,
+            schedule: Default::default(),
+            next_batches: std::collections::HashMap::new(),
+            last_weather: None,
+            notices: vec![],
         }
     }
 }
 
+impl PartialEq for AprsFiQuery {
+    fn eq(&self, other: &Self) -> bool {
+        self.api_key == other.api_key
+            && self.callsigns == other.callsigns
+            && self.weather_stations == other.weather_stations
+            && self.schedule == other.schedule
+    }
+}
+
 impl AprsFiQuery {
-    fn parameters(&self) -> Result<Vec<(&str, String)>, super::ConnectionError> {
-        if let Some(callsigns) = &self.callsigns {
-            let parameters = vec![
-                ("name", callsigns.join(",")),
-                ("what", "loc".to_string()),
-                ("apikey", self.api_key.to_owned()),
-                ("format", "json".to_string()),
-            ];
-            Ok(parameters)
+    // parameters for each request, splitting callsigns into batches that the API will accept
+    fn parameters(&self, what: &str) -> Result<Vec<Parameters>, super::ConnectionError> {
+        let callsigns = if what == "wx" {
+            &self.weather_stations
+        } else {
+            &self.callsigns
+        };
+        if let Some(callsigns) = callsigns {
+            Ok(callsigns
+                .chunks(*MAXIMUM_NAMES_PER_REQUEST)
+                .map(|batch| {
+                    vec![
+                        ("name", batch.join(",")),
+                        ("what", what.to_string()),
+                        ("apikey", self.api_key.to_owned()),
+                        ("format", "json".to_string()),
+                    ]
+                })
+                .collect())
         } else {
             Err(super::ConnectionError::FailedToEstablish {
                 connection: "APRS.fi".to_string(),
@@ -40,34 +82,145 @@ impl AprsFiQuery {
         }
     }
 
+    // parameters of the batch to request next, along with its index; successive reads cycle through the batches
+    fn next_batch(
+        &self,
+        what: &str,
+    ) -> Result<Option<(usize, Parameters)>, super::ConnectionError> {
+        let mut batches = self.parameters(what)?;
+        if batches.is_empty() {
+            return Ok(None);
+        }
+        let index = self.next_batches.get(what).copied().unwrap_or_default() % batches.len();
+        Ok(Some((index, batches.swap_remove(index))))
+    }
+
+    // whether to read the weather stations instead of locations this time
+    fn weather_due(&self, now: chrono::DateTime<chrono::Local>) -> bool {
+        self.weather_stations
+            .as_ref()
+            .is_some_and(|stations| !stations.is_empty())
+            && self
+                .last_weather
+                .is_none_or(|last_weather| now - last_weather >= *WEATHER_INTERVAL)
+    }
+
     pub fn retrieve_aprs_from_aprsfi(
         &mut self,
+        http: &crate::configuration::HttpConfiguration,
     ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
         let now = chrono::Local::now();
-        if let Some(last_access_time) = self.last_access {
-            if now - last_access_time < *MINIMUM_ACCESS_INTERVAL {
-                return Err(crate::connection::ConnectionError::TooFrequent {
-                    connection: "APRS.fi".to_string(),
-                    duration: crate::utilities::duration_string(&MINIMUM_ACCESS_INTERVAL),
-                });
+        if self.weather_due(now) {
+            self.last_weather = Some(now);
+            for observation in self.retrieve_weather_from_aprsfi(http)? {
+                self.notices
+                    .push((observation.to_string(), log::Level::Info));
             }
+            return Ok(vec![]);
         }
 
-        let client = reqwest::blocking::Client::builder()
-            .user_agent(crate::connection::USER_AGENT.to_owned())
-            .timeout(Some(std::time::Duration::from_secs(10)))
-            .build()
-            .unwrap();
+        let mut balloon_locations: Vec<crate::location::BalloonLocation> = vec![];
+        for entries in self.entries("loc", http)? {
+            match entries {
+                AprsFiEntries::Loc(locations) => {
+                    for location in locations {
+                        balloon_locations.push(location.to_balloon_location());
+                    }
+                }
+                other => return Err(other.unexpected("locations")),
+            }
+        }
+        Ok(balloon_locations)
+    }
 
-        let parameters = self.parameters()?;
-        let response = client
-            .get("https://api.aprs.fi/api/get")
-            .query(&parameters)
-            .send()
-            .expect(&format!("{:?}", parameters));
-        let url = response.url().to_string();
+    // latest surface conditions from the given callsigns, if they are weather stations
+    pub fn retrieve_weather_from_aprsfi(
+        &mut self,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<Vec<WeatherObservation>, crate::connection::ConnectionError> {
+        let mut observations: Vec<WeatherObservation> = vec![];
+        for entries in self.entries("wx", http)? {
+            match entries {
+                AprsFiEntries::Wx(weather) => {
+                    for entry in weather {
+                        observations.push(entry.to_weather_observation());
+                    }
+                }
+                // no stations found
+                AprsFiEntries::Loc(locations) if locations.is_empty() => {}
+                other => return Err(other.unexpected("weather")),
+            }
+        }
+        Ok(observations)
+    }
+
+    fn entries(
+        &mut self,
+        what: &str,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<Vec<AprsFiEntries>, crate::connection::ConnectionError> {
+        let client = crate::connection::http_client(http).map_err(|message| {
+            crate::connection::ConnectionError::FailedToEstablish {
+                connection: "APRS.fi".to_string(),
+                message,
+            }
+        })?;
+
+        // a single request per read, so that more than one batch of callsigns stays within the rate limit
+        let (index, parameters) = match self.next_batch(what)? {
+            Some(next) => next,
+            None => return Ok(vec![]),
+        };
+        let (found, entries) = Self::request(&client, &parameters)?;
+        self.next_batches.insert(what.to_string(), index + 1);
+        if found == 0 {
+            let names = parameters
+                .iter()
+                .find(|(key, _)| *key == "name")
+                .map(|(_, names)| names.to_owned())
+                .unwrap_or_default();
+            self.notices.push((
+                format!("APRS.fi has no recent {:} entries for {:}", what, names),
+                log::Level::Debug,
+            ));
+        }
+
+        Ok(self.without_messages(entries).into_iter().collect())
+    }
 
-        self.last_access = Some(now);
+    // log message entries instead of treating them as a response of the wrong kind
+    fn without_messages(&mut self, entries: AprsFiEntries) -> Option<AprsFiEntries> {
+        match entries {
+            AprsFiEntries::Msg(messages) => {
+                for message in messages {
+                    self.notices
+                        .push((format!("APRS.fi message {:}", message), log::Level::Info));
+                }
+                None
+            }
+            other => Some(other),
+        }
+    }
+
+    // messages about the queries since the last call, i.e. that nothing was found
+    pub fn take_notices(&mut self) -> Vec<(String, log::Level)> {
+        std::mem::take(&mut self.notices)
+    }
+
+    fn request(
+        client: &reqwest::blocking::Client,
+        parameters: &[(&str, String)],
+    ) -> Result<(u32, AprsFiEntries), crate::connection::ConnectionError> {
+        let response = crate::connection::send_with_retry(
+            client.get("https://api.aprs.fi/api/get").query(parameters),
+            *crate::connection::RETRY_ATTEMPTS,
+            *crate::connection::RETRY_BACKOFF,
+        )
+        .map_err(|error| crate::connection::ConnectionError::ReadFailure {
+            connection: "APRS.fi".to_string(),
+            message: error.to_string(),
+        })?;
+        let url = response.url().to_string();
 
         match response.status() {
             reqwest::StatusCode::OK => {
@@ -82,15 +235,7 @@ impl AprsFiQuery {
                     }
                 };
                 match aprs_fi_response {
-                    AprsFiResponse::Ok { entries, .. } => {
-                        let mut balloon_locations: Vec<crate::location::BalloonLocation> = vec![];
-                        if let AprsFiEntries::Loc(locations) = entries {
-                            for location in locations {
-                                balloon_locations.push(location.to_balloon_location());
-                            }
-                        }
-                        Ok(balloon_locations)
-                    }
+                    AprsFiResponse::Ok { found, entries, .. } => Ok((found, entries)),
                     AprsFiResponse::Fail { description, .. } => {
                         Err(crate::connection::ConnectionError::ApiError {
                             message: description,
@@ -116,6 +261,8 @@ enum AprsFiResponse {
         command: String,
         what: String,
         found: u32,
+        /// absent when nothing was found
+        #[serde(default = "AprsFiEntries::empty")]
         entries: AprsFiEntries,
     },
     Fail {
@@ -133,6 +280,26 @@ enum AprsFiEntries {
     Msg(Vec<AprsFiMessage>),
 }
 
+impl AprsFiEntries {
+    fn empty() -> Self {
+        Self::Loc(vec![])
+    }
+
+    // report entries of a different kind than requested, instead of discarding them
+    fn unexpected(&self, expected: &str) -> crate::connection::ConnectionError {
+        let message = match self {
+            Self::Loc(locations) => format!("{:} location(s)", locations.len()),
+            Self::Wx(weather) => format!("{:} weather observation(s)", weather.len()),
+            Self::Msg(messages) => format!("{:} message(s)", messages.len()),
+        };
+        crate::connection::ConnectionError::ReadFailure {
+            connection: "APRS.fi".to_string(),
+            message: format!("expected {:} but received {:}", expected, message),
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(serde::Deserialize)]
 #[serde(tag = "class")]
 #[serde(rename_all = "lowercase")]
@@ -159,7 +326,12 @@ impl AprsFiLocation {
             Self::A { location } | Self::W { location } => location.to_balloon_location(),
             Self::I { location, ais } => {
                 let mut output = location.to_balloon_location();
-                output.data.ais = Some(ais.to_owned());
+                let mut ais = ais.to_owned();
+                // aprs.fi names AIS targets by vessel
+                if ais.vessel_name.is_none() {
+                    ais.vessel_name = location.name.to_owned();
+                }
+                output.data.ais = Some(ais);
                 output
             }
         }
@@ -197,23 +369,40 @@ struct AprsFiLocationRecord {
     path: Option<String>,
     phg: Option<String>,
     status: Option<String>,
-    status_lasttime: Option<String>,
+    #[serde(default)]
+    #[serde(with = "crate::utilities::optional_u64_string")]
+    status_lasttime: Option<u64>,
 }
 
 impl AprsFiLocationRecord {
+    // whether the status was reported close to the time of this location
+    fn status_is_current(&self) -> bool {
+        self.status_lasttime
+            .and_then(|status_time| chrono::DateTime::from_timestamp(status_time as i64, 0))
+            .is_some_and(|status_time| (status_time - self.time).abs() <= *STATUS_TIME_TOLERANCE)
+    }
+
     pub fn to_balloon_location(&self) -> crate::location::BalloonLocation {
         let from = aprs_parser::Callsign::new(&self.srccall).unwrap();
 
-        let mut via = vec![];
-        for step in self.path.as_ref().unwrap().split(',') {
-            via.push(aprs_parser::Via::decode_textual(step.as_bytes()).unwrap());
-        }
+        // AIS targets and some objects have no path
+        let via = match &self.path {
+            Some(path) => path
+                .split(',')
+                .filter_map(|step| aprs_parser::Via::decode_textual(step.as_bytes()))
+                .collect(),
+            None => vec![],
+        };
 
         let to = aprs_parser::Callsign::new(&self.dstcall).unwrap();
 
         let time = self.time.to_owned();
 
-        let symbol_chars: Vec<char> = self.symbol.as_ref().unwrap().chars().collect();
+        // default to the balloon symbol when none is given
+        let symbol_chars: Vec<char> = match &self.symbol {
+            Some(symbol) if symbol.chars().count() >= 2 => symbol.chars().collect(),
+            _ => vec!['/', 'O'],
+        };
 
         let aprs_packet = aprs_parser::AprsPacket {
             from,
@@ -236,19 +425,32 @@ impl AprsFiLocationRecord {
             }),
         };
 
+        let mut data = crate::location::BalloonData::new(
+            None,
+            Some(aprs_packet),
+            None,
+            None,
+            crate::location::LocationSource::AprsFi,
+        );
+        // fall back to the latest status report when the comment does not give the phase,
+        // unless the status was reported too long before or after this location
+        if data.phase.is_none() && self.status_is_current() {
+            data.phase = self
+                .status
+                .as_deref()
+                .and_then(crate::location::phase::FlightPhase::from_text);
+        }
+        data.course = self.course.map(|course| course as f64);
+        // APRS.fi reports speed in km/h
+        data.speed = self.speed.map(|speed| speed / 3.6);
+
         crate::location::BalloonLocation {
             location: crate::location::Location {
                 time: time.with_timezone(&chrono::Local),
                 coord: geo::coord! { x: self.lng, y: self.lat },
                 altitude: self.altitude,
             },
-            data: crate::location::BalloonData::new(
-                None,
-                Some(aprs_packet),
-                None,
-                None,
-                crate::location::LocationSource::AprsFi,
-            ),
+            data,
         }
     }
 }
@@ -269,38 +471,125 @@ struct AprsFiWeather {
     name: String,
     #[serde(with = "crate::utilities::utc_timestamp_string")]
     time: chrono::DateTime<chrono::Utc>,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    temp: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    pressure: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    humidity: u8,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    wind_direction: u8,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    wind_speed: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    wind_gust: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    rain_1h: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    rain_24h: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    rain_mn: f64,
-    #[serde_as(as = "serde_with::DisplayFromStr")]
-    luminosity: f64,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    temp: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    pressure: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    humidity: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    wind_direction: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    wind_speed: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    wind_gust: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    rain_1h: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    rain_24h: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    rain_mn: Option<f64>,
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    luminosity: Option<f64>,
+}
+
+impl AprsFiWeather {
+    pub fn to_weather_observation(&self) -> WeatherObservation {
+        WeatherObservation {
+            station: self.name.to_owned(),
+            time: self.time.with_timezone(&chrono::Local),
+            temperature: self.temp,
+            pressure: self.pressure,
+            humidity: self.humidity,
+            wind_direction: self.wind_direction,
+            wind_speed: self.wind_speed,
+            wind_gust: self.wind_gust,
+        }
+    }
+}
+
+/// surface conditions reported by an APRS weather station
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherObservation {
+    pub station: String,
+    pub time: chrono::DateTime<chrono::Local>,
+    /// temperature (°C)
+    pub temperature: Option<f64>,
+    /// pressure (hPa)
+    pub pressure: Option<f64>,
+    /// relative humidity (%)
+    pub humidity: Option<f64>,
+    /// direction from which the wind is blowing (degrees)
+    pub wind_direction: Option<f64>,
+    /// wind speed (m/s)
+    pub wind_speed: Option<f64>,
+    /// wind gust speed (m/s)
+    pub wind_gust: Option<f64>,
+}
+
+impl std::fmt::Display for WeatherObservation {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut conditions = vec![];
+        if let Some(temperature) = self.temperature {
+            conditions.push(format!("{:.1} °C", temperature));
+        }
+        if let Some(pressure) = self.pressure {
+            conditions.push(format!("{:.1} hPa", pressure));
+        }
+        if let Some(humidity) = self.humidity {
+            conditions.push(format!("{:.0}% humidity", humidity));
+        }
+        if let Some(wind_speed) = self.wind_speed {
+            let mut wind = format!("wind {:.1} m/s", wind_speed);
+            if let Some(wind_direction) = self.wind_direction {
+                wind += &format!(" from {:.0}°", wind_direction);
+            }
+            if let Some(wind_gust) = self.wind_gust {
+                wind += &format!(" gusting {:.1} m/s", wind_gust);
+            }
+            conditions.push(wind);
+        }
+        write!(
+            fmt,
+            "{:} weather at {:}: {:}",
+            self.station,
+            self.time.format(&crate::DATETIME_FORMAT),
+            if conditions.is_empty() {
+                String::from("no conditions reported")
+            } else {
+                conditions.join(", ")
+            }
+        )
+    }
 }
 
 #[serde_with::serde_as]
 #[derive(serde::Deserialize)]
 struct AprsFiMessage {
     messageid: String,
+    #[serde(with = "crate::utilities::utc_timestamp_string")]
     time: chrono::DateTime<chrono::Utc>,
     srccall: String,
     dst: String,
     message: String,
 }
 
+impl std::fmt::Display for AprsFiMessage {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(fmt, "{}>{:}: {:}", self.srccall, self.dst, self.message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,7 +606,9 @@ mod tests {
             ];
 
             let mut connection = AprsFiQuery::new(api_key, Some(&callsigns));
-            let packets = connection.retrieve_aprs_from_aprsfi().unwrap();
+            let packets = connection
+                .retrieve_aprs_from_aprsfi(&Default::default())
+                .unwrap();
 
             assert!(!packets.is_empty());
         } else {
@@ -356,6 +647,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_status() {
+        let record = |status_lasttime: &str| -> AprsFiLocationRecord {
+            serde_json::from_str(&format!(
+                r#"
+                {{
+                  "name": "W3EAX-11",
+                  "type": "l",
+                  "time": "1659286185",
+                  "lasttime": "1659286185",
+                  "lat": "39.41750",
+                  "lng": "-77.06550",
+                  "srccall": "W3EAX-11",
+                  "dstcall": "CQ",
+                  "comment": ",StrTrk,255,9,1.55V,3C,82725Pa,",
+                  "status": "burst detected, descending",
+                  "status_lasttime": "{:}"
+                }}
+                "#,
+                status_lasttime
+            ))
+            .unwrap()
+        };
+
+        assert_eq!(
+            record("1659286125").to_balloon_location().data.phase,
+            Some(crate::location::phase::FlightPhase::Descending)
+        );
+        // an hour-old status no longer describes the flight
+        assert_eq!(record("1659282585").to_balloon_location().data.phase, None);
+    }
+
     #[test]
     fn test_aprs() {
         let data = r#"
@@ -395,6 +718,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_nothing_found() {
+        for data in [
+            r#"{"command": "get", "result": "ok", "what": "loc", "found": 0}"#,
+            r#"{"command": "get", "result": "ok", "what": "loc", "found": 0, "entries": []}"#,
+        ] {
+            let response: AprsFiResponse = serde_json::from_str(data).unwrap();
+
+            match response {
+                AprsFiResponse::Ok {
+                    found,
+                    entries: AprsFiEntries::Loc(locations),
+                    ..
+                } => {
+                    assert_eq!(found, 0);
+                    assert!(locations.is_empty());
+                }
+                _ => panic!(),
+            }
+        }
+    }
+
     #[test]
     fn test_aprs_location_string() {
         let data = r#"
@@ -509,12 +854,30 @@ mod tests {
         "#;
         let response: AprsFiLocation = serde_json::from_str(data).unwrap();
 
-        match response {
+        match &response {
             AprsFiLocation::I { ais, .. } => {
                 assert_eq!(ais.mmsi, "21BWI");
             }
             _ => panic!(),
         }
+
+        // no `path` or `symbol`
+        let location = response.to_balloon_location();
+        match location.data.aprs_packet.unwrap() {
+            aprs_parser::AprsPacket {
+                via,
+                data: aprs_parser::AprsData::Position(position),
+                ..
+            } => {
+                assert!(via.is_empty());
+                assert_eq!((position.symbol_table, position.symbol_code), ('/', 'O'));
+            }
+            _ => panic!(),
+        }
+        assert_eq!(
+            location.location.coord,
+            geo::coord! { x: 17.83333, y: 62.95833 }
+        );
     }
 
     #[test]
@@ -529,6 +892,148 @@ mod tests {
         ];
 
         let mut connection = AprsFiQuery::new(api_key, Some(&callsigns));
-        assert!(connection.retrieve_aprs_from_aprsfi().is_err());
+        assert!(connection
+            .retrieve_aprs_from_aprsfi(&Default::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_batches() {
+        let callsigns: Vec<String> = (0..45).map(|index| format!("W3EAX-{:}", index)).collect();
+        let mut connection =
+            AprsFiQuery::new(String::from("123456.abcdefhijklmnop"), Some(&callsigns));
+
+        let batches = connection.parameters("loc").unwrap();
+        assert_eq!(batches.len(), 3);
+
+        let names: Vec<Vec<String>> = batches
+            .iter()
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .find(|(key, _)| *key == "name")
+                    .unwrap()
+                    .1
+                    .split(',')
+                    .map(String::from)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(names[0].len(), 20);
+        assert_eq!(names[1].len(), 20);
+        assert_eq!(names[2].len(), 5);
+        assert_eq!(names.concat(), callsigns);
+
+        // each read requests the batch after the last one read, wrapping around
+        for expected in [0, 1, 2, 0] {
+            let (index, parameters) = connection.next_batch("loc").unwrap().unwrap();
+            assert_eq!(index, expected);
+            assert_eq!(parameters, batches[expected]);
+            connection
+                .next_batches
+                .insert(String::from("loc"), index + 1);
+        }
+
+        // weather stations are requested separately from the callsigns
+        assert!(connection.next_batch("wx").is_err());
+        connection.weather_stations = Some(callsigns[..3].to_vec());
+        let (index, parameters) = connection.next_batch("wx").unwrap().unwrap();
+        assert_eq!(index, 0);
+        assert!(parameters.contains(&("name", callsigns[..3].join(","))));
+    }
+
+    #[test]
+    fn test_weather_due() {
+        let mut connection = AprsFiQuery::new(
+            String::from("123456.abcdefhijklmnop"),
+            Some(&vec![String::from("W3EAX-8")]),
+        );
+        let now = chrono::Local::now();
+        assert!(!connection.weather_due(now));
+
+        connection.weather_stations = Some(vec![String::from("KC3SKW-13")]);
+        assert!(connection.weather_due(now));
+        connection.last_weather = Some(now);
+        assert!(!connection.weather_due(now + chrono::Duration::minutes(1)));
+        assert!(connection.weather_due(now + *WEATHER_INTERVAL));
+    }
+
+    #[test]
+    fn test_weather() {
+        let data = r#"
+        {
+          "command": "get",
+          "result": "ok",
+          "what": "wx",
+          "found": 1,
+          "entries": [
+            {
+              "name": "OH2TI",
+              "time": "1267445689",
+              "temp": "-7.2",
+              "pressure": "1007.7",
+              "humidity": "90",
+              "wind_direction": "315",
+              "wind_speed": "2.2",
+              "wind_gust": "4.5"
+            }
+          ]
+        }
+        "#;
+        let response: AprsFiResponse = serde_json::from_str(data).unwrap();
+
+        match response {
+            AprsFiResponse::Ok {
+                entries: AprsFiEntries::Wx(weather),
+                ..
+            } => {
+                let observation = weather[0].to_weather_observation();
+                assert_eq!(observation.station, "OH2TI");
+                assert_eq!(observation.temperature, Some(-7.2));
+                assert_eq!(observation.wind_direction, Some(315.0));
+                assert_eq!(observation.wind_speed, Some(2.2));
+                assert!(observation.to_string().ends_with(
+                    "-7.2 °C, 1007.7 hPa, 90% humidity, wind 2.2 m/s from 315° gusting 4.5 m/s"
+                ));
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_messages() {
+        let data = r#"
+        {
+          "command": "get",
+          "result": "ok",
+          "what": "msg",
+          "found": 1,
+          "entries": [
+            {
+              "messageid": "123",
+              "time": "1267445689",
+              "srccall": "OH7LZB",
+              "dst": "OH7AA",
+              "message": "landed near the water tower"
+            }
+          ]
+        }
+        "#;
+        let response: AprsFiResponse = serde_json::from_str(data).unwrap();
+
+        match response {
+            AprsFiResponse::Ok { entries, .. } => {
+                let mut connection = AprsFiQuery::new(String::from("123456.abcdefhijklmnop"), None);
+                assert!(connection.without_messages(entries).is_none());
+                assert_eq!(
+                    connection.take_notices(),
+                    vec![(
+                        String::from("APRS.fi message OH7LZB>OH7AA: landed near the water tower"),
+                        log::Level::Info
+                    )]
+                );
+            }
+            _ => panic!(),
+        }
     }
 }
@@ -0,0 +1,342 @@
+use std::io::BufRead;
+use std::io::Write;
+
+lazy_static::lazy_static! {
+    static ref READ_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+    static ref CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+}
+
+fn default_hostname() -> String {
+    String::from("rotate.aprs2.net")
+}
+
+fn default_port() -> u16 {
+    14580
+}
+
+fn default_passcode() -> String {
+    // receive-only
+    String::from("-1")
+}
+
+// http://www.aprs-is.net/Connecting.aspx
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct AprsIsStream {
+    #[serde(default = "default_hostname")]
+    pub hostname: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub callsign: String,
+    #[serde(default = "default_passcode")]
+    pub passcode: String,
+    pub filter: Option<String>,
+    pub callsigns: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+    #[serde(skip)]
+    stream: Option<std::io::BufReader<std::net::TcpStream>>,
+    #[serde(skip)]
+    buffer: String,
+}
+
+impl AprsIsStream {
+    pub fn new(
+        hostname: Option<String>,
+        port: Option<u16>,
+        callsign: String,
+        passcode: Option<String>,
+        filter: Option<String>,
+        callsigns: Option<Vec<String>>,
+    ) -> Self {
+        Self {
+            hostname: hostname.unwrap_or_else(default_hostname),
+            port: port.unwrap_or_else(default_port),
+            callsign,
+            passcode: passcode.unwrap_or_else(default_passcode),
+            filter,
+            callsigns,
+            schedule: Default::default(),
+            stream: None,
+            buffer: String::new(),
+        }
+    }
+
+    fn address(&self) -> String {
+        format!("{:}:{:}", self.hostname, self.port)
+    }
+
+    fn login(&self) -> String {
+        let mut login = format!(
+            "user {:} pass {:} vers packetraven {:}",
+            self.callsign,
+            self.passcode,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        // without an explicit filter, ask the server for packets from the tracked callsigns
+        let filter = match &self.filter {
+            Some(filter) => Some(filter.to_owned()),
+            None => self
+                .callsigns
+                .as_ref()
+                .filter(|callsigns| !callsigns.is_empty())
+                .map(|callsigns| format!("b/{:}", callsigns.join("/"))),
+        };
+        if let Some(filter) = filter {
+            login += &format!(" filter {:}", filter);
+        }
+
+        login + "\r\n"
+    }
+
+    fn connect(
+        &self,
+    ) -> Result<std::io::BufReader<std::net::TcpStream>, crate::connection::ConnectionError> {
+        use std::net::ToSocketAddrs;
+
+        let failed = |message: String| crate::connection::ConnectionError::FailedToEstablish {
+            connection: format!("APRS-IS {:}", self.address()),
+            message,
+        };
+
+        let mut stream = None;
+        let mut last_error = String::from("could not resolve address");
+        for address in self
+            .address()
+            .to_socket_addrs()
+            .map_err(|error| failed(error.to_string()))?
+        {
+            match std::net::TcpStream::connect_timeout(&address, *CONNECT_TIMEOUT) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(error) => last_error = error.to_string(),
+            }
+        }
+        let mut stream = stream.ok_or_else(|| failed(last_error))?;
+
+        stream
+            .set_read_timeout(Some(*READ_TIMEOUT))
+            .map_err(|error| failed(error.to_string()))?;
+        stream
+            .write_all(self.login().as_bytes())
+            .map_err(|error| failed(error.to_string()))?;
+
+        Ok(std::io::BufReader::new(stream))
+    }
+
+    pub fn read_aprs_from_aprs_is(
+        &mut self,
+    ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
+        if self.stream.is_none() {
+            self.stream = Some(self.connect()?);
+            self.buffer.clear();
+        }
+
+        let mut lines = vec![];
+        let mut dropped = None;
+        if let Some(stream) = &mut self.stream {
+            // drain whatever lines are currently available
+            loop {
+                match stream.read_line(&mut self.buffer) {
+                    Ok(0) => {
+                        dropped = Some("server closed the connection".to_string());
+                        break;
+                    }
+                    Ok(_) => {
+                        if self.buffer.ends_with('\n') {
+                            lines.push(self.buffer.trim_end().to_string());
+                            self.buffer.clear();
+                        }
+                    }
+                    Err(error)
+                        if error.kind() == std::io::ErrorKind::WouldBlock
+                            || error.kind() == std::io::ErrorKind::TimedOut =>
+                    {
+                        break;
+                    }
+                    Err(error) => {
+                        dropped = Some(error.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(message) = dropped {
+            // reconnect on the next read
+            self.stream = None;
+            if lines.is_empty() {
+                return Err(crate::connection::ConnectionError::ReadFailure {
+                    connection: format!("APRS-IS {:}", self.address()),
+                    message,
+                });
+            }
+        }
+
+        let now = chrono::Local::now();
+        let filter = self
+            .callsigns
+            .as_ref()
+            .map(|callsigns| crate::connection::CallsignFilter::new(callsigns));
+        let mut locations = vec![];
+        for line in lines {
+            // lines starting with `#` are server messages
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let mut location =
+                match crate::location::BalloonLocation::from_aprs_frame(line.as_bytes(), Some(now))
+                {
+                    Ok(location) => location,
+                    Err(_) => continue,
+                };
+
+            if let Some(filter) = &filter {
+                if let Some(callsign) = &location.data.callsign {
+                    if !filter.matches(callsign) {
+                        continue;
+                    }
+                }
+            }
+
+            location.data.source = crate::location::LocationSource::AprsIs(self.address());
+            locations.push(location);
+        }
+
+        Ok(locations)
+    }
+}
+
+impl Clone for AprsIsStream {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new(
+            Some(self.hostname.to_owned()),
+            Some(self.port),
+            self.callsign.to_owned(),
+            Some(self.passcode.to_owned()),
+            self.filter.to_owned(),
+            self.callsigns.to_owned(),
+        );
+        clone.schedule = self.schedule;
+        clone
+    }
+}
+
+impl PartialEq for AprsIsStream {
+    fn eq(&self, other: &Self) -> bool {
+        self.hostname == other.hostname
+            && self.port == other.port
+            && self.callsign == other.callsign
+            && self.passcode == other.passcode
+            && self.filter == other.filter
+            && self.callsigns == other.callsigns
+            && self.schedule == other.schedule
+    }
+}
+
+impl std::fmt::Debug for AprsIsStream {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("AprsIsStream")
+            .field("hostname", &self.hostname)
+            .field("port", &self.port)
+            .field("callsign", &self.callsign)
+            .field("filter", &self.filter)
+            .field("callsigns", &self.callsigns)
+            .field("schedule", &self.schedule)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn serve(listener: &std::net::TcpListener, frames: &[&str]) -> String {
+        let (mut socket, _) = listener.accept().unwrap();
+        let mut login = vec![0; 256];
+        let length = socket.read(&mut login).unwrap();
+
+        socket.write_all(b"# aprsc 2.1.14\r\n").unwrap();
+        for frame in frames {
+            socket.write_all(frame.as_bytes()).unwrap();
+            socket.write_all(b"\r\n").unwrap();
+        }
+
+        String::from_utf8(login[..length].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_login() {
+        let connection = AprsIsStream::new(
+            None,
+            None,
+            "KC3SKW".to_string(),
+            None,
+            None,
+            Some(vec!["W3EAX-8".to_string(), "W3EAX-9".to_string()]),
+        );
+
+        assert_eq!(
+            connection.login(),
+            format!(
+                "user KC3SKW pass -1 vers packetraven {:} filter b/W3EAX-8/W3EAX-9\r\n",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+
+    #[test]
+    fn test_read_and_reconnect() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut connection = AprsIsStream::new(
+            Some("127.0.0.1".to_string()),
+            Some(port),
+            "KC3SKW".to_string(),
+            None,
+            None,
+            Some(vec!["W3EAX-8".to_string()]),
+        );
+
+        let server = std::thread::spawn(move || {
+            let first_login = serve(
+                &listener,
+                &[
+                    r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu",
+                    r"ICA3D2>APRS,qAS,dl4mea:/074849h4821.61N\01224.49E^322/103/A=003054",
+                ],
+            );
+            // the first socket is dropped here; the client should reconnect
+            let second_login = serve(
+                &listener,
+                &[
+                    r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu",
+                ],
+            );
+            (first_login, second_login)
+        });
+
+        let mut locations = vec![];
+        let mut attempts = 0;
+        while locations.len() < 2 && attempts < 20 {
+            if let Ok(new_locations) = connection.read_aprs_from_aprs_is() {
+                locations.extend(new_locations);
+            }
+            attempts += 1;
+        }
+
+        let (first_login, second_login) = server.join().unwrap();
+        assert!(first_login.starts_with("user KC3SKW pass -1"));
+        assert_eq!(first_login, second_login);
+
+        assert_eq!(locations.len(), 2);
+        for location in locations {
+            assert_eq!(location.data.callsign, Some("W3EAX-8".to_string()));
+        }
+    }
+}
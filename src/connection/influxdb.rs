@@ -0,0 +1,178 @@
+const MEASUREMENT: &str = "balloon_location";
+
+// writes new locations, with their ascent rate and ground speed, to an InfluxDB bucket in line protocol
+// https://docs.influxdata.com/influxdb/v2/api/#operation/PostWrite
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq)]
+pub struct InfluxDbOutput {
+    /// base URL of the InfluxDB server, i.e. `http://localhost:8086`
+    pub url: String,
+    pub org: Option<String>,
+    pub bucket: String,
+    pub token: Option<String>,
+}
+
+impl InfluxDbOutput {
+    pub fn new(url: String, org: Option<String>, bucket: String, token: Option<String>) -> Self {
+        Self {
+            url,
+            org,
+            bucket,
+            token,
+        }
+    }
+
+    // write the location at each index of its track, returning the number written
+    pub fn write(
+        &self,
+        locations: &[(&crate::location::track::BalloonTrack, usize)],
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<usize, crate::connection::ConnectionError> {
+        if locations.is_empty() {
+            return Ok(0);
+        }
+
+        let body = locations
+            .iter()
+            .map(|(track, index)| line(track, *index))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let failed = |message: String| crate::connection::ConnectionError::WriteFailure {
+            connection: format!("InfluxDB {:}", self.url),
+            message,
+        };
+
+        let client = crate::connection::http_client(http).map_err(failed)?;
+
+        let mut parameters = vec![
+            ("bucket", self.bucket.to_owned()),
+            ("precision", String::from("ms")),
+        ];
+        if let Some(org) = &self.org {
+            parameters.push(("org", org.to_owned()));
+        }
+        let mut request = client
+            .post(format!("{:}/api/v2/write", self.url.trim_end_matches('/')))
+            .query(&parameters)
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(body);
+        if let Some(token) = &self.token {
+            request = request.header(reqwest::header::AUTHORIZATION, format!("Token {:}", token));
+        }
+
+        let response = crate::connection::send_with_retry(
+            request,
+            *crate::connection::RETRY_ATTEMPTS,
+            *crate::connection::RETRY_BACKOFF,
+        )
+        .map_err(|error| failed(error.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(locations.len())
+        } else {
+            let status = response.status();
+            Err(failed(match response.text() {
+                Ok(text) if !text.is_empty() => format!("{:} - {:}", status, text),
+                _ => status.to_string(),
+            }))
+        }
+    }
+}
+
+// the location at the given index of the track, with the ascent rate and ground speed since the previous location
+fn line(track: &crate::location::track::BalloonTrack, index: usize) -> String {
+    let location = &track.locations[index];
+
+    let mut fields = vec![
+        format!("lat={:}", location.location.coord.y),
+        format!("lon={:}", location.location.coord.x),
+    ];
+    if let Some(altitude) = location.location.altitude {
+        fields.push(format!("altitude={:}", altitude));
+    }
+    if index > 0 {
+        let pair = &track.locations[index - 1..=index];
+        if let Some(ascent_rate) = crate::location::track::ascent_rates(pair).first() {
+            fields.push(format!("ascent_rate={:}", ascent_rate));
+        }
+        if let Some(ground_speed) = crate::location::track::ground_speeds(pair).first() {
+            fields.push(format!("ground_speed={:}", ground_speed));
+        }
+    }
+
+    format!(
+        "{:},callsign={:},source={:} {:} {:}",
+        MEASUREMENT,
+        escape_tag(&track.name),
+        escape_tag(&source_name(&location.data.source)),
+        fields.join(","),
+        location.location.time.timestamp_millis(),
+    )
+}
+
+// name of the kind of source, without its address
+fn source_name(source: &crate::location::LocationSource) -> String {
+    let name = format!("{:?}", source);
+    match name.split_once('(') {
+        Some((variant, _)) => variant.to_string(),
+        None => name,
+    }
+}
+
+// tag values must escape commas, equals signs, and spaces
+fn escape_tag(value: &str) -> String {
+    value
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+impl std::fmt::Debug for InfluxDbOutput {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("InfluxDbOutput")
+            .field("url", &self.url)
+            .field("org", &self.org)
+            .field("bucket", &self.bucket)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_protocol() {
+        let mut track = crate::location::track::BalloonTrack::new("W3EAX 8".to_string());
+        let start = chrono::DateTime::parse_from_rfc3339("2023-05-19T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Local);
+        for (seconds, latitude, altitude) in [(0, 39.0, 1000.0), (60, 39.01, 1300.0)] {
+            track.push(crate::location::BalloonLocation {
+                location: crate::location::Location {
+                    time: start + chrono::Duration::seconds(seconds),
+                    coord: geo::coord! { x: -77.0, y: latitude },
+                    altitude: Some(altitude),
+                },
+                data: crate::location::BalloonData::new(
+                    Some("W3EAX-8".to_string()),
+                    None,
+                    None,
+                    None,
+                    crate::location::LocationSource::AprsIs(String::from("rotate.aprs2.net:14580")),
+                ),
+            });
+        }
+
+        assert_eq!(
+            line(&track, 0),
+            "balloon_location,callsign=W3EAX\\ 8,source=AprsIs lat=39,lon=-77,altitude=1000 1684497600000"
+        );
+
+        let second = line(&track, 1);
+        assert!(second.starts_with(
+            "balloon_location,callsign=W3EAX\\ 8,source=AprsIs lat=39.01,lon=-77,altitude=1300,ascent_rate=5,ground_speed=18.5"
+        ));
+        assert!(second.ends_with(" 1684497660000"));
+    }
+}
@@ -1,5 +1,13 @@
+#[cfg(feature = "ais")]
+pub mod ais;
 #[cfg(feature = "aprsfi")]
 pub mod aprs_fi;
+#[cfg(feature = "aprsis")]
+pub mod aprs_is;
+#[cfg(feature = "influxdb")]
+pub mod influxdb;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 #[cfg(feature = "sondehub")]
@@ -8,33 +16,200 @@ pub mod text;
 
 lazy_static::lazy_static! {
     pub static ref USER_AGENT: String = format!("packetraven/{:}", env!("CARGO_PKG_VERSION"));
+    pub static ref RETRY_ATTEMPTS: u32 = 3;
+    pub static ref RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
 }
 
-#[derive(Debug, Clone)]
+// client for an outgoing HTTP request, with the configured timeout, user agent, and proxy
+pub fn http_client(
+    configuration: &crate::configuration::HttpConfiguration,
+) -> Result<reqwest::blocking::Client, String> {
+    let timeout = match configuration.timeout.to_std() {
+        Ok(timeout) if !timeout.is_zero() => timeout,
+        _ => return Err(String::from("http.timeout must be positive")),
+    };
+    let user_agent = configuration
+        .user_agent
+        .to_owned()
+        .unwrap_or_else(|| USER_AGENT.to_owned());
+    let user_agent = reqwest::header::HeaderValue::from_str(&user_agent)
+        .map_err(|error| format!("invalid http.user_agent - {:}", error))?;
+
+    let mut builder = reqwest::blocking::Client::builder()
+        .user_agent(user_agent)
+        .timeout(timeout);
+    // without an explicit proxy, reqwest reads `HTTP_PROXY` and `HTTPS_PROXY` from the environment
+    if let Some(proxy) = &configuration.proxy {
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|error| format!("invalid http.proxy - {:}", error))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|error| format!("could not create HTTP client - {:}", error))
+}
+
+// send a request, retrying failed requests and server errors after an exponentially increasing delay
+pub fn send_with_retry(
+    request: reqwest::blocking::RequestBuilder,
+    attempts: u32,
+    backoff: std::time::Duration,
+) -> Result<reqwest::blocking::Response, reqwest::Error> {
+    let mut backoff = backoff;
+    let mut attempt = 1;
+    loop {
+        let result = match request.try_clone() {
+            Some(request) => request.send(),
+            // requests with streaming bodies cannot be repeated
+            None => return request.send(),
+        };
+
+        match result {
+            Ok(response) if !response.status().is_server_error() || attempt >= attempts => {
+                return Ok(response)
+            }
+            Err(error) if attempt >= attempts => return Err(error),
+            _ => {}
+        }
+
+        std::thread::sleep(backoff);
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+// without the optional features, only the file connections remain, which all end in `File`
+#[allow(clippy::enum_variant_names)]
 pub enum Connection {
     AprsTextFile(text::file::AprsTextFile),
     GeoJsonFile(text::file::GeoJsonFile),
+    CsvFile(text::file::CsvFile),
+    GpxFile(text::file::GpxFile),
     #[cfg(feature = "serial")]
     AprsSerial(text::serial::AprsSerial),
     #[cfg(feature = "sondehub")]
     SondeHub(sondehub::SondeHubQuery),
     #[cfg(feature = "aprsfi")]
     AprsFi(aprs_fi::AprsFiQuery),
+    #[cfg(feature = "aprsis")]
+    AprsIs(aprs_is::AprsIsStream),
+    #[cfg(feature = "ais")]
+    AisStream(ais::AisStream),
     #[cfg(feature = "postgres")]
     PacketDatabase(postgres::PacketDatabase),
 }
 
 impl Connection {
+    // whether this connection needs the internet to retrieve locations
+    pub fn requires_internet(&self) -> bool {
+        match self {
+            #[cfg(feature = "aprsfi")]
+            Self::AprsFi(_) => true,
+            #[cfg(feature = "aprsis")]
+            Self::AprsIs(_) => true,
+            #[cfg(feature = "ais")]
+            Self::AisStream(_) => true,
+            #[cfg(feature = "sondehub")]
+            Self::SondeHub(_) => true,
+            Self::AprsTextFile(connection) => connection.path.iter().any(|path| is_remote(path)),
+            Self::GeoJsonFile(connection) => is_remote(&connection.path),
+            Self::CsvFile(connection) => is_remote(&connection.path),
+            Self::GpxFile(connection) => is_remote(&connection.path),
+            #[cfg(feature = "postgres")]
+            Self::PacketDatabase(_) => false,
+            #[cfg(feature = "serial")]
+            Self::AprsSerial(_) => false,
+        }
+    }
+
+    // short description of this connection, for display
+    pub fn name(&self) -> String {
+        match self {
+            #[cfg(feature = "aprsfi")]
+            Self::AprsFi(_) => String::from("APRS.fi"),
+            #[cfg(feature = "aprsis")]
+            Self::AprsIs(connection) => {
+                format!("APRS-IS {:}:{:}", connection.hostname, connection.port)
+            }
+            #[cfg(feature = "ais")]
+            Self::AisStream(_) => String::from("AISStream"),
+            #[cfg(feature = "sondehub")]
+            Self::SondeHub(_) => String::from("SondeHub"),
+            Self::AprsTextFile(connection) => connection.path.to_string(),
+            Self::GeoJsonFile(connection) => connection.path.to_owned(),
+            Self::CsvFile(connection) => connection.path.to_owned(),
+            Self::GpxFile(connection) => connection.path.to_owned(),
+            #[cfg(feature = "postgres")]
+            Self::PacketDatabase(connection) => format!("database {:}", connection.address()),
+            #[cfg(feature = "serial")]
+            Self::AprsSerial(connection) => connection.address(),
+        }
+    }
+
+    // how often to read from this connection, if configured
+    pub fn interval(&self) -> Option<chrono::Duration> {
+        match self {
+            #[cfg(feature = "aprsfi")]
+            Self::AprsFi(connection) => connection.schedule.interval,
+            #[cfg(feature = "aprsis")]
+            Self::AprsIs(connection) => connection.schedule.interval,
+            #[cfg(feature = "ais")]
+            Self::AisStream(connection) => connection.schedule.interval,
+            #[cfg(feature = "sondehub")]
+            Self::SondeHub(connection) => connection.schedule.interval,
+            Self::AprsTextFile(connection) => connection.schedule.interval,
+            Self::GeoJsonFile(connection) => connection.schedule.interval,
+            Self::CsvFile(connection) => connection.schedule.interval,
+            Self::GpxFile(connection) => connection.schedule.interval,
+            #[cfg(feature = "postgres")]
+            Self::PacketDatabase(connection) => connection.interval(),
+            #[cfg(feature = "serial")]
+            Self::AprsSerial(connection) => connection.schedule.interval,
+        }
+    }
+
+    // shortest interval between requests allowed by the connection's API
+    pub fn minimum_interval(&self) -> Option<chrono::Duration> {
+        match self {
+            #[cfg(feature = "aprsfi")]
+            Self::AprsFi(_) => Some(*aprs_fi::MINIMUM_ACCESS_INTERVAL),
+            #[cfg(feature = "sondehub")]
+            Self::SondeHub(_) => Some(*sondehub::MINIMUM_ACCESS_INTERVAL),
+            _ => None,
+        }
+    }
+
+    // messages from the connection since the last call, i.e. reconnections
+    pub fn take_notices(&mut self) -> Vec<(String, log::Level)> {
+        match self {
+            #[cfg(feature = "aprsfi")]
+            Self::AprsFi(connection) => connection.take_notices(),
+            #[cfg(feature = "serial")]
+            Self::AprsSerial(connection) => connection.take_notices(),
+            Self::AprsTextFile(connection) => connection.take_notices(),
+            _ => vec![],
+        }
+    }
+
     pub fn retrieve_locations(
         &mut self,
+        http: &crate::configuration::HttpConfiguration,
     ) -> Result<Vec<crate::location::BalloonLocation>, ConnectionError> {
         match self {
             #[cfg(feature = "aprsfi")]
-            Self::AprsFi(connection) => connection.retrieve_aprs_from_aprsfi(),
+            Self::AprsFi(connection) => connection.retrieve_aprs_from_aprsfi(http),
+            #[cfg(feature = "aprsis")]
+            Self::AprsIs(connection) => connection.read_aprs_from_aprs_is(),
+            #[cfg(feature = "ais")]
+            Self::AisStream(connection) => connection.read_locations_from_aisstream(),
             #[cfg(feature = "sondehub")]
-            Self::SondeHub(connection) => connection.retrieve_locations_from_sondehub(),
-            Self::AprsTextFile(connection) => connection.read_aprs_from_file(),
-            Self::GeoJsonFile(connection) => connection.read_locations_from_geojson(),
+            Self::SondeHub(connection) => connection.retrieve_locations_from_sondehub(http),
+            Self::AprsTextFile(connection) => connection.read_aprs_from_file(http),
+            Self::GeoJsonFile(connection) => connection.read_locations_from_geojson(http),
+            Self::CsvFile(connection) => connection.read_locations_from_csv(http),
+            Self::GpxFile(connection) => connection.read_locations_from_gpx(http),
             #[cfg(feature = "postgres")]
             Self::PacketDatabase(connection) => connection.retrieve_locations_from_database(),
             #[cfg(feature = "serial")]
@@ -43,9 +218,449 @@ impl Connection {
     }
 }
 
+/// scheduling settings shared by every connection
+#[serde_with::serde_as]
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Clone, Copy, Default)]
+pub struct ConnectionSchedule {
+    /// how often to read from this connection, if different from `time.interval`
+    #[serde(default)]
+    #[serde_as(as = "Option<serde_with::DurationSeconds<i64>>")]
+    pub interval: Option<chrono::Duration>,
+}
+
+// a connection, along with how often to read from it and when it was last read successfully
+#[derive(Debug, Clone)]
+pub struct ScheduledConnection {
+    pub connection: Connection,
+    pub interval: chrono::Duration,
+    pub last_access: Option<chrono::DateTime<chrono::Local>>,
+    pub status: ConnectionStatus,
+}
+
+impl ScheduledConnection {
+    pub fn new(connection: Connection, default_interval: chrono::Duration) -> Self {
+        // a non-positive interval would read on every tick
+        let mut interval = connection
+            .interval()
+            .filter(|interval| *interval > chrono::Duration::zero())
+            .unwrap_or(default_interval);
+        if let Some(minimum_interval) = connection.minimum_interval() {
+            interval = interval.max(minimum_interval);
+        }
+
+        Self {
+            connection,
+            interval,
+            last_access: None,
+            status: ConnectionStatus::default(),
+        }
+    }
+
+    // whether the interval of this connection has elapsed since its last successful read
+    pub fn due(&self, now: chrono::DateTime<chrono::Local>) -> bool {
+        self.last_access
+            .is_none_or(|last_access| now - last_access >= self.interval)
+    }
+}
+
+// result of reading from the connection at `index`; either the number of packets received or an error message
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionOutcome {
+    pub index: usize,
+    pub time: chrono::DateTime<chrono::Local>,
+    pub result: Result<usize, String>,
+}
+
+// when a connection last succeeded and last failed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionStatus {
+    pub last_success: Option<chrono::DateTime<chrono::Local>>,
+    pub last_error: Option<(chrono::DateTime<chrono::Local>, String)>,
+}
+
+impl ConnectionStatus {
+    pub fn update(&mut self, outcome: &ConnectionOutcome) {
+        match &outcome.result {
+            Ok(_) => self.last_success = Some(outcome.time),
+            Err(message) => self.last_error = Some((outcome.time, message.to_owned())),
+        }
+    }
+
+    // whether the most recent read failed
+    pub fn failing(&self) -> bool {
+        match (&self.last_success, &self.last_error) {
+            (Some(last_success), Some((last_error, _))) => last_error > last_success,
+            (None, Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    // i.e. `OK 12s ago` or `error 3m ago - <message>`
+    pub fn summary(&self, now: chrono::DateTime<chrono::Local>) -> String {
+        let ago = |time: &chrono::DateTime<chrono::Local>| {
+            if now - *time < chrono::Duration::seconds(1) {
+                String::from("just now")
+            } else {
+                crate::utilities::duration_string(&(*time - now))
+            }
+        };
+
+        if self.failing() {
+            let (time, message) = self.last_error.as_ref().unwrap();
+            format!("error {:} - {:}", ago(time), message)
+        } else if let Some(last_success) = &self.last_success {
+            format!("OK {:}", ago(last_success))
+        } else {
+            String::from("not yet read")
+        }
+    }
+}
+
 custom_error::custom_error! {pub ConnectionError
     ReadFailure { connection: String, message: String } = "failed to read from {connection} - {message}",
+    WriteFailure { connection: String, message: String } = "failed to write to {connection} - {message}",
     TooFrequent { connection: String, duration: String } = "retrieval request exceeded request frequency set for {connection} ({duration})",
     ApiError { message: String, url: String } = "API error parsing {url} - {message}",
     FailedToEstablish { connection: String, message: String } = "failed to establish connection to {connection}; {message}",
 }
+
+// callsigns to accept, where `*` matches any characters and `?` matches a single character (i.e. `W3EAX-*`)
+#[derive(Debug, Clone)]
+pub struct CallsignFilter {
+    patterns: Vec<regex::Regex>,
+}
+
+impl CallsignFilter {
+    pub fn new(callsigns: &[String]) -> Self {
+        Self {
+            patterns: callsigns
+                .iter()
+                .map(|callsign| {
+                    let pattern = regex::escape(callsign)
+                        .replace(r"\*", ".*")
+                        .replace(r"\?", ".");
+                    regex::Regex::new(&format!("^{:}$", pattern)).unwrap()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, callsign: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.is_match(callsign))
+    }
+}
+
+// whether the callsign contains wildcards, which not every API accepts
+pub fn is_callsign_pattern(callsign: &str) -> bool {
+    callsign.contains(['*', '?'])
+}
+
+fn is_remote(path: &str) -> bool {
+    !std::path::Path::new(path).exists() && url::Url::parse(path).is_ok()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectivityChange {
+    Lost,
+    Restored,
+}
+
+// lightweight check of internet connectivity, by opening a TCP connection to a known address
+#[derive(Debug, Clone)]
+pub struct ConnectivityProbe {
+    pub address: String,
+    pub timeout: std::time::Duration,
+    online: Option<bool>,
+}
+
+impl ConnectivityProbe {
+    pub fn new(address: String, timeout: std::time::Duration) -> Self {
+        Self {
+            address,
+            timeout,
+            online: None,
+        }
+    }
+
+    pub fn online(&self) -> bool {
+        self.online.unwrap_or(true)
+    }
+
+    pub fn check(&mut self) -> Option<ConnectivityChange> {
+        use std::net::ToSocketAddrs;
+
+        let online = match self.address.to_socket_addrs() {
+            Ok(addresses) => addresses.into_iter().any(|address| {
+                std::net::TcpStream::connect_timeout(&address, self.timeout).is_ok()
+            }),
+            Err(_) => false,
+        };
+
+        self.update(online)
+    }
+
+    fn update(&mut self, online: bool) -> Option<ConnectivityChange> {
+        let previous = self.online();
+        self.online = Some(online);
+
+        if previous && !online {
+            Some(ConnectivityChange::Lost)
+        } else if !previous && online {
+            Some(ConnectivityChange::Restored)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callsign_filter() {
+        let filter = CallsignFilter::new(&["W3EAX-*".to_string(), "KC3SKW".to_string()]);
+
+        assert!(filter.matches("W3EAX-8"));
+        assert!(filter.matches("W3EAX-14"));
+        assert!(filter.matches("KC3SKW"));
+        assert!(!filter.matches("W3EAX"));
+        assert!(!filter.matches("KC3SKW-9"));
+        assert!(!filter.matches("XW3EAX-8"));
+
+        let filter = CallsignFilter::new(&["W3EAX-?".to_string(), "W3E.X-1".to_string()]);
+        assert!(filter.matches("W3EAX-8"));
+        assert!(!filter.matches("W3EAX-10"));
+        // other characters are literal
+        assert!(!filter.matches("W3EBX-1"));
+
+        assert!(is_callsign_pattern("W3EAX-*"));
+        assert!(!is_callsign_pattern("W3EAX-8"));
+    }
+
+    #[test]
+    fn test_connectivity_transitions() {
+        let mut probe =
+            ConnectivityProbe::new("localhost:0".to_string(), std::time::Duration::from_secs(1));
+
+        assert!(probe.online());
+        assert_eq!(probe.update(true), None);
+        assert_eq!(probe.update(false), Some(ConnectivityChange::Lost));
+        assert!(!probe.online());
+        assert_eq!(probe.update(false), None);
+        assert_eq!(probe.update(true), Some(ConnectivityChange::Restored));
+        assert!(probe.online());
+    }
+
+    #[test]
+    fn test_connectivity_probe() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let mut probe = ConnectivityProbe::new(address, std::time::Duration::from_secs(1));
+        assert_eq!(probe.check(), None);
+        assert!(probe.online());
+
+        drop(listener);
+        assert_eq!(probe.check(), Some(ConnectivityChange::Lost));
+        assert!(!probe.online());
+    }
+
+    #[test]
+    fn test_send_with_retry() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = std::thread::spawn(move || {
+            for status in ["503 Service Unavailable", "200 OK"] {
+                let (mut socket, _) = listener.accept().unwrap();
+                let mut request = vec![0; 1024];
+                let _ = socket.read(&mut request).unwrap();
+                socket
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {:}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                            status
+                        )
+                        .as_bytes(),
+                    )
+                    .unwrap();
+            }
+        });
+
+        let response = send_with_retry(
+            reqwest::blocking::Client::new().get(format!("http://{:}", address)),
+            3,
+            std::time::Duration::from_millis(10),
+        )
+        .unwrap();
+        server.join().unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    #[test]
+    fn test_http_client() {
+        use std::io::Read;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // server that never responds
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = vec![0; 1024];
+            let length = socket.read(&mut request).unwrap();
+            std::thread::sleep(std::time::Duration::from_secs(2));
+            String::from_utf8_lossy(&request[..length]).to_string()
+        });
+
+        let client = http_client(&crate::configuration::HttpConfiguration {
+            timeout: chrono::Duration::milliseconds(500),
+            user_agent: Some(String::from("balloon-tracker/1.0")),
+            proxy: None,
+        })
+        .unwrap();
+        let start = std::time::Instant::now();
+        let result = client.get(format!("http://{:}", address)).send();
+        assert!(result.unwrap_err().is_timeout());
+        assert!(start.elapsed() < std::time::Duration::from_secs(2));
+
+        let request = server.join().unwrap().to_lowercase();
+        assert!(request.contains("user-agent: balloon-tracker/1.0"));
+
+        assert!(http_client(&crate::configuration::HttpConfiguration {
+            timeout: chrono::Duration::seconds(-1),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(http_client(&crate::configuration::HttpConfiguration {
+            user_agent: Some(String::from("balloon\ntracker")),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_http_proxy() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let proxy = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = vec![0; 1024];
+            let length = socket.read(&mut request).unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            String::from_utf8_lossy(&request[..length]).to_string()
+        });
+
+        let client = http_client(&crate::configuration::HttpConfiguration {
+            proxy: Some(format!("http://{:}", address)),
+            ..Default::default()
+        })
+        .unwrap();
+        let response = client
+            .get("http://api.v2.sondehub.org/amateur/telemetry")
+            .send()
+            .unwrap();
+        assert_eq!(response.text().unwrap(), "ok");
+
+        // requests through a proxy give the full URL
+        let request = proxy.join().unwrap();
+        assert!(request.starts_with("GET http://api.v2.sondehub.org/amateur/telemetry HTTP/1.1"));
+
+        assert!(http_client(&crate::configuration::HttpConfiguration {
+            proxy: Some(String::from("not a proxy")),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let path = format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        );
+        let mut file = text::file::AprsTextFile::new(path, None).unwrap();
+        file.schedule.interval = Some(chrono::Duration::seconds(2));
+
+        let mut connection = ScheduledConnection::new(
+            Connection::AprsTextFile(file),
+            chrono::Duration::seconds(10),
+        );
+        assert_eq!(connection.interval, chrono::Duration::seconds(2));
+
+        let now = chrono::Local::now();
+        assert!(connection.due(now));
+        connection.last_access = Some(now);
+        assert!(!connection.due(now + chrono::Duration::seconds(1)));
+        assert!(connection.due(now + chrono::Duration::seconds(2)));
+
+        // a non-positive interval falls back to the default
+        let mut file = text::file::AprsTextFile::new(connection.connection.name(), None).unwrap();
+        file.schedule.interval = Some(chrono::Duration::seconds(-5));
+        let connection = ScheduledConnection::new(
+            Connection::AprsTextFile(file),
+            chrono::Duration::seconds(10),
+        );
+        assert_eq!(connection.interval, chrono::Duration::seconds(10));
+
+        // every connection reads the same `interval` setting
+        let file: text::file::CsvFile =
+            serde_yaml::from_str("path: locations.csv\ninterval: 30").unwrap();
+        assert_eq!(
+            Connection::CsvFile(file).interval(),
+            Some(chrono::Duration::seconds(30))
+        );
+
+        // API limits take precedence over the configured interval
+        #[cfg(feature = "sondehub")]
+        {
+            let mut query = sondehub::SondeHubQuery::default();
+            query.schedule.interval = Some(chrono::Duration::seconds(1));
+            let connection =
+                ScheduledConnection::new(Connection::SondeHub(query), chrono::Duration::seconds(1));
+            assert_eq!(connection.interval, *sondehub::MINIMUM_ACCESS_INTERVAL);
+        }
+    }
+
+    #[test]
+    fn test_connection_status() {
+        let now = chrono::Local::now();
+        let mut status = ConnectionStatus::default();
+        assert_eq!(status.summary(now), "not yet read");
+
+        status.update(&ConnectionOutcome {
+            index: 0,
+            time: now - chrono::Duration::seconds(12),
+            result: Ok(3),
+        });
+        assert!(!status.failing());
+        assert_eq!(status.summary(now), "OK 12s ago");
+
+        status.update(&ConnectionOutcome {
+            index: 0,
+            time: now - chrono::Duration::minutes(3),
+            result: Err(String::from("offline")),
+        });
+        assert!(!status.failing());
+
+        status.update(&ConnectionOutcome {
+            index: 0,
+            time: now,
+            result: Err(String::from("offline")),
+        });
+        assert!(status.failing());
+        assert_eq!(status.summary(now), "error just now - offline");
+    }
+}
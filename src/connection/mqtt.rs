@@ -0,0 +1,312 @@
+lazy_static::lazy_static! {
+    static ref RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+}
+
+// locations waiting to be sent while the broker is unreachable
+const QUEUE_CAPACITY: usize = 100;
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_topic() -> String {
+    String::from("packetraven")
+}
+
+fn default_client_id() -> String {
+    format!("packetraven-{:}", std::process::id())
+}
+
+// publishes new locations as JSON to an MQTT broker, on the topic `{topic}/{track name}`
+// (MQTT 3.1.1 at QoS 0; https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/mqtt-v3.1.1.html)
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct MqttPublisher {
+    pub hostname: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_topic")]
+    pub topic: String,
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// whether the broker keeps the latest location on each topic for new subscribers
+    #[serde(default)]
+    pub retain: bool,
+    #[serde(skip)]
+    session: Option<Session>,
+}
+
+// a connection to the broker, kept up (and reconnected) by its own thread so that the app never waits on the network
+struct Session {
+    client: rumqttc::Client,
+    // the latest connection error, until taken by `MqttPublisher::take_error`
+    error: std::sync::Arc<std::sync::Mutex<Option<String>>>,
+    stopped: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl Session {
+    fn start(options: rumqttc::MqttOptions) -> Self {
+        let (client, mut connection) = rumqttc::Client::new(options, QUEUE_CAPACITY);
+        let error = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let stopped = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let connection_error = error.clone();
+        let connection_stopped = stopped.clone();
+        std::thread::spawn(move || {
+            for event in connection.iter() {
+                if connection_stopped.load(std::sync::atomic::Ordering::Relaxed) {
+                    break;
+                }
+                match event {
+                    Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                        *connection_error.lock().unwrap() = None;
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        *connection_error.lock().unwrap() = Some(error.to_string());
+                        std::thread::sleep(*RECONNECT_DELAY);
+                    }
+                }
+            }
+        });
+
+        Self {
+            client,
+            error,
+            stopped,
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.stopped
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        let _ = self.client.try_disconnect();
+    }
+}
+
+impl MqttPublisher {
+    pub fn new(
+        hostname: String,
+        port: Option<u16>,
+        topic: Option<String>,
+        username: Option<String>,
+        password: Option<String>,
+        retain: bool,
+    ) -> Self {
+        Self {
+            hostname,
+            port: port.unwrap_or_else(default_port),
+            topic: topic.unwrap_or_else(default_topic),
+            client_id: default_client_id(),
+            username,
+            password,
+            retain,
+            session: None,
+        }
+    }
+
+    fn address(&self) -> String {
+        format!("{:}:{:}", self.hostname, self.port)
+    }
+
+    fn options(&self) -> rumqttc::MqttOptions {
+        let mut options = rumqttc::MqttOptions::new(&self.client_id, &self.hostname, self.port);
+        options.set_clean_session(true);
+        if let Some(username) = &self.username {
+            options.set_credentials(username, self.password.to_owned().unwrap_or_default());
+        }
+        options
+    }
+
+    // queue each location on the topic of its track, returning the number queued
+    pub fn publish(
+        &mut self,
+        locations: &[(&str, &crate::location::BalloonLocation)],
+    ) -> Result<usize, crate::connection::ConnectionError> {
+        if locations.is_empty() {
+            return Ok(0);
+        }
+
+        let address = self.address();
+        let failed = |message: String| crate::connection::ConnectionError::WriteFailure {
+            connection: format!("MQTT {:}", address),
+            message,
+        };
+
+        let mut payloads = vec![];
+        for (name, location) in locations {
+            let payload =
+                serde_json::to_vec(location).map_err(|error| failed(error.to_string()))?;
+            payloads.push((format!("{:}/{:}", self.topic, name), payload));
+        }
+
+        let options = self.options();
+        let session = self.session.get_or_insert_with(|| Session::start(options));
+        // queued even while the broker is unreachable, to be sent once it reconnects
+        for (topic, payload) in payloads {
+            session
+                .client
+                .try_publish(topic, rumqttc::QoS::AtMostOnce, self.retain, payload)
+                .map_err(|error| failed(error.to_string()))?;
+        }
+
+        Ok(locations.len())
+    }
+
+    // the latest error connecting to the broker since the last call, if any
+    pub fn take_error(&mut self) -> Option<crate::connection::ConnectionError> {
+        let message = self.session.as_ref()?.error.lock().unwrap().take()?;
+        Some(crate::connection::ConnectionError::FailedToEstablish {
+            connection: format!("MQTT {:}", self.address()),
+            message,
+        })
+    }
+}
+
+impl Clone for MqttPublisher {
+    fn clone(&self) -> Self {
+        Self {
+            hostname: self.hostname.to_owned(),
+            port: self.port,
+            topic: self.topic.to_owned(),
+            client_id: self.client_id.to_owned(),
+            username: self.username.to_owned(),
+            password: self.password.to_owned(),
+            retain: self.retain,
+            session: None,
+        }
+    }
+}
+
+impl PartialEq for MqttPublisher {
+    fn eq(&self, other: &Self) -> bool {
+        self.hostname == other.hostname
+            && self.port == other.port
+            && self.topic == other.topic
+            && self.client_id == other.client_id
+            && self.username == other.username
+            && self.password == other.password
+            && self.retain == other.retain
+    }
+}
+
+impl std::fmt::Debug for MqttPublisher {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("MqttPublisher")
+            .field("hostname", &self.hostname)
+            .field("port", &self.port)
+            .field("topic", &self.topic)
+            .field("username", &self.username)
+            .field("retain", &self.retain)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::io::Write;
+
+    // read a control packet, returning its type and flags and its body
+    fn read_packet(socket: &mut std::net::TcpStream) -> (u8, Vec<u8>) {
+        let mut header = [0; 1];
+        socket.read_exact(&mut header).unwrap();
+        let mut length = 0;
+        let mut multiplier = 1;
+        loop {
+            let mut byte = [0; 1];
+            socket.read_exact(&mut byte).unwrap();
+            length += (byte[0] & 0x7f) as usize * multiplier;
+            multiplier *= 128;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        socket.read_exact(&mut body).unwrap();
+        (header[0], body)
+    }
+
+    #[test]
+    fn test_publish() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut publisher = MqttPublisher::new(
+            "127.0.0.1".to_string(),
+            Some(port),
+            Some("balloons".to_string()),
+            Some("ground".to_string()),
+            Some("station".to_string()),
+            false,
+        );
+
+        let broker = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let connect = read_packet(&mut socket);
+            socket.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+            let published = read_packet(&mut socket);
+            (connect, published)
+        });
+
+        let location = crate::location::BalloonLocation::from_aprs_frame(
+            r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu".as_bytes(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(publisher.publish(&[("W3EAX-8", &location)]).unwrap(), 1);
+
+        let ((connect_header, connect), (published_header, published)) = broker.join().unwrap();
+        assert_eq!(connect_header, 0x10);
+        assert_eq!(&connect[..7], &[0x00, 0x04, b'M', b'Q', b'T', b'T', 0x04]);
+        // clean session, with username and password
+        assert_eq!(connect[7], 0xc2);
+
+        assert_eq!(published_header, 0x30);
+        let topic = b"balloons/W3EAX-8";
+        assert_eq!(&published[..2], &(topic.len() as u16).to_be_bytes());
+        assert_eq!(&published[2..2 + topic.len()], topic);
+        let payload: serde_json::Value =
+            serde_json::from_slice(&published[2 + topic.len()..]).unwrap();
+        assert_eq!(payload["data"]["callsign"], "W3EAX-8");
+        assert!(publisher.take_error().is_none());
+    }
+
+    #[test]
+    fn test_unreachable_broker() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut publisher =
+            MqttPublisher::new("127.0.0.1".to_string(), Some(port), None, None, None, false);
+        assert!(publisher.take_error().is_none());
+
+        let location = crate::location::BalloonLocation::from_aprs_frame(
+            r"W3EAX-8>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu".as_bytes(),
+            None,
+        )
+        .unwrap();
+        // the location is queued regardless, and the connection error is reported separately
+        assert_eq!(publisher.publish(&[("W3EAX-8", &location)]).unwrap(), 1);
+
+        let start = std::time::Instant::now();
+        let error = loop {
+            if let Some(error) = publisher.take_error() {
+                break error;
+            }
+            assert!(start.elapsed() < std::time::Duration::from_secs(5));
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert!(matches!(
+            error,
+            crate::connection::ConnectionError::FailedToEstablish { .. }
+        ));
+        assert!(publisher.take_error().is_none());
+    }
+}
@@ -1,19 +1,27 @@
 use chrono::TimeZone;
 
+lazy_static::lazy_static! {
+    // range of the pause between polls of an idle SSH tunnel
+    static ref MINIMUM_IDLE_SLEEP: std::time::Duration = std::time::Duration::from_millis(1);
+    static ref MAXIMUM_IDLE_SLEEP: std::time::Duration = std::time::Duration::from_millis(100);
+}
+
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub struct DatabaseCredentials {
     pub hostname: String,
-    pub port: u32,
+    pub port: u16,
     pub database: String,
     pub table: String,
     pub username: String,
     pub password: String,
     pub tunnel: Option<SshCredentials>,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
 }
 impl DatabaseCredentials {
     pub fn new(
         hostname: String,
-        port: Option<u32>,
+        port: Option<u16>,
         database: Option<String>,
         table: String,
         username: String,
@@ -22,44 +30,65 @@ impl DatabaseCredentials {
     ) -> Self {
         Self {
             hostname,
-            port: match port {
-                Some(port) => port,
-                None => 5432,
-            },
-            database: match database {
-                Some(database) => database,
-                None => username.to_owned(),
-            },
+            port: port.unwrap_or(5432),
+            database: database.unwrap_or_else(|| username.to_owned()),
             table,
             username,
             password,
             tunnel,
+            schedule: Default::default(),
         }
     }
 
-    pub fn client(&self) -> postgres::Client {
+    // connect directly, or through the given tunnel's local port
+    pub fn client(
+        &self,
+        tunnel: Option<&SshTunnel>,
+    ) -> Result<postgres::Client, crate::connection::ConnectionError> {
+        let (hostname, port) = match tunnel {
+            Some(tunnel) => (String::from("127.0.0.1"), tunnel.local_port),
+            None => (self.hostname.to_owned(), self.port),
+        };
+
         postgres::Client::connect(
             &format!(
                 "host={:} port={:} dbname={:} user={:} password={:}",
-                self.hostname, self.port, self.database, self.username, self.password,
+                hostname, port, self.database, self.username, self.password,
             ),
             postgres::NoTls,
         )
-        .unwrap()
+        .map_err(
+            |error| crate::connection::ConnectionError::FailedToEstablish {
+                connection: format!("{:}:{:}", self.hostname, self.port),
+                message: error.to_string(),
+            },
+        )
+    }
+
+    pub fn tunnel(&self) -> Result<Option<SshTunnel>, crate::connection::ConnectionError> {
+        self.tunnel
+            .as_ref()
+            .map(|ssh_credentials| SshTunnel::open(ssh_credentials, &self.hostname, self.port))
+            .transpose()
     }
 }
 
 pub struct PacketDatabase {
     credentials: DatabaseCredentials,
-    client: postgres::Client,
+    // opened on first use; the client must be dropped before the tunnel it connects through
+    client: Option<postgres::Client>,
+    tunnel: Option<SshTunnel>,
 }
 
 impl Clone for PacketDatabase {
     fn clone(&self) -> Self {
-        Self {
-            credentials: self.credentials.clone(),
-            client: self.credentials.client(),
-        }
+        Self::from_credentials(&self.credentials)
+    }
+}
+
+impl PartialEq for PacketDatabase {
+    fn eq(&self, other: &Self) -> bool {
+        self.credentials == other.credentials
     }
 }
 
@@ -85,7 +114,7 @@ impl Default for PacketDatabase {
 impl PacketDatabase {
     pub fn new(
         hostname: String,
-        port: Option<u32>,
+        port: Option<u16>,
         database: Option<String>,
         table: String,
         username: String,
@@ -100,65 +129,114 @@ impl PacketDatabase {
     pub fn from_credentials(credentials: &DatabaseCredentials) -> Self {
         Self {
             credentials: credentials.to_owned(),
-            client: credentials.client(),
+            client: None,
+            tunnel: None,
         }
     }
 
-    pub fn table_exists(&mut self, table: &String) -> bool {
-        self.client
+    // the open client, connecting (through the tunnel, if any) when not yet connected
+    fn client(&mut self) -> Result<&mut postgres::Client, crate::connection::ConnectionError> {
+        let client = match self.client.take() {
+            Some(client) => client,
+            None => {
+                if self.tunnel.is_none() {
+                    self.tunnel = self.credentials.tunnel()?;
+                }
+                self.credentials.client(self.tunnel.as_ref())?
+            }
+        };
+        Ok(self.client.insert(client))
+    }
+
+    pub fn interval(&self) -> Option<chrono::Duration> {
+        self.credentials.schedule.interval
+    }
+
+    pub fn address(&self) -> String {
+        format!("{:}:{:}", self.credentials.hostname, self.credentials.port)
+    }
+
+    pub fn table_exists(
+        &mut self,
+        table: &String,
+    ) -> Result<bool, crate::connection::ConnectionError> {
+        let address = self.address();
+        self.client()?
             .query_one(
-                "SELECT EXISTS(SELECT 1 FROM pg_class WHERE relname=%s);",
+                "SELECT EXISTS(SELECT 1 FROM pg_class WHERE relname=$1);",
                 &[table],
             )
-            .unwrap()
-            .get(0)
+            .map(|row| row.get(0))
+            .map_err(|error| crate::connection::ConnectionError::ReadFailure {
+                connection: address,
+                message: error.to_string(),
+            })
     }
 
-    pub fn retrieve_locations_from_database(
-        &mut self,
-    ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
-        let mut locations: Vec<crate::location::BalloonLocation> = vec![];
+    fn create_table(&mut self) -> Result<(), crate::connection::ConnectionError> {
+        let table = self.credentials.table.to_owned();
+        if self.table_exists(&table)? {
+            return Ok(());
+        }
 
-        self.client
+        let address = self.address();
+        self.client()?
             .batch_execute(&format!(
                 "
-                    CREATE TABLE {:} (
-                        time    TIMESTAMP, 
-                        x       REAL, 
-                        y       REAL, 
-                        z       REAL, 
-                        source  VARCHAR, 
-                        point   GEOMETRY, 
+                    CREATE TABLE IF NOT EXISTS {:} (
+                        time    TIMESTAMP,
+                        x       REAL,
+                        y       REAL,
+                        z       REAL,
+                        source  VARCHAR,
+                        point   GEOMETRY,
                         PRIMARY KEY(time)
                     )
                 ",
-                self.credentials.table
+                table
             ))
-            .unwrap();
-
-        for row in self
-            .client
-            .query(
-                &format!("SELECT time, x, y, z FROM {:}", self.credentials.table),
-                &[],
+            .map_err(
+                |error| crate::connection::ConnectionError::FailedToEstablish {
+                    connection: address,
+                    message: error.to_string(),
+                },
             )
-            .unwrap()
-        {
+    }
+
+    pub fn retrieve_locations_from_database(
+        &mut self,
+    ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
+        let mut locations: Vec<crate::location::BalloonLocation> = vec![];
+
+        self.create_table()?;
+
+        let query = format!("SELECT time, x, y, z FROM {:}", self.credentials.table);
+        let rows = self.client()?.query(&query, &[]).map_err(|error| {
+            crate::connection::ConnectionError::ReadFailure {
+                connection: self.address(),
+                message: error.to_string(),
+            }
+        })?;
+
+        for row in rows {
+            let time: chrono::NaiveDateTime = row.get(0);
+            let x: f32 = row.get(1);
+            let y: f32 = row.get(2);
+            let z: Option<f32> = row.get(3);
             locations.push(crate::location::BalloonLocation {
                 location: crate::location::Location {
-                    time: chrono::Local.timestamp_opt(row.get(0), 0).unwrap(),
-                    coord: geo::coord! { x: row.get(1), y: row.get(2) },
-                    altitude: Some(row.get(3)),
+                    time: chrono::Utc
+                        .from_utc_datetime(&time)
+                        .with_timezone(&chrono::Local),
+                    coord: geo::coord! { x: x as f64, y: y as f64 },
+                    altitude: z.map(|z| z as f64),
                 },
                 data: crate::location::BalloonData::new(
                     None,
                     None,
                     None,
                     None,
-                    crate::location::LocationSource::Database(format!(
-                        "{:}:{:}",
-                        self.credentials.hostname, self.credentials.port,
-                    )),
+                    crate::location::LocationSource::Database(self.address()),
                 ),
             });
         }
@@ -166,115 +244,423 @@ impl PacketDatabase {
         Ok(locations)
     }
 
-    pub fn insert(&self) {
-        // TODO
+    // archive the given locations; rows whose time already exists are left as-is
+    pub fn insert(
+        &mut self,
+        locations: &[crate::location::BalloonLocation],
+    ) -> Result<u64, crate::connection::ConnectionError> {
+        self.create_table()?;
+
+        let statement = format!(
+            "
+                INSERT INTO {:} (time, x, y, z, source, point)
+                VALUES ($1, $2, $3, $4, $5, ST_SetSRID(ST_MakePoint($2, $3, COALESCE($4, 0)), 4326))
+                ON CONFLICT (time) DO NOTHING
+            ",
+            self.credentials.table
+        );
+
+        let address = self.address();
+        let client = self.client()?;
+        let mut num_inserted = 0;
+        for location in locations {
+            let time = location.location.time.naive_utc();
+            let x = location.location.coord.x as f32;
+            let y = location.location.coord.y as f32;
+            let z = location.location.altitude.map(|altitude| altitude as f32);
+            let source = format!("{:?}", location.data.source);
+
+            num_inserted += client
+                .execute(&statement, &[&time, &x, &y, &z, &source])
+                .map_err(|error| crate::connection::ConnectionError::WriteFailure {
+                    connection: address.to_owned(),
+                    message: error.to_string(),
+                })?;
+        }
+
+        Ok(num_inserted)
     }
 }
 
-fn default_port() -> u32 {
+fn default_port() -> u16 {
     22
 }
 
-#[derive(serde::Deserialize, Debug, PartialEq, Clone)]
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub struct SshCredentials {
     pub hostname: String,
     #[serde(default = "default_port")]
-    pub port: u32,
+    pub port: u16,
     pub username: String,
     pub password: String,
 }
 
+// forwards connections on a local port to a remote address, through an SSH session
+pub struct SshTunnel {
+    pub local_port: u16,
+    shutdown: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl SshTunnel {
+    pub fn open(
+        credentials: &SshCredentials,
+        remote_hostname: &str,
+        remote_port: u16,
+    ) -> Result<Self, crate::connection::ConnectionError> {
+        let failed = |message: String| crate::connection::ConnectionError::FailedToEstablish {
+            connection: format!("SSH tunnel {:}:{:}", credentials.hostname, credentials.port),
+            message,
+        };
+
+        let stream = std::net::TcpStream::connect(format!(
+            "{:}:{:}",
+            credentials.hostname, credentials.port
+        ))
+        .map_err(|error| failed(error.to_string()))?;
+        let mut session = ssh2::Session::new().map_err(|error| failed(error.to_string()))?;
+        session.set_tcp_stream(stream);
+        session
+            .handshake()
+            .map_err(|error| failed(error.to_string()))?;
+        session
+            .userauth_password(&credentials.username, &credentials.password)
+            .map_err(|error| failed(error.to_string()))?;
+        if !session.authenticated() {
+            return Err(failed(String::from("authentication failed")));
+        }
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")
+            .map_err(|error| failed(error.to_string()))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|error| failed(error.to_string()))?;
+        let local_port = listener
+            .local_addr()
+            .map_err(|error| failed(error.to_string()))?
+            .port();
+
+        let shutdown = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let thread = {
+            let shutdown = shutdown.clone();
+            let remote_hostname = remote_hostname.to_string();
+            std::thread::spawn(move || {
+                forward(session, listener, &remote_hostname, remote_port, &shutdown)
+            })
+        };
+
+        Ok(Self {
+            local_port,
+            shutdown,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.shutdown
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// shuttle bytes between accepted local sockets and SSH channels until shutdown
+fn forward(
+    session: ssh2::Session,
+    listener: std::net::TcpListener,
+    remote_hostname: &str,
+    remote_port: u16,
+    shutdown: &std::sync::atomic::AtomicBool,
+) {
+    use std::io::Read;
+
+    let mut pairs: Vec<(std::net::TcpStream, ssh2::Channel)> = vec![];
+    let mut buffer = [0; 16384];
+    let mut idle_sleep = *MINIMUM_IDLE_SLEEP;
+
+    while !shutdown.load(std::sync::atomic::Ordering::SeqCst) {
+        let mut idle = true;
+
+        if let Ok((socket, _)) = listener.accept() {
+            session.set_blocking(true);
+            let channel = session.channel_direct_tcpip(remote_hostname, remote_port, None);
+            session.set_blocking(false);
+            if let (Ok(channel), Ok(())) = (channel, socket.set_nonblocking(true)) {
+                pairs.push((socket, channel));
+            }
+            idle = false;
+        }
+
+        pairs.retain_mut(|(socket, channel)| {
+            let mut open = true;
+
+            match socket.read(&mut buffer) {
+                Ok(0) => open = false,
+                Ok(length) => {
+                    idle = false;
+                    open &= write_all(channel, &buffer[..length], shutdown);
+                }
+                Err(error) => open &= error.kind() == std::io::ErrorKind::WouldBlock,
+            }
+
+            match channel.read(&mut buffer) {
+                Ok(0) => open &= !channel.eof(),
+                Ok(length) => {
+                    idle = false;
+                    open &= write_all(socket, &buffer[..length], shutdown);
+                }
+                Err(error) => open &= error.kind() == std::io::ErrorKind::WouldBlock,
+            }
+
+            if !open {
+                let _ = channel.close();
+            }
+            open
+        });
+
+        idle_sleep = next_idle_sleep(idle_sleep, idle);
+        if idle {
+            std::thread::sleep(idle_sleep);
+        }
+    }
+}
+
+// back off while nothing is forwarded, to stay responsive during a transfer without spinning while idle
+fn next_idle_sleep(previous: std::time::Duration, idle: bool) -> std::time::Duration {
+    if idle {
+        (previous * 2).min(*MAXIMUM_IDLE_SLEEP)
+    } else {
+        *MINIMUM_IDLE_SLEEP
+    }
+}
+
+// write to a non-blocking stream, retrying until all bytes are written
+fn write_all(
+    writer: &mut impl std::io::Write,
+    mut data: &[u8],
+    shutdown: &std::sync::atomic::AtomicBool,
+) -> bool {
+    while !data.is_empty() {
+        if shutdown.load(std::sync::atomic::Ordering::SeqCst) {
+            return false;
+        }
+        match writer.write(data) {
+            Ok(0) => return false,
+            Ok(length) => data = &data[length..],
+            Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(std::time::Duration::from_millis(1))
+            }
+            Err(_) => return false,
+        }
+    }
+    writer.flush().is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn database_from_environment(table: &str) -> PacketDatabase {
+        let hostname = std::env::var("POSTGRES_HOSTNAME")
+            .expect("database credentials not set in environment variables");
+        let port = std::env::var("POSTGRES_PORT")
+            .ok()
+            .map(|port| port.parse::<u16>().unwrap());
+        let database = std::env::var("POSTGRES_DATABASE").ok();
+        let username = std::env::var("POSTGRES_USERNAME").unwrap();
+        let password = std::env::var("POSTGRES_PASSWORD").unwrap();
+
+        let tunnel = match std::env::var("SSH_HOSTNAME") {
+            Ok(hostname) => Some(SshCredentials {
+                hostname,
+                port: match std::env::var("SSH_PORT") {
+                    Ok(port) => port.parse::<u16>().unwrap(),
+                    Err(_) => 22,
+                },
+                username: std::env::var("SSH_USERNAME").unwrap(),
+                password: std::env::var("SSH_PASSWORD").unwrap(),
+            }),
+            Err(_) => None,
+        };
+
+        PacketDatabase::new(
+            hostname,
+            port,
+            database,
+            table.to_string(),
+            username,
+            password,
+            tunnel,
+        )
+    }
+
+    // a local port on which nothing listens
+    fn closed_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let tunnel = SshCredentials {
+            hostname: String::from("127.0.0.1"),
+            port: closed_port(),
+            username: String::from("user"),
+            password: String::from("password"),
+        };
+        assert!(matches!(
+            SshTunnel::open(&tunnel, "localhost", 5432),
+            Err(crate::connection::ConnectionError::FailedToEstablish { .. })
+        ));
+
+        // errors are returned when reading or writing, instead of panicking when constructed
+        let mut database = PacketDatabase::new(
+            String::from("127.0.0.1"),
+            Some(closed_port()),
+            None,
+            String::from("test_table"),
+            String::from("postgres"),
+            String::new(),
+            Some(tunnel),
+        );
+        assert!(database.retrieve_locations_from_database().is_err());
+        assert!(database.insert(&[]).is_err());
+        assert!(database.clone().client().is_err());
+    }
+
+    #[test]
+    fn test_port_range() {
+        assert!(serde_yaml::from_str::<SshCredentials>(
+            "hostname: bastion\nport: 70000\nusername: user\npassword: password"
+        )
+        .is_err());
+        let credentials: SshCredentials =
+            serde_yaml::from_str("hostname: bastion\nusername: user\npassword: password").unwrap();
+        assert_eq!(credentials.port, 22);
+    }
+
+    #[test]
+    fn test_idle_sleep() {
+        let mut sleep = *MINIMUM_IDLE_SLEEP;
+        for _ in 0..20 {
+            sleep = next_idle_sleep(sleep, true);
+        }
+        assert_eq!(sleep, *MAXIMUM_IDLE_SLEEP);
+        assert_eq!(next_idle_sleep(sleep, false), *MINIMUM_IDLE_SLEEP);
+    }
+
+    #[test]
+    fn test_write_all() {
+        // accepts one byte at a time, after first reporting that it would block
+        struct SlowWriter {
+            written: Vec<u8>,
+            blocked: bool,
+        }
+
+        impl std::io::Write for SlowWriter {
+            fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+                self.blocked = !self.blocked;
+                if self.blocked {
+                    return Err(std::io::ErrorKind::WouldBlock.into());
+                }
+                self.written.push(data[0]);
+                Ok(1)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let shutdown = std::sync::atomic::AtomicBool::new(false);
+        let mut writer = SlowWriter {
+            written: vec![],
+            blocked: false,
+        };
+        assert!(write_all(&mut writer, b"packet", &shutdown));
+        assert_eq!(writer.written, b"packet");
+
+        shutdown.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(!write_all(&mut writer, b"packet", &shutdown));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_read_twice() {
+        let mut database = database_from_environment("test_read_twice");
+
+        database.retrieve_locations_from_database().unwrap();
+        database.retrieve_locations_from_database().unwrap();
+        assert!(database
+            .table_exists(&String::from("test_read_twice"))
+            .unwrap());
+
+        database
+            .client()
+            .unwrap()
+            .batch_execute("DROP TABLE test_read_twice;")
+            .unwrap();
+    }
+
     #[test]
     #[ignore]
     fn test_database() {
-        if let Ok(hostname) = std::env::var("POSTGRES_HOSTNAME") {
-            let port = match std::env::var("POSTGRES_PORT") {
-                Ok(port) => Some(port.parse::<u32>().unwrap()),
-                Err(_) => None,
-            };
-            let database = match std::env::var("POSTGRES_DATABASE") {
-                Ok(database) => Some(database),
-                Err(_) => None,
-            };
-            let username = std::env::var("POSTGRES_USERNAME").unwrap();
-            let password = std::env::var("POSTGRES_PASSWORD").unwrap();
-
-            let tunnel = match std::env::var("SSH_HOSTNAME") {
-                Ok(hostname) => Some(SshCredentials {
-                    hostname,
-                    port: match std::env::var("SSH_PORT") {
-                        Ok(port) => port.parse::<u32>().unwrap(),
-                        Err(_) => 22,
-                    },
-                    username: std::env::var("SSH_USERNAME").unwrap(),
-                    password: std::env::var("SSH_PASSWORD").unwrap(),
-                }),
-                Err(_) => None,
-            };
-
-            let mut database = crate::connection::postgres::PacketDatabase::new(
-                hostname,
-                port,
-                database,
-                String::from("test_table"),
-                username,
-                password,
-                tunnel,
-            );
+        let mut database = database_from_environment("test_table");
 
-            let table_name = String::from("test_table");
+        let table_name = String::from("test_table");
 
-            database.table_exists(&table_name);
+        if database.table_exists(&table_name).unwrap() {
             database
-                .client
-                .execute("DROP TABLE table;", &[&table_name])
+                .client()
+                .unwrap()
+                .batch_execute("DROP TABLE test_table;")
                 .unwrap();
+        }
 
-            let packet_1 = crate::location::BalloonLocation::from_aprs_frame(
+        let packet_1 = crate::location::BalloonLocation::from_aprs_frame(
         "W3EAX-13>APRS,N3KTX-10*,WIDE1,WIDE2-1,qAR,N3TJJ-11:!/:J..:sh'O   /A=053614|!g|  /W3EAX,313,0,21'C,nearspace.umd.edu".as_bytes(),
         Some(chrono::Local.with_ymd_and_hms(2019, 2, 3, 14, 36, 16).unwrap()),
     ).unwrap();
-            let packet_2 = crate::location::BalloonLocation::from_aprs_frame(
+        let packet_2 = crate::location::BalloonLocation::from_aprs_frame(
         "W3EAX-13>APRS,WIDE1-1,WIDE2-1,qAR,W4TTU:!/:JAe:tn8O   /A=046255|!i|  /W3EAX,322,0,20'C,nearspace.umd.edu".as_bytes(),
         Some(chrono::Local.with_ymd_and_hms(2019, 2, 3, 14, 38, 23).unwrap()),
     ).unwrap();
-            let packet_3 = crate::location::BalloonLocation::from_aprs_frame(
+        let packet_3 = crate::location::BalloonLocation::from_aprs_frame(
         "W3EAX-13>APRS,KC3FIT-1,WIDE1*,WIDE2-1,qAR,KC3AWP-10:!/:JL2:u4wO   /A=043080|!j|  /W3EAX,326,0,20'C,nearspace.umd.edu".as_bytes(),
         Some(chrono::Local.with_ymd_and_hms(2019, 2, 3, 14, 39, 28).unwrap()),
     ).unwrap();
 
-            let input_packets = vec![packet_1, packet_2, packet_3];
+        let input_packets = vec![packet_1, packet_2, packet_3];
 
-            // database.insert(input_packets, table_name);
+        database.insert(&input_packets).unwrap();
 
-            // assert_eq!(
-            //     packet_1,
-            //     database.get(
-            //         packet_1.time,
-            //         packet_1.data.aprs_packet.unwrap().from.call()
-            //     )
-            // );
+        // assert_eq!(
+        //     packet_1,
+        //     database.get(
+        //         packet_1.time,
+        //         packet_1.data.aprs_packet.unwrap().from.call()
+        //     )
+        // );
 
-            let mut connection = super::super::Connection::PacketDatabase(database);
-            let packets = connection.retrieve_locations().unwrap();
+        let mut connection = super::super::Connection::PacketDatabase(database);
+        let packets = connection.retrieve_locations(&Default::default()).unwrap();
 
-            // database.table_exists(&table_name);
-            // database.client.execute("DROP TABLE table;", &[&table_name]);
+        // database.table_exists(&table_name);
+        // database.client.execute("DROP TABLE table;", &[&table_name]);
 
-            assert!(!packets.is_empty());
+        assert!(!packets.is_empty());
 
-            for index in 0..packets.len() {
-                assert_eq!(
-                    packets.get(index).unwrap(),
-                    input_packets.get(index).unwrap()
-                )
-            }
-        } else {
-            panic!("database credentials not set in environment variables");
+        for index in 0..packets.len() {
+            assert_eq!(
+                packets.get(index).unwrap(),
+                input_packets.get(index).unwrap()
+            )
         }
     }
 }
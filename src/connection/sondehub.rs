@@ -1,5 +1,5 @@
 lazy_static::lazy_static! {
-    static ref MINIMUM_ACCESS_INTERVAL: chrono::Duration = chrono::Duration::seconds(10);
+    pub static ref MINIMUM_ACCESS_INTERVAL: chrono::Duration = chrono::Duration::seconds(10);
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, Default, serde::Serialize)]
@@ -7,8 +7,8 @@ pub struct SondeHubQuery {
     pub start: Option<chrono::DateTime<chrono::Local>>,
     pub end: Option<chrono::DateTime<chrono::Local>>,
     pub callsigns: Option<Vec<String>>,
-    #[serde(skip)]
-    last_access: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
 }
 
 // https://generator.swagger.io/?url=https://raw.githubusercontent.com/projecthorus/sondehub-infra/main/swagger.yaml#/amateur/get_amateur_telemetry__payload_callsign_
@@ -22,7 +22,7 @@ impl SondeHubQuery {
             start,
             end,
             callsigns: callsigns.map(|callsigns| callsigns.to_owned()),
-            last_access: None,
+            schedule: Default::default(),
         }
     }
 }
@@ -50,36 +50,36 @@ impl SondeHubQuery {
 
     pub fn retrieve_locations_from_sondehub(
         &mut self,
+        http: &crate::configuration::HttpConfiguration,
     ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
-        let now = chrono::Local::now();
-        if let Some(last_access_time) = self.last_access {
-            if now - last_access_time < *MINIMUM_ACCESS_INTERVAL {
-                return Err(crate::connection::ConnectionError::TooFrequent {
-                    connection: "SondeHub".to_string(),
-                    duration: crate::utilities::duration_string(&MINIMUM_ACCESS_INTERVAL),
-                });
-            }
-        }
-
         let mut balloon_locations: Vec<crate::location::BalloonLocation> = vec![];
 
-        let client = reqwest::blocking::Client::builder()
-            .user_agent(crate::connection::USER_AGENT.to_owned())
-            .timeout(Some(std::time::Duration::from_secs(10)))
-            .build()
-            .unwrap();
+        let client = crate::connection::http_client(http).map_err(|message| {
+            crate::connection::ConnectionError::FailedToEstablish {
+                connection: "SondeHub".to_string(),
+                message,
+            }
+        })?;
 
         let parameters = self.parameters();
         if let Some(callsigns) = &self.callsigns {
             for callsign in callsigns {
-                let response = client
-                    .get(format!(
-                        "https://api.v2.sondehub.org/amateur/telemetry/{:}",
-                        callsign
-                    ))
-                    .query(&parameters)
-                    .send()
-                    .unwrap_or_else(|error| panic!("{:} - {:?}", error, parameters));
+                let response = crate::connection::send_with_retry(
+                    client
+                        .get(format!(
+                            "https://api.v2.sondehub.org/amateur/telemetry/{:}",
+                            callsign
+                        ))
+                        .query(&parameters),
+                    *crate::connection::RETRY_ATTEMPTS,
+                    *crate::connection::RETRY_BACKOFF,
+                )
+                .map_err(|error| {
+                    crate::connection::ConnectionError::ReadFailure {
+                        connection: "SondeHub".to_string(),
+                        message: error.to_string(),
+                    }
+                })?;
 
                 let url = response.url().to_string().to_owned();
 
@@ -114,7 +114,6 @@ impl SondeHubQuery {
             });
         }
 
-        self.last_access = Some(now);
         Ok(balloon_locations)
     }
 }
@@ -155,27 +154,37 @@ struct SondeHubLocation {
 impl SondeHubLocation {
     pub fn to_balloon_location(&self) -> crate::location::BalloonLocation {
         let aprs_packet = match self.raw.as_ref() {
-            Some(frame) => match aprs_parser::AprsPacket::decode_textual(frame.as_bytes()) {
-                Ok(packet) => Some(packet),
-                Err(_) => None,
-            },
+            Some(frame) => aprs_parser::AprsPacket::decode_textual(frame.as_bytes()).ok(),
             None => None,
         };
         let time = self.datetime.to_owned();
 
+        let mut data = crate::location::BalloonData::new(
+            Some(self.payload_callsign.to_owned()),
+            aprs_packet,
+            None,
+            self.raw.to_owned(),
+            crate::location::LocationSource::SondeHub,
+        );
+
+        // sensor readings reported by the payload take precedence over values parsed from the comment
+        let mut telemetry = data.telemetry.take().unwrap_or_default();
+        telemetry.voltage = self.batt.or(telemetry.voltage);
+        telemetry.temperature = self.temp.or(telemetry.temperature);
+        telemetry.pressure = self.pressure.or(telemetry.pressure);
+        telemetry.humidity = self.humidity.or(telemetry.humidity);
+        telemetry.satellites = self.sats.or(telemetry.satellites);
+        if telemetry != crate::location::telemetry::Telemetry::default() {
+            data.telemetry = Some(telemetry);
+        }
+
         crate::location::BalloonLocation {
             location: crate::location::Location {
                 time: time.with_timezone(&chrono::Local),
                 coord: geo::coord! { x: self.lon, y: self.lat },
                 altitude: Some(self.alt),
             },
-            data: crate::location::BalloonData::new(
-                Some(self.payload_callsign.to_owned()),
-                aprs_packet,
-                None,
-                self.raw.to_owned(),
-                crate::location::LocationSource::AprsFi,
-            ),
+            data,
         }
     }
 }
@@ -204,7 +213,9 @@ mod tests {
             None,
             Some(&callsigns),
         );
-        let packets = connection.retrieve_locations_from_sondehub().unwrap();
+        let packets = connection
+            .retrieve_locations_from_sondehub(&Default::default())
+            .unwrap();
 
         assert!(!packets.is_empty());
     }
@@ -232,6 +243,14 @@ mod tests {
         "#;
         let response: SondeHubLocation = serde_json::from_str(data).unwrap();
 
+        let location = response.to_balloon_location();
+        assert_eq!(location.data.callsign, Some("N1YIP-11".to_string()));
+        assert!(location.data.aprs_packet.is_some());
+        assert_eq!(
+            location.data.source,
+            crate::location::LocationSource::SondeHub
+        );
+
         let SondeHubLocation { lon, .. } = response;
         assert_eq!(lon, -68.30413186813188);
     }
@@ -266,6 +285,43 @@ mod tests {
         assert_eq!(lon, 1.86);
     }
 
+    #[test]
+    fn test_sensors() {
+        let data = r#"
+        {
+            "software_name": "radiosonde_auto_rx",
+            "software_version": "1.6.2",
+            "uploader_callsign": "KC3SKW",
+            "time_received": "2023-05-23T09:46:10Z",
+            "payload_callsign": "HORUS-V2",
+            "datetime": "2023-05-23T09:46:09Z",
+            "lat": 39.15,
+            "lon": -76.45,
+            "alt": 20151.2,
+            "temp": -52.3,
+            "humidity": 4.1,
+            "pressure": 55.2,
+            "batt": 2.95,
+            "sats": 9,
+            "vel_v": 5.1,
+            "vel_h": 12.4
+        }
+        "#;
+        let response: SondeHubLocation = serde_json::from_str(data).unwrap();
+
+        let location = response.to_balloon_location();
+        assert_eq!(
+            location.data.telemetry,
+            Some(crate::location::telemetry::Telemetry {
+                voltage: Some(2.95),
+                temperature: Some(-52.3),
+                pressure: Some(55.2),
+                humidity: Some(4.1),
+                satellites: Some(9),
+            })
+        );
+    }
+
     #[test]
     fn test_response() {
         let data = r#"
@@ -317,7 +373,9 @@ mod tests {
         let callsigns = vec![String::from("nonexistent")];
 
         let mut connection = SondeHubQuery::new(None, None, Some(&callsigns));
-        let packets = connection.retrieve_locations_from_sondehub().unwrap();
+        let packets = connection
+            .retrieve_locations_from_sondehub(&Default::default())
+            .unwrap();
 
         assert!(packets.is_empty());
     }
@@ -1,11 +1,93 @@
-use std::io::prelude::BufRead;
+use std::io::prelude::Read;
 
 use chrono::{TimeZone, Timelike};
 
+/// a single path or URL, or a list of them; local file names may contain `*` and `?` wildcards
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum TextPaths {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl TextPaths {
+    pub fn iter(&self) -> std::slice::Iter<'_, String> {
+        match self {
+            Self::Single(path) => std::slice::from_ref(path).iter(),
+            Self::Multiple(paths) => paths.iter(),
+        }
+    }
+
+    // paths with any wildcards replaced by the matching files, in order of name; URLs are left as they are
+    pub fn expanded(&self) -> Vec<String> {
+        let mut expanded = vec![];
+        for path in self.iter() {
+            if std::path::Path::new(path).exists()
+                || !path.contains(['*', '?'])
+                || url::Url::parse(path).is_ok()
+            {
+                expanded.push(path.to_owned());
+                continue;
+            }
+
+            let path = std::path::Path::new(path);
+            let directory = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => std::path::Path::new("."),
+            };
+            let pattern = path.file_name().unwrap_or_default().to_string_lossy();
+            let pattern = regex::Regex::new(&format!(
+                "^{:}$",
+                regex::escape(&pattern)
+                    .replace(r"\*", ".*")
+                    .replace(r"\?", ".")
+            ))
+            .unwrap();
+
+            let mut matches: Vec<String> = match std::fs::read_dir(directory) {
+                Ok(entries) => entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.is_file()
+                            && path
+                                .file_name()
+                                .is_some_and(|name| pattern.is_match(&name.to_string_lossy()))
+                    })
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                Err(_) => vec![],
+            };
+            matches.sort();
+            expanded.extend(matches);
+        }
+        expanded
+    }
+}
+
+impl std::fmt::Display for TextPaths {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            fmt,
+            "{:}",
+            self.iter()
+                .map(|path| path.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        )
+    }
+}
+
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub struct AprsTextFile {
-    pub path: String,
+    pub path: TextPaths,
     pub callsigns: Option<Vec<String>>,
+    /// `strftime` format of the time prepended to each line, i.e. `%d/%m/%Y %H:%M:%S`
+    pub time_format: Option<String>,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+    #[serde(skip)]
+    notices: Vec<(String, log::Level)>,
 }
 
 impl AprsTextFile {
@@ -14,7 +96,13 @@ impl AprsTextFile {
         callsigns: Option<Vec<String>>,
     ) -> Result<Self, crate::connection::ConnectionError> {
         if std::path::Path::new(&path).exists() || url::Url::parse(&path).is_ok() {
-            Ok(Self { path, callsigns })
+            Ok(Self {
+                path: TextPaths::Single(path),
+                callsigns,
+                time_format: None,
+                schedule: Default::default(),
+                notices: vec![],
+            })
         } else {
             Err(crate::connection::ConnectionError::FailedToEstablish {
                 connection: path,
@@ -24,16 +112,13 @@ impl AprsTextFile {
     }
 }
 
-fn read_lines(path: &String) -> Result<Vec<String>, crate::connection::ConnectionError> {
-    let mut lines: Vec<String> = vec![];
-    if std::path::Path::new(path).exists() {
-        match std::fs::File::open(path) {
-            Ok(file) => {
-                let reader = std::io::BufReader::new(file);
-                for line in reader.lines() {
-                    lines.push(line.unwrap());
-                }
-            }
+fn read_lines(
+    path: &String,
+    http: &crate::configuration::HttpConfiguration,
+) -> Result<Vec<String>, crate::connection::ConnectionError> {
+    let bytes = if std::path::Path::new(path).exists() {
+        match std::fs::read(path) {
+            Ok(bytes) => bytes,
             Err(error) => {
                 return Err(crate::connection::ConnectionError::FailedToEstablish {
                     connection: path.to_owned(),
@@ -44,7 +129,17 @@ fn read_lines(path: &String) -> Result<Vec<String>, crate::connection::Connectio
     } else {
         match url::Url::parse(path) {
             Ok(url) => {
-                let response = match reqwest::blocking::get(url.to_owned()) {
+                let client = crate::connection::http_client(http).map_err(|message| {
+                    crate::connection::ConnectionError::FailedToEstablish {
+                        connection: url.to_string(),
+                        message,
+                    }
+                })?;
+                let response = match crate::connection::send_with_retry(
+                    client.get(url.to_owned()),
+                    *crate::connection::RETRY_ATTEMPTS,
+                    *crate::connection::RETRY_BACKOFF,
+                ) {
                     Ok(response) => response,
                     Err(error) => {
                         return Err(crate::connection::ConnectionError::ReadFailure {
@@ -54,18 +149,14 @@ fn read_lines(path: &String) -> Result<Vec<String>, crate::connection::Connectio
                     }
                 };
 
-                let text = match response.text() {
-                    Ok(text) => text,
+                match response.bytes() {
+                    Ok(bytes) => bytes.to_vec(),
                     Err(error) => {
                         return Err(crate::connection::ConnectionError::ReadFailure {
                             connection: url.to_string(),
                             message: error.to_string(),
                         })
                     }
-                };
-
-                for line in text.split('\n') {
-                    lines.push(line.to_string());
                 }
             }
             Err(error) => {
@@ -75,61 +166,147 @@ fn read_lines(path: &String) -> Result<Vec<String>, crate::connection::Connectio
                 });
             }
         }
+    };
+
+    let text = decompress(path, bytes)?;
+    Ok(text.lines().map(|line| line.to_string()).collect())
+}
+
+// decompress gzipped files, recognized by their extension or magic bytes
+fn decompress(path: &str, bytes: Vec<u8>) -> Result<String, crate::connection::ConnectionError> {
+    if path.ends_with(".gz") || bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut decompressed = vec![];
+        if let Err(error) =
+            flate2::read::MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)
+        {
+            return Err(crate::connection::ConnectionError::ReadFailure {
+                connection: path.to_owned(),
+                message: format!("could not decompress - {:}", error),
+            });
+        }
+        Ok(String::from_utf8_lossy(&decompressed).to_string())
+    } else {
+        Ok(String::from_utf8_lossy(&bytes).to_string())
     }
+}
 
-    Ok(lines)
+// parse the time prepended to a line with the given format, falling back to the default format, RFC 3339, and epoch seconds
+fn parse_line_time(
+    prefix: &str,
+    time_format: Option<&str>,
+) -> Option<chrono::DateTime<chrono::Local>> {
+    let prefix = prefix.trim();
+
+    let mut formats = vec![];
+    if let Some(time_format) = time_format {
+        formats.push(time_format);
+    }
+    formats.push("%Y-%m-%d %H:%M:%S %Z");
+
+    for format in formats {
+        if let Ok(time) = chrono::DateTime::parse_from_str(prefix, format) {
+            return Some(time.with_timezone(&chrono::Local));
+        }
+        if let Ok(time) = chrono::NaiveDateTime::parse_from_str(prefix, format) {
+            if let chrono::LocalResult::Single(time) = time.and_local_timezone(chrono::Local) {
+                return Some(time);
+            }
+        }
+    }
+
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(prefix) {
+        return Some(time.with_timezone(&chrono::Local));
+    }
+
+    if let Ok(seconds) = prefix.parse::<f64>() {
+        if let chrono::LocalResult::Single(time) =
+            chrono::Local.timestamp_opt(seconds.trunc() as i64, (seconds.fract() * 1e9) as u32)
+        {
+            return Some(time);
+        }
+    }
+
+    None
 }
 
 impl AprsTextFile {
+    // messages from reading since the last call, i.e. files in the list that could not be read
+    pub fn take_notices(&mut self) -> Vec<(String, log::Level)> {
+        std::mem::take(&mut self.notices)
+    }
+
     pub fn read_aprs_from_file(
-        &self,
+        &mut self,
+        http: &crate::configuration::HttpConfiguration,
     ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
-        let lines = match read_lines(&self.path) {
-            Ok(lines) => lines,
-            Err(error) => match error {
-                crate::connection::ConnectionError::FailedToEstablish { .. } => {
-                    return Err(error);
-                }
-                _ => {
-                    return Err(crate::connection::ConnectionError::FailedToEstablish {
+        let paths = self.path.expanded();
+        if paths.is_empty() {
+            return Err(crate::connection::ConnectionError::FailedToEstablish {
+                connection: self.path.to_string(),
+                message: "no files match".to_string(),
+            });
+        }
+
+        // a file that cannot be read is skipped, unless none of the files can be read
+        let mut lines = vec![];
+        let mut errors = vec![];
+        for path in &paths {
+            match read_lines(path, http) {
+                Ok(path_lines) => lines.extend(path_lines),
+                Err(error) => errors.push(match error {
+                    crate::connection::ConnectionError::FailedToEstablish { .. } => error,
+                    _ => crate::connection::ConnectionError::FailedToEstablish {
                         connection: "file".to_string(),
                         message: error.to_string(),
-                    })
-                }
-            },
-        };
+                    },
+                }),
+            }
+        }
+        if errors.len() == paths.len() {
+            return Err(errors.remove(0));
+        }
+        for error in errors {
+            self.notices
+                .push((format!("{:}; skipped it", error), log::Level::Warn));
+        }
 
+        let filter = self
+            .callsigns
+            .as_ref()
+            .map(|callsigns| crate::connection::CallsignFilter::new(callsigns));
         let mut locations: Vec<crate::location::BalloonLocation> = vec![];
         for line in lines {
-            let frame;
-            let time;
-            if line.contains(": ") {
-                let mut parts = vec![];
-                parts.extend(line.splitn(2, ": "));
-                time = match chrono::NaiveDateTime::parse_from_str(parts[0], "%Y-%m-%d %H:%M:%S %Z")
+            let parse = |frame: &str, time| {
+                crate::location::BalloonLocation::from_aprs_frame(frame.as_bytes(), time)
+            };
+            // without a recognizable time before the frame, use the time in the packet itself;
+            // the prefix is either a time in an unknown format or part of the frame
+            let location = match line.split_once(": ") {
+                Some((prefix, frame)) => match parse_line_time(prefix, self.time_format.as_deref())
                 {
-                    Ok(time) => Some(time.and_local_timezone(chrono::Local).unwrap()),
-                    Err(_) => None,
-                };
-                frame = parts[1];
-            } else {
-                frame = &line;
-                time = None;
-            }
-            let location =
-                match crate::location::BalloonLocation::from_aprs_frame(frame.as_bytes(), time) {
-                    Ok(location) => location,
-                    Err(_) => continue,
-                };
+                    Some(time) => parse(frame, Some(time)),
+                    None => parse(&line, None).or_else(|_| parse(frame, None)),
+                },
+                None => parse(&line, None),
+            };
+            let location = match location {
+                Ok(location) => location,
+                Err(_) => continue,
+            };
 
-            if let Some(callsigns) = &self.callsigns {
-                if !callsigns.contains(&location.data.callsign.to_owned().unwrap()) {
+            if let Some(filter) = &filter {
+                if !filter.matches(location.data.callsign.as_deref().unwrap()) {
                     continue;
                 }
             }
 
             locations.push(location);
         }
+
+        // logs from several stations overlap in time
+        if paths.len() > 1 {
+            locations.sort_by_key(|location| location.location.time);
+        }
         Ok(locations)
     }
 }
@@ -137,12 +314,17 @@ impl AprsTextFile {
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub struct GeoJsonFile {
     pub path: String,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
 }
 
 impl GeoJsonFile {
     pub fn new(path: String) -> Result<Self, crate::connection::ConnectionError> {
         if std::path::Path::new(&path).exists() || url::Url::parse(&path).is_ok() {
-            Ok(Self { path })
+            Ok(Self {
+                path,
+                schedule: Default::default(),
+            })
         } else {
             Err(crate::connection::ConnectionError::FailedToEstablish {
                 connection: path,
@@ -155,8 +337,9 @@ impl GeoJsonFile {
 impl GeoJsonFile {
     pub fn read_locations_from_geojson(
         &self,
+        http: &crate::configuration::HttpConfiguration,
     ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
-        let lines = read_lines(&self.path).unwrap();
+        let lines = read_lines(&self.path, http)?;
         let contents = lines.join("\n");
         let parsed = match contents.parse::<geojson::GeoJson>() {
             Ok(parsed) => parsed,
@@ -231,11 +414,11 @@ impl GeoJsonFile {
                                 },
                                 via: vec![],
                                 data: aprs_parser::AprsData::Position(aprs_parser::AprsPosition {
-                                    to: match properties.get("to").unwrap() {
-                                        serde_json::Value::String(callsign) => {
+                                    to: match properties.get("to") {
+                                        Some(serde_json::Value::String(callsign)) => {
                                             aprs_parser::Callsign::new(callsign).unwrap()
                                         }
-                                        _ => continue,
+                                        _ => aprs_parser::Callsign::new("APRS").unwrap(),
                                     },
                                     timestamp: aprs_parser::Timestamp::new_hms(
                                         time.hour() as u8,
@@ -243,8 +426,8 @@ impl GeoJsonFile {
                                         time.second() as u8,
                                     ),
                                     messaging_supported: false,
-                                    latitude: aprs_parser::Latitude::new(point[0]).unwrap(),
-                                    longitude: aprs_parser::Longitude::new(point[1]).unwrap(),
+                                    latitude: aprs_parser::Latitude::new(point[1]).unwrap(),
+                                    longitude: aprs_parser::Longitude::new(point[0]).unwrap(),
                                     precision: aprs_parser::Precision::HundredthMinute,
                                     symbol_table: '/',
                                     symbol_code: 'O',
@@ -263,14 +446,19 @@ impl GeoJsonFile {
                                 altitude,
                             },
                             data: crate::location::BalloonData::new(
-                                None,
+                                match properties.get("callsign") {
+                                    Some(serde_json::Value::String(callsign)) => {
+                                        Some(callsign.to_owned())
+                                    }
+                                    _ => None,
+                                },
                                 aprs_packet,
                                 None,
                                 match properties.get("raw") {
                                     Some(serde_json::Value::String(raw)) => Some(raw.to_owned()),
                                     _ => None,
                                 },
-                                crate::location::LocationSource::TextFile(self.path.to_owned()),
+                                crate::location::LocationSource::GeoJsonFile(self.path.to_owned()),
                             ),
                         };
                         locations.push(location);
@@ -283,50 +471,37 @@ impl GeoJsonFile {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    #[ignore]
-    fn test_aprs_from_url() {
-        let url = "http://bpp.umd.edu/archives/Launches/NS-111_2022_07_31/APRS/W3EAX-11%20raw.txt"
-            .to_string();
-
-        let connection = AprsTextFile::new(url, None).unwrap();
-
-        let packets = connection.read_aprs_from_file().unwrap();
-
-        assert!(!packets.is_empty());
-    }
-
-    #[test]
-    fn test_aprs_from_file() {
-        let path = format!(
-            "{:}/{:}",
-            env!("CARGO_MANIFEST_DIR"),
-            "data/aprs/W3EAX-8_raw_NS-111.txt"
-        );
-
-        let connection = AprsTextFile::new(path, None).unwrap();
-
-        let packets = connection.read_aprs_from_file().unwrap();
-
-        assert!(!packets.is_empty());
-    }
-}
-
 pub fn locations_geojson_featurecollection(
     locations: Vec<&crate::location::BalloonLocation>,
 ) -> geojson::FeatureCollection {
     let features: Vec<geojson::Feature> = locations
         .iter()
         .map(|location| {
-            let geometry = geojson::Geometry::new(geojson::Value::Point(vec![
-                location.location.coord.x,
-                location.location.coord.y,
-            ]));
+            let mut point = vec![location.location.coord.x, location.location.coord.y];
+            if let Some(altitude) = location.location.altitude {
+                point.push(altitude);
+            }
+            let geometry = geojson::Geometry::new(geojson::Value::Point(point));
+
             let mut properties = geojson::JsonObject::new();
+            properties.insert(
+                "time".to_string(),
+                geojson::JsonValue::String(
+                    location.location.time.format("%Y%m%d%H%M%S").to_string(),
+                ),
+            );
+            if let Some(callsign) = &location.data.callsign {
+                properties.insert(
+                    "callsign".to_string(),
+                    geojson::JsonValue::String(callsign.to_owned()),
+                );
+            }
+            if let Some(raw) = &location.data.raw {
+                properties.insert(
+                    "raw".to_string(),
+                    geojson::JsonValue::String(raw.to_owned()),
+                );
+            }
             if let Some(aprs_packet) = &location.data.aprs_packet {
                 properties.insert(
                     "from".to_string(),
@@ -359,3 +534,708 @@ pub fn locations_geojson_featurecollection(
 
     geojson::FeatureCollection::from_iter(features)
 }
+
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct CsvFile {
+    pub path: String,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+}
+
+impl CsvFile {
+    pub fn new(path: String) -> Result<Self, crate::connection::ConnectionError> {
+        if std::path::Path::new(&path).exists() || url::Url::parse(&path).is_ok() {
+            Ok(Self {
+                path,
+                schedule: Default::default(),
+            })
+        } else {
+            Err(crate::connection::ConnectionError::FailedToEstablish {
+                connection: path,
+                message: "path does not exist".to_string(),
+            })
+        }
+    }
+
+    pub fn read_locations_from_csv(
+        &self,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
+        let failed = |message: String| crate::connection::ConnectionError::ReadFailure {
+            connection: self.path.to_owned(),
+            message,
+        };
+
+        let lines = read_lines(&self.path, http)?;
+        let contents = lines.join("\n");
+        let mut reader = csv::Reader::from_reader(contents.as_bytes());
+
+        let mut locations: Vec<crate::location::BalloonLocation> = vec![];
+        for record in reader.deserialize::<CsvRecord>() {
+            let record = record.map_err(|error| failed(error.to_string()))?;
+
+            let time = chrono::DateTime::parse_from_rfc3339(&record.time)
+                .map_err(|error| failed(format!("{:} - {:}", record.time, error)))?
+                .with_timezone(&chrono::Local);
+
+            let mut data = crate::location::BalloonData::new(
+                record.callsign,
+                None,
+                None,
+                None,
+                crate::location::LocationSource::TextFile(self.path.to_owned()),
+            );
+            if let Some(comment) = &record.comment {
+                data.telemetry = crate::location::telemetry::Telemetry::from_comment(comment);
+                data.phase = crate::location::phase::FlightPhase::from_text(comment);
+            }
+
+            locations.push(crate::location::BalloonLocation {
+                location: crate::location::Location {
+                    time,
+                    coord: geo::coord! { x: record.longitude, y: record.latitude },
+                    altitude: record.altitude,
+                },
+                data,
+            });
+        }
+
+        Ok(locations)
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct CsvRecord {
+    time: String,
+    callsign: Option<String>,
+    longitude: f64,
+    latitude: f64,
+    altitude: Option<f64>,
+    source: String,
+    comment: Option<String>,
+}
+
+// one row per location, with columns time, callsign, longitude, latitude, altitude, source, and comment
+pub fn locations_csv(locations: Vec<&crate::location::BalloonLocation>) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for location in locations {
+        let comment = match &location.data.aprs_packet {
+            Some(aprs_packet) => match &aprs_packet.data {
+                aprs_parser::AprsData::Position(data) => {
+                    Some(String::from_utf8_lossy(&data.comment).to_string())
+                }
+                aprs_parser::AprsData::MicE(data) => {
+                    Some(String::from_utf8_lossy(&data.comment).to_string())
+                }
+                _ => None,
+            },
+            None => None,
+        };
+
+        writer
+            .serialize(CsvRecord {
+                time: location.location.time.to_rfc3339(),
+                callsign: location.data.callsign.to_owned(),
+                longitude: location.location.coord.x,
+                latitude: location.location.coord.y,
+                altitude: location.location.altitude,
+                source: format!("{:?}", location.data.source),
+                comment,
+            })
+            .unwrap();
+    }
+
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+/// GPX file written by `locations_gpx`, read back as the points of each track
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct GpxFile {
+    pub path: String,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+}
+
+impl GpxFile {
+    // the points of each track, named by callsign; waypoints only repeat the last and predicted positions
+    pub fn read_locations_from_gpx(
+        &self,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
+        let failed = |message: String| crate::connection::ConnectionError::ReadFailure {
+            connection: self.path.to_owned(),
+            message,
+        };
+
+        let lines = read_lines(&self.path, http)?;
+        let contents = lines.join("\n");
+        let gpx = gpx::read(contents.as_bytes()).map_err(|error| failed(error.to_string()))?;
+
+        let mut locations: Vec<crate::location::BalloonLocation> = vec![];
+        for track in gpx.tracks {
+            for point in track.segments.iter().flat_map(|segment| &segment.points) {
+                let time = point
+                    .time
+                    .ok_or_else(|| failed(String::from("track point has no time")))?
+                    .format()
+                    .map_err(|error| failed(error.to_string()))?;
+                let time = chrono::DateTime::parse_from_rfc3339(&time)
+                    .map_err(|error| failed(format!("{:} - {:}", time, error)))?
+                    .with_timezone(&chrono::Local);
+
+                locations.push(crate::location::BalloonLocation {
+                    location: crate::location::Location {
+                        time,
+                        coord: point.point().0,
+                        altitude: point.elevation,
+                    },
+                    data: crate::location::BalloonData::new(
+                        track.name.to_owned(),
+                        None,
+                        None,
+                        None,
+                        crate::location::LocationSource::TextFile(self.path.to_owned()),
+                    ),
+                });
+            }
+        }
+
+        Ok(locations)
+    }
+}
+
+// connection to read back locations written by `write_locations`
+pub fn locations_file_connection(path: &std::path::Path) -> crate::connection::Connection {
+    let path = format!("{:}", path.to_string_lossy());
+    if path.ends_with(".csv") {
+        crate::connection::Connection::CsvFile(CsvFile {
+            path,
+            schedule: Default::default(),
+        })
+    } else if path.ends_with(".gpx") {
+        crate::connection::Connection::GpxFile(GpxFile {
+            path,
+            schedule: Default::default(),
+        })
+    } else {
+        crate::connection::Connection::GeoJsonFile(GeoJsonFile {
+            path,
+            schedule: Default::default(),
+        })
+    }
+}
+
+// one `<trk>` per track, with waypoints at the last known position and predicted landing of each
+pub fn locations_gpx(tracks: Vec<&crate::location::track::BalloonTrack>) -> String {
+    let mut waypoints = String::new();
+    let mut gpx_tracks = String::new();
+    for track in tracks {
+        if let Some(last_location) = track.locations.last() {
+            waypoints += &gpx_point(
+                "wpt",
+                last_location,
+                Some(&format!("{:} last position", track.name)),
+            );
+        }
+        if let Some(predicted_landing_location) = track
+            .prediction
+            .as_ref()
+            .and_then(|prediction| prediction.last())
+        {
+            waypoints += &gpx_point(
+                "wpt",
+                predicted_landing_location,
+                Some(&format!("{:} predicted landing", track.name)),
+            );
+        }
+
+        gpx_tracks += &format!(
+            "  <trk>\n    <name>{}</name>\n    <trkseg>\n",
+            xml_escape(&track.name)
+        );
+        for location in &track.locations {
+            gpx_tracks += &format!("    {:}", gpx_point("trkpt", location, None));
+        }
+        gpx_tracks += "    </trkseg>\n  </trk>\n";
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gpx version=\"1.1\" creator=\"packetraven {:}\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n{:}{}</gpx>\n",
+        env!("CARGO_PKG_VERSION"),
+        waypoints,
+        gpx_tracks,
+    )
+}
+
+fn gpx_point(
+    tag: &str,
+    location: &crate::location::BalloonLocation,
+    name: Option<&String>,
+) -> String {
+    let mut point = format!(
+        "  <{} lat=\"{:}\" lon=\"{:}\">",
+        tag, location.location.coord.y, location.location.coord.x
+    );
+    if let Some(altitude) = location.location.altitude {
+        point += &format!("<ele>{}</ele>", altitude);
+    }
+    point += &format!(
+        "<time>{}</time>",
+        location
+            .location
+            .time
+            .with_timezone(&chrono::Utc)
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    );
+    if let Some(name) = name {
+        point += &format!("<name>{}</name>", xml_escape(name));
+    }
+    point + &format!("</{}>\n", tag)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// write locations as CSV if the path ends with `.csv`, GPX if `.gpx`, otherwise as GeoJSON
+pub fn write_locations(
+    path: &std::path::Path,
+    locations: Vec<&crate::location::BalloonLocation>,
+) -> std::io::Result<()> {
+    let contents = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("csv") => locations_csv(locations),
+        Some("gpx") => {
+            let mut track = crate::location::track::BalloonTrack::new(String::from("locations"));
+            track.locations = locations.into_iter().cloned().collect();
+            locations_gpx(vec![&track])
+        }
+        _ => locations_geojson_featurecollection(locations).to_string(),
+    };
+    std::fs::write(path, contents)
+}
+
+// CSV of each track interpolated to the given interval
+pub fn write_resampled_tracks(
+    path: &std::path::Path,
+    tracks: &[crate::location::track::BalloonTrack],
+    interval: chrono::Duration,
+) -> std::io::Result<()> {
+    let resampled: Vec<crate::location::BalloonLocation> = tracks
+        .iter()
+        .flat_map(|track| track.resample(interval))
+        .collect();
+    std::fs::write(path, locations_csv(resampled.iter().collect()))
+}
+
+// write tracks to the given path, by extension; GPX output keeps each track separate
+pub fn write_tracks(
+    path: &std::path::Path,
+    tracks: &[crate::location::track::BalloonTrack],
+) -> std::io::Result<()> {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("gpx") => std::fs::write(path, locations_gpx(tracks.iter().collect())),
+        _ => write_locations(
+            path,
+            tracks
+                .iter()
+                .flat_map(|track| track.locations.iter())
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test_aprs_from_url() {
+        let url = "http://bpp.umd.edu/archives/Launches/NS-111_2022_07_31/APRS/W3EAX-11%20raw.txt"
+            .to_string();
+
+        let mut connection = AprsTextFile::new(url, None).unwrap();
+
+        let packets = connection.read_aprs_from_file(&Default::default()).unwrap();
+
+        assert!(!packets.is_empty());
+    }
+
+    #[test]
+    fn test_aprs_from_file() {
+        let path = format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        );
+
+        let mut connection = AprsTextFile::new(path, None).unwrap();
+
+        let packets = connection.read_aprs_from_file(&Default::default()).unwrap();
+
+        assert!(!packets.is_empty());
+    }
+
+    #[test]
+    fn test_aprs_callsign_pattern() {
+        let path = std::env::temp_dir().join(format!(
+            "packetraven_test_aprs_callsign_pattern_{:}.txt",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            [
+                "W3EAX-8>APLIGA,WIDE2,qAR,W4VA-10:/141737h3920.08N/07745.08WO103/019/A=007931",
+                "W3EAX-13>APLIGA,WIDE2,qAR,W4VA-10:/142009h3920.08N/07744.15WO083/019/A=010648",
+                "KC3SKW-9>APLIGA,WIDE2,qAR,W4VA-10:/142009h3920.08N/07744.15WO083/019/A=010648",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let mut connection = AprsTextFile::new(
+            path.to_str().unwrap().to_string(),
+            Some(vec!["W3EAX-*".to_string()]),
+        )
+        .unwrap();
+        let packets = connection.read_aprs_from_file(&Default::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let callsigns: Vec<String> = packets
+            .into_iter()
+            .filter_map(|packet| packet.data.callsign)
+            .collect();
+        assert_eq!(callsigns, vec!["W3EAX-8", "W3EAX-13"]);
+    }
+
+    #[test]
+    fn test_aprs_from_multiple_files() {
+        let fixture = std::fs::read_to_string(format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        ))
+        .unwrap();
+        let lines: Vec<&str> = fixture.lines().filter(|line| !line.is_empty()).collect();
+
+        // the same flight heard by two stations, with alternating packets in each log
+        let directory = std::env::temp_dir().join(format!(
+            "packetraven_test_aprs_from_multiple_files_{:}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        for (station, offset) in [("W4VA-10", 0), ("N3FYI-2", 1)] {
+            std::fs::write(
+                directory.join(format!("{:}.txt", station)),
+                lines
+                    .iter()
+                    .skip(offset)
+                    .step_by(2)
+                    .copied()
+                    .collect::<Vec<&str>>()
+                    .join("\n"),
+            )
+            .unwrap();
+        }
+
+        let single = AprsTextFile::new(
+            format!(
+                "{:}/{:}",
+                env!("CARGO_MANIFEST_DIR"),
+                "data/aprs/W3EAX-8_raw_NS-111.txt"
+            ),
+            None,
+        )
+        .unwrap()
+        .read_aprs_from_file(&Default::default())
+        .unwrap();
+
+        let mut connection =
+            AprsTextFile::new(directory.to_string_lossy().to_string(), None).unwrap();
+        for path in [
+            TextPaths::Single(directory.join("*.txt").to_string_lossy().to_string()),
+            TextPaths::Multiple(vec![
+                directory.join("W4VA-10.txt").to_string_lossy().to_string(),
+                directory.join("N3FYI-2.txt").to_string_lossy().to_string(),
+            ]),
+        ] {
+            connection.path = path;
+            assert_eq!(connection.path.expanded().len(), 2);
+
+            let packets = connection.read_aprs_from_file(&Default::default()).unwrap();
+            assert_eq!(packets.len(), single.len());
+            assert!(packets
+                .iter()
+                .all(|packet| packet.data.callsign.as_deref() == Some("W3EAX-8")));
+            assert!(packets
+                .windows(2)
+                .all(|pair| pair[0].location.time <= pair[1].location.time));
+        }
+
+        // a missing file is skipped with a notice
+        connection.path = TextPaths::Multiple(vec![
+            directory.join("W4VA-10.txt").to_string_lossy().to_string(),
+            directory.join("missing.txt").to_string_lossy().to_string(),
+        ]);
+        assert!(!connection
+            .read_aprs_from_file(&Default::default())
+            .unwrap()
+            .is_empty());
+        assert_eq!(connection.take_notices().len(), 1);
+
+        connection.path = TextPaths::Single(directory.join("*.log").to_string_lossy().to_string());
+        assert!(connection.read_aprs_from_file(&Default::default()).is_err());
+
+        // URLs are not wildcards
+        let url = "http://example.com/packets.txt?callsign=W3EAX-8";
+        assert_eq!(TextPaths::Single(url.to_string()).expanded(), vec![url]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_line_time() {
+        let expected = chrono::Local
+            .with_ymd_and_hms(2022, 7, 31, 10, 17, 43)
+            .unwrap();
+
+        // default format
+        assert_eq!(
+            parse_line_time("2022-07-31 10:17:43 EDT", None),
+            Some(expected)
+        );
+
+        // configured format
+        assert_eq!(
+            parse_line_time("31/07/2022 10:17:43", Some("%d/%m/%Y %H:%M:%S")),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_line_time("2022-07-31 10:17:43 EDT", Some("%d/%m/%Y %H:%M:%S")),
+            Some(expected)
+        );
+
+        // RFC 3339
+        assert_eq!(
+            parse_line_time(&expected.to_rfc3339(), None),
+            Some(expected)
+        );
+
+        // epoch seconds
+        assert_eq!(
+            parse_line_time(&expected.timestamp().to_string(), None),
+            Some(expected)
+        );
+
+        assert_eq!(parse_line_time("W3EAX-8>APRS", None), None);
+    }
+
+    #[test]
+    fn test_unrecognized_line_time() {
+        let frame = "W3EAX-8>APLIGA,WIDE2,qAR,W4VA-10:/141737h3920.08N/07745.08WO103/019/A=007931";
+        let path = std::env::temp_dir().join(format!(
+            "packetraven_test_unrecognized_line_time_{:}.txt",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            format!(
+                "Sun Jul 31 10:17:43 2022: {:}\n2022-07-31 10:17:43 EDT: {:}\n",
+                frame, frame
+            ),
+        )
+        .unwrap();
+
+        let locations = AprsTextFile::new(path.to_str().unwrap().to_string(), None)
+            .unwrap()
+            .read_aprs_from_file(&Default::default())
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // the line with an unrecognized time falls back to the time in the packet
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations[0].data.callsign, Some(String::from("W3EAX-8")));
+        assert_eq!(
+            locations[1].location.time,
+            chrono::Local
+                .with_ymd_and_hms(2022, 7, 31, 10, 17, 43)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_aprs_from_gzip_file() {
+        use std::io::Write;
+
+        let path = format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        );
+        let packets = AprsTextFile::new(path.to_owned(), None)
+            .unwrap()
+            .read_aprs_from_file(&Default::default())
+            .unwrap();
+
+        let output_path = std::env::temp_dir().join(format!(
+            "packetraven_test_aprs_from_gzip_file_{:}.txt.gz",
+            std::process::id()
+        ));
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&output_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&std::fs::read(&path).unwrap()).unwrap();
+        encoder.finish().unwrap();
+
+        let compressed_packets = AprsTextFile::new(output_path.to_str().unwrap().to_string(), None)
+            .unwrap()
+            .read_aprs_from_file(&Default::default())
+            .unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+
+        assert_eq!(compressed_packets.len(), packets.len());
+    }
+
+    #[test]
+    fn test_geojson_round_trip() {
+        let path = format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        );
+        let locations = AprsTextFile::new(path, None)
+            .unwrap()
+            .read_aprs_from_file(&Default::default())
+            .unwrap();
+        assert!(!locations.is_empty());
+
+        let output_path = std::env::temp_dir().join(format!(
+            "packetraven_test_geojson_round_trip_{:}.geojson",
+            std::process::id()
+        ));
+        std::fs::write(
+            &output_path,
+            locations_geojson_featurecollection(locations.iter().collect()).to_string(),
+        )
+        .unwrap();
+
+        let read_locations = GeoJsonFile::new(output_path.to_str().unwrap().to_string())
+            .unwrap()
+            .read_locations_from_geojson(&Default::default())
+            .unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+
+        assert_eq!(read_locations.len(), locations.len());
+        for (read_location, location) in read_locations.iter().zip(locations.iter()) {
+            assert_eq!(read_location.location, location.location);
+            assert_eq!(read_location.data.callsign, location.data.callsign);
+            assert_eq!(read_location.data.raw, location.data.raw);
+        }
+    }
+
+    #[test]
+    fn test_missing_geojson() {
+        // a file removed after the connection was configured
+        let file = GeoJsonFile {
+            path: String::from("nonexistent.geojson"),
+            schedule: Default::default(),
+        };
+        assert!(file
+            .read_locations_from_geojson(&Default::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let path = format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        );
+        let locations = AprsTextFile::new(path, None)
+            .unwrap()
+            .read_aprs_from_file(&Default::default())
+            .unwrap();
+
+        let output_path = std::env::temp_dir().join(format!(
+            "packetraven_test_csv_round_trip_{:}.csv",
+            std::process::id()
+        ));
+        write_locations(&output_path, locations.iter().collect()).unwrap();
+
+        let contents = std::fs::read_to_string(&output_path).unwrap();
+        assert!(contents.starts_with("time,callsign,longitude,latitude,altitude,source,comment\n"));
+
+        let mut connection = locations_file_connection(&output_path);
+        let read_locations = connection.retrieve_locations(&Default::default()).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+
+        assert_eq!(read_locations.len(), locations.len());
+        for (read_location, location) in read_locations.iter().zip(locations.iter()) {
+            assert_eq!(read_location.location, location.location);
+            assert_eq!(read_location.data.callsign, location.data.callsign);
+        }
+    }
+
+    #[test]
+    fn test_gpx() {
+        let path = format!(
+            "{:}/{:}",
+            env!("CARGO_MANIFEST_DIR"),
+            "data/aprs/W3EAX-8_raw_NS-111.txt"
+        );
+        let mut track = crate::location::track::BalloonTrack::new(String::from("W3EAX-8"));
+        for location in AprsTextFile::new(path, None)
+            .unwrap()
+            .read_aprs_from_file(&Default::default())
+            .unwrap()
+        {
+            track.push(location);
+        }
+        track.prediction = Some(vec![track.locations.first().unwrap().to_owned()]);
+
+        let parsed = gpx::read(locations_gpx(vec![&track]).as_bytes()).unwrap();
+
+        assert_eq!(parsed.waypoints.len(), 2);
+        assert_eq!(
+            parsed.waypoints[0].name,
+            Some(String::from("W3EAX-8 last position"))
+        );
+        assert_eq!(
+            parsed.waypoints[1].name,
+            Some(String::from("W3EAX-8 predicted landing"))
+        );
+
+        assert_eq!(parsed.tracks.len(), 1);
+        assert_eq!(parsed.tracks[0].name, Some(String::from("W3EAX-8")));
+        let points = &parsed.tracks[0].segments[0].points;
+        assert_eq!(points.len(), track.locations.len());
+        for (point, location) in points.iter().zip(track.locations.iter()) {
+            assert_eq!(point.point().x(), location.location.coord.x);
+            assert_eq!(point.point().y(), location.location.coord.y);
+            assert_eq!(point.elevation, location.location.altitude);
+            assert!(point.time.is_some());
+        }
+
+        // the output is read back on restart
+        let output_path = std::env::temp_dir().join(format!(
+            "packetraven_test_gpx_round_trip_{:}.gpx",
+            std::process::id()
+        ));
+        write_tracks(&output_path, std::slice::from_ref(&track)).unwrap();
+        let mut connection = locations_file_connection(&output_path);
+        let read_locations = connection.retrieve_locations(&Default::default()).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+
+        assert_eq!(read_locations.len(), track.locations.len());
+        for (read_location, location) in read_locations.iter().zip(track.locations.iter()) {
+            assert_eq!(read_location.location, location.location);
+            assert_eq!(read_location.data.callsign, Some(String::from("W3EAX-8")));
+        }
+    }
+}
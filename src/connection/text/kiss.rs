@@ -0,0 +1,110 @@
+// KISS framing used by hardware TNCs; see http://www.ax25.net/kiss.aspx
+
+const FEND: u8 = 0xC0;
+const FESC: u8 = 0xDB;
+const TFEND: u8 = 0xDC;
+const TFESC: u8 = 0xDD;
+
+// command byte of a data frame on the first TNC port
+const DATA_FRAME: u8 = 0x00;
+
+// split a stream of bytes into the AX.25 payloads of its complete data frames,
+// along with any trailing bytes belonging to a frame that has not finished yet
+pub fn decode_frames(data: &[u8]) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let last_delimiter = match data.iter().rposition(|byte| *byte == FEND) {
+        Some(index) => index,
+        None => return (vec![], data.to_vec()),
+    };
+
+    let frames = data[..last_delimiter]
+        .split(|byte| *byte == FEND)
+        .filter(|frame| !frame.is_empty())
+        .map(unescape)
+        // only data frames carry packets; the low nibble of the command byte is the command
+        .filter(|frame| {
+            frame
+                .first()
+                .is_some_and(|command| command & 0x0F == DATA_FRAME)
+        })
+        .map(|frame| frame[1..].to_vec())
+        .filter(|payload| !payload.is_empty())
+        .collect();
+
+    (frames, data[last_delimiter + 1..].to_vec())
+}
+
+// wrap an AX.25 payload in a KISS data frame
+pub fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![FEND, DATA_FRAME];
+    for byte in payload {
+        match *byte {
+            FEND => frame.extend_from_slice(&[FESC, TFEND]),
+            FESC => frame.extend_from_slice(&[FESC, TFESC]),
+            other => frame.push(other),
+        }
+    }
+    frame.push(FEND);
+    frame
+}
+
+fn unescape(frame: &[u8]) -> Vec<u8> {
+    let mut unescaped = Vec::with_capacity(frame.len());
+    let mut escaped = false;
+    for byte in frame {
+        if escaped {
+            unescaped.push(match *byte {
+                TFEND => FEND,
+                TFESC => FESC,
+                other => other,
+            });
+            escaped = false;
+        } else if *byte == FESC {
+            escaped = true;
+        } else {
+            unescaped.push(*byte);
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kiss_frames() {
+        let payload = vec![0x82, FEND, 0x01, FESC, 0x02];
+        let frame = encode_frame(&payload);
+        assert_eq!(
+            frame,
+            vec![FEND, DATA_FRAME, 0x82, FESC, TFEND, 0x01, FESC, TFESC, 0x02, FEND]
+        );
+
+        // a non-data (TX delay) frame, a data frame, and the start of another data frame
+        let mut stream = vec![FEND, 0x01, 0x32, FEND];
+        stream.extend(&frame);
+        stream.extend([FEND, DATA_FRAME, 0x82]);
+
+        let (frames, remainder) = decode_frames(&stream);
+        assert_eq!(frames, vec![payload]);
+        assert_eq!(remainder, vec![DATA_FRAME, 0x82]);
+    }
+
+    #[test]
+    fn test_kiss_aprs() {
+        let packet = aprs_parser::AprsPacket::decode_textual(
+            br"W3EAX-8>APRS,WIDE1-1,WIDE2-1:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu",
+        )
+        .unwrap();
+
+        let mut payload = vec![];
+        packet.encode_ax25(&mut payload).unwrap();
+
+        let (frames, remainder) = decode_frames(&encode_frame(&payload));
+        assert!(remainder.is_empty());
+        assert_eq!(
+            aprs_parser::AprsPacket::decode_ax25(&frames[0]).unwrap(),
+            packet
+        );
+    }
+}
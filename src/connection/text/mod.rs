@@ -1,9 +1,12 @@
 pub mod file;
 #[cfg(feature = "serial")]
+pub mod kiss;
+#[cfg(feature = "serial")]
 pub mod serial;
 
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum TextStream {
     AprsTextFile(file::AprsTextFile),
     GeoJsonFile(file::GeoJsonFile),
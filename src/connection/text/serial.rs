@@ -1,68 +1,250 @@
+use std::io::{Read, Write};
+
 lazy_static::lazy_static! {
     static ref DEFAULT_BAUD_RATE: u32 = 9600;
+    static ref READ_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+    static ref DETECTION_WINDOW: std::time::Duration = std::time::Duration::from_millis(1500);
+}
+
+// common TNC baud rates, in the order in which to try them
+const BAUD_RATES: [u32; 5] = [1200, 4800, 9600, 19200, 115200];
+
+fn default_beacon_interval() -> chrono::Duration {
+    chrono::Duration::minutes(10)
+}
+
+fn default_beacon_path() -> String {
+    String::from("WIDE1-1,WIDE2-1")
+}
+
+fn default_beacon_symbol() -> String {
+    // house
+    String::from("/-")
+}
+
+/// how frames are delimited on the serial port
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialMode {
+    /// one textual APRS frame per line
+    #[default]
+    Text,
+    /// binary AX.25 frames in KISS framing
+    Kiss,
+}
+
+/// baud rate of the serial port, or `auto` to detect it from the received packets
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, Copy, serde::Serialize)]
+#[serde(try_from = "BaudRateValue", into = "BaudRateValue")]
+pub enum BaudRate {
+    Fixed(u32),
+    Auto,
+}
+
+impl Default for BaudRate {
+    fn default() -> Self {
+        Self::Fixed(*DEFAULT_BAUD_RATE)
+    }
+}
+
+// a baud rate as written in the configuration, i.e. `9600` or `auto`
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+enum BaudRateValue {
+    Rate(u32),
+    Name(String),
+}
+
+impl TryFrom<BaudRateValue> for BaudRate {
+    type Error = String;
+
+    fn try_from(value: BaudRateValue) -> Result<Self, Self::Error> {
+        match value {
+            BaudRateValue::Rate(rate) => Ok(Self::Fixed(rate)),
+            BaudRateValue::Name(name) if name == "auto" => Ok(Self::Auto),
+            BaudRateValue::Name(name) => Err(format!(
+                "invalid baud rate \"{:}\"; expected a number or `auto`",
+                name
+            )),
+        }
+    }
+}
+
+impl From<BaudRate> for BaudRateValue {
+    fn from(baud_rate: BaudRate) -> Self {
+        match baud_rate {
+            BaudRate::Fixed(rate) => Self::Rate(rate),
+            BaudRate::Auto => Self::Name(String::from("auto")),
+        }
+    }
+}
+
+/// position report to periodically transmit through the serial TNC
+#[serde_with::serde_as]
+#[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
+pub struct SerialBeacon {
+    pub callsign: String,
+    #[serde(deserialize_with = "crate::location::deserialize_fixed_position")]
+    pub position: crate::location::Location,
+    #[serde(default = "default_beacon_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub interval: chrono::Duration,
+    #[serde(default = "default_beacon_path")]
+    pub path: String,
+    /// symbol table and code, i.e. `/-` for a house or `/>` for a car
+    #[serde(default = "default_beacon_symbol")]
+    pub symbol: String,
+    pub comment: Option<String>,
+}
+
+impl SerialBeacon {
+    pub fn packet(&self) -> Result<aprs_parser::AprsPacket, crate::connection::ConnectionError> {
+        let invalid = |message: String| crate::connection::ConnectionError::WriteFailure {
+            connection: format!("beacon {:}", self.callsign),
+            message,
+        };
+
+        let from = aprs_parser::Callsign::new(&self.callsign)
+            .ok_or_else(|| invalid(format!("invalid callsign \"{:}\"", self.callsign)))?;
+
+        let mut via = vec![];
+        for step in self.path.split(',').filter(|step| !step.is_empty()) {
+            via.push(
+                aprs_parser::Via::decode_textual(step.as_bytes())
+                    .ok_or_else(|| invalid(format!("invalid path \"{:}\"", self.path)))?,
+            );
+        }
+
+        let symbol: Vec<char> = self.symbol.chars().collect();
+        if symbol.len() != 2 {
+            return Err(invalid(format!("invalid symbol \"{:}\"", self.symbol)));
+        }
+
+        let mut comment = String::new();
+        if let Some(altitude) = self.position.altitude {
+            comment += &format!(
+                "/A={:06}",
+                (altitude / crate::location::aprs::M_PER_FT)
+                    .round()
+                    .max(0.0) as u32
+            );
+        }
+        if let Some(text) = &self.comment {
+            comment += text;
+        }
+
+        Ok(aprs_parser::AprsPacket {
+            from,
+            via,
+            data: aprs_parser::AprsData::Position(aprs_parser::AprsPosition {
+                to: aprs_parser::Callsign::new_no_ssid("APRS"),
+                timestamp: None,
+                messaging_supported: false,
+                latitude: aprs_parser::Latitude::new(self.position.coord.y)
+                    .ok_or_else(|| invalid(String::from("invalid latitude")))?,
+                longitude: aprs_parser::Longitude::new(self.position.coord.x)
+                    .ok_or_else(|| invalid(String::from("invalid longitude")))?,
+                precision: aprs_parser::Precision::HundredthMinute,
+                symbol_table: symbol[0],
+                symbol_code: symbol[1],
+                comment: comment.into_bytes(),
+                cst: aprs_parser::AprsCst::Uncompressed,
+            }),
+        })
+    }
 }
 
 #[derive(serde::Deserialize, Debug, PartialEq, Clone, serde::Serialize)]
 pub struct AprsSerial {
     #[serde(default = "first_available_port")]
     pub port: String,
-    #[serde(default = "default_baud_rate")]
-    pub baud_rate: u32,
+    #[serde(default)]
+    pub baud_rate: BaudRate,
+    /// baud rate detected from the received packets, if `baud_rate` is `auto`
+    #[serde(skip)]
+    pub detected_baud_rate: Option<u32>,
+    #[serde(default)]
+    pub mode: SerialMode,
     pub callsigns: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub schedule: crate::connection::ConnectionSchedule,
+    pub beacon: Option<SerialBeacon>,
+    /// when the beacon was last transmitted
+    #[serde(skip)]
+    pub last_beacon: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(skip)]
+    pub reader: SerialReader,
 }
 
 impl AprsSerial {
     pub fn new(
         port: Option<String>,
-        baud_rate: Option<u32>,
+        baud_rate: BaudRate,
         callsigns: Option<Vec<String>>,
     ) -> Result<Self, crate::connection::ConnectionError> {
-        let baud = baud_rate.unwrap_or(*DEFAULT_BAUD_RATE);
-        let mut port_name: Option<String> = None;
-        match port {
-            Some(name) => {
-                port_name = match serialport::new(&name, baud).open() {
-                    Ok(port) => port.name(),
-                    Err(error) => {
-                        return Err(crate::connection::ConnectionError::FailedToEstablish {
-                            connection: "serial".to_string(),
-                            message: format!("error connecting to port {:?} - {:}", &name, error,),
-                        });
-                    }
-                };
+        let port_names = match port {
+            Some(name) => vec![name],
+            None => match serialport::available_ports() {
+                Ok(ports) => ports.into_iter().map(|port| port.port_name).collect(),
+                Err(error) => {
+                    return Err(crate::connection::ConnectionError::FailedToEstablish {
+                        connection: "serial".to_string(),
+                        message: error.to_string(),
+                    })
+                }
+            },
+        };
+
+        let mut selected: Option<(String, Option<u32>)> = None;
+        if baud_rate == BaudRate::Auto {
+            // prefer the first port on which packets can be decoded at some baud rate
+            for name in &port_names {
+                if let Some(detected) = detect_baud_rate(name, SerialMode::default()) {
+                    selected = Some((name.to_owned(), Some(detected)));
+                    break;
+                }
             }
-            None => {
-                let available_ports = match serialport::available_ports() {
-                    Ok(ports) => ports,
-                    Err(error) => {
-                        return Err(crate::connection::ConnectionError::FailedToEstablish {
-                            connection: "serial".to_string(),
-                            message: error.to_string(),
-                        })
+        }
+        if selected.is_none() {
+            let baud = match baud_rate {
+                BaudRate::Fixed(rate) => rate,
+                BaudRate::Auto => *DEFAULT_BAUD_RATE,
+            };
+            let mut last_error = None;
+            // return the next available port
+            for name in &port_names {
+                match serialport::new(name, baud).open() {
+                    Ok(successful) => {
+                        selected = Some((successful.name().unwrap_or(name.to_owned()), None));
+                        break;
                     }
-                };
-
-                // return the next available port
-                for port in available_ports {
-                    let connection_attempt = serialport::new(port.port_name, baud).open();
-                    match connection_attempt {
-                        Ok(successful) => {
-                            port_name = successful.name();
-                            break;
-                        }
-                        Err(_) => {
-                            continue;
-                        }
+                    Err(error) => {
+                        last_error =
+                            Some(format!("error connecting to port {:?} - {:}", name, error))
                     }
                 }
             }
+            if selected.is_none() {
+                if let Some(message) = last_error {
+                    return Err(crate::connection::ConnectionError::FailedToEstablish {
+                        connection: "serial".to_string(),
+                        message,
+                    });
+                }
+            }
         }
 
-        if let Some(port_name) = port_name {
+        if let Some((port_name, detected_baud_rate)) = selected {
             Ok(Self {
                 port: port_name,
-                baud_rate: baud,
+                baud_rate,
+                detected_baud_rate,
+                mode: SerialMode::default(),
                 callsigns,
+                schedule: Default::default(),
+                beacon: None,
+                last_beacon: None,
+                reader: SerialReader::default(),
             })
         } else {
             Err(crate::connection::ConnectionError::FailedToEstablish {
@@ -72,68 +254,361 @@ impl AprsSerial {
         }
     }
 
+    // whether the baud rate is to be detected, but has not been yet
+    pub fn needs_detection(&self) -> bool {
+        self.baud_rate == BaudRate::Auto && self.detected_baud_rate.is_none()
+    }
+
+    // detect the baud rate of the port if it is `auto`, returning the detected rate
+    pub fn detect_baud_rate(&mut self) -> Option<u32> {
+        if !self.needs_detection() {
+            return None;
+        }
+        let detected = detect_baud_rate(&self.port, self.mode);
+        self.detected_baud_rate = detected;
+        detected
+    }
+
+    fn baud(&self) -> u32 {
+        match self.baud_rate {
+            BaudRate::Fixed(rate) => rate,
+            BaudRate::Auto => self.detected_baud_rate.unwrap_or(*DEFAULT_BAUD_RATE),
+        }
+    }
+
+    pub fn address(&self) -> String {
+        format!("{:}@{:}", self.port, self.baud())
+    }
+
+    // the open port, opening it if necessary
+    fn open_port(
+        &mut self,
+    ) -> Result<&mut Box<dyn serialport::SerialPort>, crate::connection::ConnectionError> {
+        if self.reader.port.is_none() {
+            let now = std::time::Instant::now();
+            if let Some(next_attempt) = self.reader.next_attempt {
+                if now < next_attempt {
+                    return Err(crate::connection::ConnectionError::FailedToEstablish {
+                        connection: self.address(),
+                        message: format!(
+                            "waiting {:}s to reconnect after {:} failed attempt(s)",
+                            (next_attempt - now).as_secs(),
+                            self.reader.failures
+                        ),
+                    });
+                }
+            }
+
+            let mut result = serialport::new(&self.port, self.baud())
+                .timeout(*READ_TIMEOUT)
+                .open();
+            if result.is_err() {
+                // the device may have come back under a different name after being unplugged
+                if let Some(name) = self.reader.usb.as_ref().and_then(find_usb_port) {
+                    if name != self.port {
+                        self.port = name;
+                        result = serialport::new(&self.port, self.baud())
+                            .timeout(*READ_TIMEOUT)
+                            .open();
+                    }
+                }
+            }
+
+            match result {
+                Ok(port) => {
+                    if self.reader.connected {
+                        self.reader.notices.push((
+                            format!("reconnected to serial port {:}", self.address()),
+                            log::Level::Info,
+                        ));
+                    }
+                    if self.reader.usb.is_none() {
+                        self.reader.usb = usb_info(&self.port);
+                    }
+                    self.reader.connected = true;
+                    self.reader.port = Some(port);
+                    self.reader.buffer.clear();
+                    self.reader.failures = 0;
+                    self.reader.next_attempt = None;
+                }
+                Err(error) => {
+                    self.reader.failures += 1;
+                    self.reader.next_attempt = Some(now + reconnect_delay(self.reader.failures));
+                    return Err(crate::connection::ConnectionError::FailedToEstablish {
+                        connection: self.address(),
+                        message: error.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(self.reader.port.as_mut().unwrap())
+    }
+
+    // messages about the connection since the last call, i.e. reconnections
+    pub fn take_notices(&mut self) -> Vec<(String, log::Level)> {
+        std::mem::take(&mut self.reader.notices)
+    }
+
+    pub fn write_aprs_frame(
+        &mut self,
+        packet: &aprs_parser::AprsPacket,
+    ) -> Result<(), crate::connection::ConnectionError> {
+        let address = self.address();
+        let failed = |message: String| crate::connection::ConnectionError::WriteFailure {
+            connection: address.to_owned(),
+            message,
+        };
+
+        let mut frame = vec![];
+        match self.mode {
+            SerialMode::Text => {
+                packet
+                    .encode_textual(&mut frame)
+                    .map_err(|error| failed(error.to_string()))?;
+                frame.extend_from_slice(b"\r\n");
+            }
+            SerialMode::Kiss => {
+                let mut payload = vec![];
+                packet
+                    .encode_ax25(&mut payload)
+                    .map_err(|error| failed(error.to_string()))?;
+                frame = super::kiss::encode_frame(&payload);
+            }
+        }
+
+        let port = self.open_port()?;
+        let result = port.write_all(&frame).and_then(|_| port.flush());
+        if let Err(error) = result {
+            // reopen the port on the next access
+            self.reader.port = None;
+            return Err(failed(error.to_string()));
+        }
+        Ok(())
+    }
+
+    // transmit the configured beacon if its interval has elapsed; a failed beacon is logged and
+    // retried after the next interval, without interrupting reading
+    fn beacon_if_due(&mut self) {
+        if let Some(beacon) = &self.beacon {
+            let now = chrono::Local::now();
+            if self
+                .last_beacon
+                .is_none_or(|last_beacon| now - last_beacon >= beacon.interval)
+            {
+                let result = match beacon.packet() {
+                    Ok(packet) => self.write_aprs_frame(&packet),
+                    Err(error) => Err(error),
+                };
+                if let Err(error) = result {
+                    self.reader.notices.push((
+                        format!("could not transmit beacon - {:}", error),
+                        log::Level::Error,
+                    ));
+                }
+                self.last_beacon = Some(now);
+            }
+        }
+    }
+
     pub fn read_aprs_from_serial(
-        &self,
+        &mut self,
     ) -> Result<Vec<crate::location::BalloonLocation>, crate::connection::ConnectionError> {
-        let mut connection = match serialport::new(&self.port, self.baud_rate).open() {
-            Ok(connection) => connection,
+        self.beacon_if_due();
+
+        // read only the bytes that have already arrived, so that an idle TNC does not block
+        let port = self.open_port()?;
+        let result = port.bytes_to_read().and_then(|available| {
+            let mut chunk = vec![0; available as usize];
+            port.read_exact(&mut chunk)
+                .map(|_| chunk)
+                .map_err(serialport::Error::from)
+        });
+        match result {
+            Ok(chunk) => self.reader.buffer.extend(chunk),
             Err(error) => {
-                return Err(super::super::ConnectionError::FailedToEstablish {
-                    connection: format!("{:}@{:}", self.port, self.baud_rate),
+                // reopen the port on the next read
+                self.reader.port = None;
+                return Err(crate::connection::ConnectionError::ReadFailure {
+                    connection: self.address(),
                     message: error.to_string(),
                 });
             }
-        };
+        }
 
-        let mut buffer = Vec::<u8>::new();
-        match connection.read_to_end(&mut buffer) {
-            Ok(_) => Ok(buffer
-                .split(|a| a == &b'\n')
-                .filter_map(|line| {
-                    match crate::location::BalloonLocation::from_aprs_frame(line, None) {
-                        Ok(location) => {
-                            if let Some(callsigns) = &self.callsigns {
-                                if !callsigns.contains(
-                                    &location
-                                        .data
-                                        .aprs_packet
-                                        .to_owned()
-                                        .unwrap()
-                                        .from
-                                        .call()
-                                        .to_string(),
-                                ) {
-                                    return None;
-                                }
+        let filter = self
+            .callsigns
+            .as_ref()
+            .map(|callsigns| crate::connection::CallsignFilter::new(callsigns));
+        Ok(self
+            .reader
+            .take_frames(self.mode)
+            .iter()
+            .filter_map(|line| {
+                match crate::location::BalloonLocation::from_aprs_frame(line, None) {
+                    Ok(location) => {
+                        if let Some(filter) = &filter {
+                            if !filter
+                                .matches(location.data.aprs_packet.as_ref().unwrap().from.call())
+                            {
+                                return None;
                             }
-                            Some(location)
                         }
-                        Err(_) => None,
+                        Some(location)
                     }
-                })
-                .collect()),
-            Err(error) => Err(crate::connection::ConnectionError::ReadFailure {
-                connection: format!("{:}@{:}", self.port, self.baud_rate),
-                message: error.to_string(),
-            }),
+                    Err(_) => None,
+                }
+            })
+            .collect())
+    }
+}
+
+/// open serial port and the bytes received on it that do not yet form a complete frame
+#[derive(Default)]
+pub struct SerialReader {
+    port: Option<Box<dyn serialport::SerialPort>>,
+    buffer: Vec<u8>,
+    // whether the port has been opened before
+    connected: bool,
+    // USB identifiers of the port, with which to find it again if it is renamed
+    usb: Option<serialport::UsbPortInfo>,
+    // consecutive failed attempts to open the port, and when to try next
+    failures: u32,
+    next_attempt: Option<std::time::Instant>,
+    notices: Vec<(String, log::Level)>,
+}
+
+impl SerialReader {
+    // remove complete frames from the buffer, as textual APRS frames, leaving any partial frame for the next read
+    fn take_frames(&mut self, mode: SerialMode) -> Vec<Vec<u8>> {
+        match mode {
+            SerialMode::Text => {
+                let end = match self.buffer.iter().rposition(|byte| *byte == b'\n') {
+                    Some(index) => index,
+                    None => return vec![],
+                };
+                let frames = self.buffer[..end]
+                    .split(|byte| *byte == b'\n')
+                    .map(|line| line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+                    .filter(|line| !line.is_empty())
+                    .collect();
+                self.buffer.drain(..=end);
+                frames
+            }
+            SerialMode::Kiss => {
+                let (payloads, remainder) = super::kiss::decode_frames(&self.buffer);
+                self.buffer = remainder;
+                payloads
+                    .iter()
+                    .filter_map(|payload| aprs_parser::AprsPacket::decode_ax25(payload).ok())
+                    .filter_map(|packet| {
+                        let mut frame = vec![];
+                        packet.encode_textual(&mut frame).ok().map(|_| frame)
+                    })
+                    .collect()
+            }
         }
     }
 }
 
+// the port is reopened rather than shared by clones
+impl Clone for SerialReader {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for SerialReader {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for SerialReader {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("SerialReader")
+            .field("open", &self.port.is_some())
+            .field("buffered", &self.buffer.len())
+            .finish()
+    }
+}
+
 impl Default for AprsSerial {
     fn default() -> Self {
-        match Self::new(None, None, None) {
+        match Self::new(None, BaudRate::default(), None) {
             Ok(connection) => connection,
             Err(error) => panic!("{:}", error),
         }
     }
 }
 
+// try each common baud rate on the port, returning the first at which a decodable APRS frame arrives
+pub fn detect_baud_rate(port: &str, mode: SerialMode) -> Option<u32> {
+    for baud_rate in BAUD_RATES {
+        let mut connection = match serialport::new(port, baud_rate)
+            .timeout(*READ_TIMEOUT)
+            .open()
+        {
+            Ok(connection) => connection,
+            Err(_) => continue,
+        };
+
+        let mut reader = SerialReader::default();
+        let start = std::time::Instant::now();
+        while start.elapsed() < *DETECTION_WINDOW {
+            let mut chunk = [0; 256];
+            match connection.read(&mut chunk) {
+                Ok(length) => reader.buffer.extend(&chunk[..length]),
+                Err(error) if error.kind() == std::io::ErrorKind::TimedOut => {}
+                Err(_) => break,
+            }
+            if reader
+                .take_frames(mode)
+                .iter()
+                .any(|frame| aprs_parser::AprsPacket::decode_textual(frame).is_ok())
+            {
+                return Some(baud_rate);
+            }
+        }
+    }
+    None
+}
+
+// wait longer after each failed attempt to reopen a port, up to a minute
+fn reconnect_delay(failures: u32) -> std::time::Duration {
+    std::time::Duration::from_secs(2u64.pow(failures.saturating_sub(1).min(6)).min(60))
+}
+
+fn usb_info(port: &str) -> Option<serialport::UsbPortInfo> {
+    serialport::available_ports()
+        .ok()?
+        .into_iter()
+        .find(|available| available.port_name == port)
+        .and_then(|available| match available.port_type {
+            serialport::SerialPortType::UsbPort(info) => Some(info),
+            _ => None,
+        })
+}
+
+fn find_usb_port(usb: &serialport::UsbPortInfo) -> Option<String> {
+    serialport::available_ports()
+        .ok()?
+        .into_iter()
+        .find(|available| match &available.port_type {
+            serialport::SerialPortType::UsbPort(info) => {
+                info.vid == usb.vid
+                    && info.pid == usb.pid
+                    && info.serial_number == usb.serial_number
+            }
+            _ => false,
+        })
+        .map(|available| available.port_name)
+}
+
 fn first_available_port() -> String {
-    // TODO iterate over baud rates
+    // a baud rate of `auto` is detected after choosing the port
     match serialport::available_ports() {
         Ok(available_ports) => {
-            for available_port in available_ports {
+            if let Some(available_port) = available_ports.into_iter().next() {
                 let connection_attempt =
                     serialport::new(available_port.port_name, *DEFAULT_BAUD_RATE).open();
                 match connection_attempt {
@@ -153,6 +628,85 @@ fn first_available_port() -> String {
     }
 }
 
-fn default_baud_rate() -> u32 {
-    *DEFAULT_BAUD_RATE
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baud_rate() {
+        assert_eq!(
+            serde_yaml::from_str::<BaudRate>("4800").unwrap(),
+            BaudRate::Fixed(4800)
+        );
+        assert_eq!(
+            serde_yaml::from_str::<BaudRate>("auto").unwrap(),
+            BaudRate::Auto
+        );
+        assert!(serde_yaml::from_str::<BaudRate>("fast").is_err());
+        assert_eq!(serde_yaml::to_string(&BaudRate::Auto).unwrap(), "auto\n");
+        assert_eq!(BaudRate::default(), BaudRate::Fixed(9600));
+    }
+
+    #[test]
+    fn test_beacon_packet() {
+        let beacon: SerialBeacon = serde_yaml::from_str(
+            r#"
+            callsign: KC3SKW-1
+            position:
+              coord:
+                x: -76.9383
+                y: 38.9869
+              altitude: 30
+            comment: " PacketRaven"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(beacon.interval, chrono::Duration::minutes(10));
+
+        let mut frame = vec![];
+        beacon.packet().unwrap().encode_textual(&mut frame).unwrap();
+        assert_eq!(
+            String::from_utf8(frame.to_owned()).unwrap(),
+            "KC3SKW-1>APRS,WIDE1-1,WIDE2-1:!3859.21N/07656.30W-/A=000098 PacketRaven"
+        );
+
+        let location = crate::location::BalloonLocation::from_aprs_frame(&frame, None).unwrap();
+        assert!((location.location.coord.x - -76.9383).abs() < 0.001);
+        assert!((location.location.coord.y - 38.9869).abs() < 0.001);
+
+        let mut invalid = beacon.to_owned();
+        invalid.symbol = String::from("-");
+        assert!(invalid.packet().is_err());
+    }
+
+    #[test]
+    fn test_partial_frames() {
+        let mut reader = SerialReader::default();
+
+        reader
+            .buffer
+            .extend(b"W3EAX-8>APRS,WIDE1-1:!/:Gh=:j)#O   /A=026909|!Q|\r\nW3EAX-8>AP");
+        assert_eq!(
+            reader.take_frames(SerialMode::Text),
+            vec![b"W3EAX-8>APRS,WIDE1-1:!/:Gh=:j)#O   /A=026909|!Q|".to_vec()]
+        );
+        assert!(reader.take_frames(SerialMode::Text).is_empty());
+
+        reader
+            .buffer
+            .extend(b"RS,WIDE1-1:!/:Gh=:j)#O   /A=026909|!Q|\n");
+        assert_eq!(
+            reader.take_frames(SerialMode::Text),
+            vec![b"W3EAX-8>APRS,WIDE1-1:!/:Gh=:j)#O   /A=026909|!Q|".to_vec()]
+        );
+        assert!(reader.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_reconnect_delay() {
+        assert_eq!(reconnect_delay(1), std::time::Duration::from_secs(1));
+        assert_eq!(reconnect_delay(2), std::time::Duration::from_secs(2));
+        assert_eq!(reconnect_delay(4), std::time::Duration::from_secs(8));
+        assert_eq!(reconnect_delay(100), std::time::Duration::from_secs(60));
+    }
 }
@@ -2,6 +2,7 @@
 #[derive(serde::Deserialize, Clone, Debug, PartialEq, serde::Serialize)]
 pub struct AisData {
     pub mmsi: String,
+    pub vessel_name: Option<String>,
     pub imo: Option<String>,
     pub vesselclass: Option<String>,
     pub navstat: Option<String>,
@@ -1,21 +1,87 @@
 use chrono::Datelike;
 
-const M_PER_FT: f64 = 0.3048;
+pub const M_PER_FT: f64 = 0.3048;
 
-fn parse_aprs_comment_altitude_feet(comment: &str) -> Result<u32, ParseError> {
+// the standard `/A=` altitude in feet, or else an explicit altitude in meters (i.e. `a=10326.1`)
+fn parse_aprs_comment_altitude_feet(comment: &str) -> Result<f64, ParseError> {
     lazy_static::lazy_static! {
-        static ref PATTERN: regex::Regex =
-            regex::Regex::new(r"/A=(?P<altitude_feet>\d{6})").unwrap();
+        static ref FEET_PATTERN: regex::Regex =
+            regex::Regex::new(r"/A=(?P<altitude_feet>-\d{5}|\d{6})").unwrap();
+        static ref METERS_PATTERN: regex::Regex =
+            regex::Regex::new(r"(?:^|[/,\s])a=(?P<altitude_meters>-?\d+(?:\.\d+)?)").unwrap();
     }
-    let captures = match PATTERN.captures(comment) {
-        Some(captured_group) => captured_group,
-        None => {
-            return Err(ParseError::NoAltitudeInComment {
-                comment: comment.to_string(),
-            });
+    if let Some(captures) = FEET_PATTERN.captures(comment) {
+        return Ok(captures["altitude_feet"].parse::<f64>().unwrap());
+    }
+    if let Some(captures) = METERS_PATTERN.captures(comment) {
+        return Ok(captures["altitude_meters"].parse::<f64>().unwrap() / M_PER_FT);
+    }
+    Err(ParseError::NoAltitudeInComment {
+        comment: comment.to_string(),
+    })
+}
+
+// Mic-E altitude in meters, encoded as three base-91 characters followed by `}` at the start of the status text
+// (after the optional radio type character)
+fn parse_mic_e_altitude(comment: &[u8]) -> Option<f64> {
+    let start = match comment.first() {
+        Some(b'>' | b']' | b'`' | b'\'') => 1,
+        _ => 0,
+    };
+    if comment.get(start + 3) != Some(&b'}') {
+        return None;
+    }
+    let digits = &comment[start..start + 3];
+    if !digits.iter().all(|byte| (33..=123).contains(byte)) {
+        return None;
+    }
+    let value = digits
+        .iter()
+        .fold(0, |value, byte| value * 91 + (*byte as i64 - 33));
+    Some((value - 10000) as f64)
+}
+
+// the name and position of an object (`;NAME_____*DDHHMMz...`) or item (`)NAME!...`) report
+fn decode_object(
+    information: &[u8],
+    to: aprs_parser::Callsign,
+) -> Result<Option<(String, aprs_parser::AprsPosition)>, ParseError> {
+    let (name, live, body) = match information.first() {
+        Some(b';') => {
+            let name = information.get(1..10).ok_or(ParseError::NoPosition)?;
+            let state = information.get(10).ok_or(ParseError::NoPosition)?;
+            // an object's timestamp is mandatory
+            let mut body = vec![b'/'];
+            body.extend_from_slice(&information[11..]);
+            (name, *state == b'*', body)
+        }
+        Some(b')') => {
+            let end = information
+                .iter()
+                .take(11)
+                .skip(4)
+                .position(|byte| *byte == b'!' || *byte == b'_')
+                .ok_or(ParseError::NoPosition)?
+                + 4;
+            let mut body = vec![b'!'];
+            body.extend_from_slice(&information[end + 1..]);
+            (&information[1..end], information[end] == b'!', body)
         }
+        _ => return Ok(None),
     };
-    Ok(captures["altitude_feet"].parse::<u32>().unwrap())
+
+    let name = String::from_utf8_lossy(name).trim().to_string();
+    if !live {
+        return Err(ParseError::KilledObject { name });
+    }
+
+    match aprs_parser::AprsPosition::decode(&body, to) {
+        Ok(position) => Ok(Some((name, position))),
+        Err(error) => Err(ParseError::InvalidFrame {
+            error: error.to_string(),
+            frame: String::from_utf8_lossy(information).to_string(),
+        }),
+    }
 }
 
 impl crate::location::BalloonLocation {
@@ -26,35 +92,45 @@ impl crate::location::BalloonLocation {
         let packet_time: chrono::DateTime<chrono::Local>;
         let longitude: f64;
         let latitude: f64;
-        let altitude: f64;
+        let altitude: Option<f64>;
         let comment: String;
 
-        let packet = match aprs_parser::AprsPacket::decode_textual(frame) {
+        let mut packet = match aprs_parser::AprsPacket::decode_textual(frame) {
             Ok(packet) => packet,
             Err(error) => {
                 return Err(ParseError::InvalidFrame {
                     error: error.to_string(),
-                    frame: String::from_utf8(frame.to_vec()).unwrap(),
+                    frame: String::from_utf8_lossy(frame).to_string(),
                 });
             }
         };
+
+        // objects and items embed a position report, which is tracked under the object's name
+        let mut object_name = None;
+        if let aprs_parser::AprsData::Unknown(to) = &packet.data {
+            if let Some(information) = frame
+                .iter()
+                .position(|byte| *byte == b':')
+                .map(|index| &frame[index + 1..])
+            {
+                if let Some((name, position)) = decode_object(information, to.to_owned())? {
+                    packet.data = aprs_parser::AprsData::Position(position);
+                    object_name = Some(name);
+                }
+            }
+        }
+
         match &packet.data {
             aprs_parser::AprsData::Position(payload) => {
-                comment = String::from_utf8(payload.comment.to_owned()).unwrap();
-                let altitude_feet: f64;
-                match payload.cst {
-                    aprs_parser::AprsCst::CompressedSome { cs, .. } => match cs {
-                        aprs_parser::AprsCompressedCs::Altitude(compressed_altitude) => {
-                            altitude_feet = compressed_altitude.altitude_feet();
-                        }
-                        _ => {
-                            return Err(ParseError::NoAltitudeInCompressedData);
-                        }
-                    },
-                    aprs_parser::AprsCst::Uncompressed | aprs_parser::AprsCst::CompressedNone => {
-                        altitude_feet = parse_aprs_comment_altitude_feet(&comment).unwrap() as f64
-                    }
-                }
+                comment = String::from_utf8_lossy(&payload.comment).to_string();
+                // keep packets without an altitude (i.e. from a digipeater), so that they still show on the map
+                let altitude_feet = match payload.cst {
+                    aprs_parser::AprsCst::CompressedSome {
+                        cs: aprs_parser::AprsCompressedCs::Altitude(compressed_altitude),
+                        ..
+                    } => Some(compressed_altitude.altitude_feet()),
+                    _ => parse_aprs_comment_altitude_feet(&comment).ok(),
+                };
 
                 let now = chrono::offset::Utc::now();
                 match time {
@@ -106,13 +182,17 @@ impl crate::location::BalloonLocation {
                         .with_timezone(&chrono::Local);
                     }
                 }
-                altitude = altitude_feet * M_PER_FT;
+                altitude = altitude_feet.map(|altitude_feet| altitude_feet * M_PER_FT);
                 longitude = payload.longitude.value();
                 latitude = payload.latitude.value();
             }
             aprs_parser::AprsData::MicE(payload) => {
-                comment = String::from_utf8(payload.comment.clone()).unwrap();
-                let altitude_feet = parse_aprs_comment_altitude_feet(&comment).unwrap() as f64;
+                comment = String::from_utf8_lossy(&payload.comment).to_string();
+                let altitude_meters = parse_mic_e_altitude(&payload.comment).or_else(|| {
+                    parse_aprs_comment_altitude_feet(&comment)
+                        .ok()
+                        .map(|altitude_feet| altitude_feet * M_PER_FT)
+                });
 
                 match time {
                     Some(time) => {
@@ -125,7 +205,7 @@ impl crate::location::BalloonLocation {
                         }
                     },
                 }
-                altitude = altitude_feet * M_PER_FT;
+                altitude = altitude_meters;
                 longitude = payload.longitude.value();
                 latitude = payload.latitude.value();
             }
@@ -138,13 +218,13 @@ impl crate::location::BalloonLocation {
             location: super::Location {
                 time: packet_time,
                 coord: geo::coord! { x: longitude, y: latitude },
-                altitude: Some(altitude),
+                altitude,
             },
             data: crate::location::BalloonData::new(
-                None,
+                object_name,
                 Some(packet),
                 None,
-                Some(String::from_utf8(frame.to_vec()).unwrap()),
+                Some(String::from_utf8_lossy(frame).to_string()),
                 crate::location::LocationSource::None,
             ),
         })
@@ -158,6 +238,7 @@ custom_error::custom_error! {pub ParseError
     InvalidTimestamp  = "could not parse packet timestamp",
     NoAltitudeInComment {comment: String} = "comment does not contain an altitude; {comment}",
     NoAltitudeInCompressedData = "compressed data does not contain altitude",
+    KilledObject { name: String } = "object {name} was killed",
 }
 
 #[cfg(test)]
@@ -335,4 +416,95 @@ mod tests {
             _ => panic!("packet data not retrieved"),
         }
     }
+
+    #[test]
+    fn parse_altitude_variants() {
+        assert_eq!(
+            super::parse_aprs_comment_altitude_feet("322/103/A=003054").unwrap(),
+            3054.0
+        );
+        assert_eq!(
+            super::parse_aprs_comment_altitude_feet("/A=-00012").unwrap(),
+            -12.0
+        );
+        assert!(
+            (super::parse_aprs_comment_altitude_feet("a=10326.1/R=47").unwrap() * super::M_PER_FT
+                - 10326.1)
+                .abs()
+                < 1e-6
+        );
+        assert!(super::parse_aprs_comment_altitude_feet("/R=47").is_err());
+
+        // the standard form takes precedence
+        let frame = br"N1YIP-11>APZUME,WIDE2-1,qAR,KD1KE:/123115h4454.54N/06818.24WO097/034/A=033869!wYi!/a=10326.1/R=47";
+        let packet = crate::location::BalloonLocation::from_aprs_frame(frame, None).unwrap();
+        assert_eq!(packet.location.altitude.unwrap(), 33869.0 * super::M_PER_FT);
+    }
+
+    #[test]
+    fn parse_mic_e_altitude() {
+        // 1000 m is encoded as `">q}`
+        assert_eq!(super::parse_mic_e_altitude(br#"]">q}"#), Some(1000.0));
+        assert_eq!(super::parse_mic_e_altitude(br#"">q}"#), Some(1000.0));
+        assert_eq!(super::parse_mic_e_altitude(b"no altitude"), None);
+        assert_eq!(super::parse_mic_e_altitude(br#"balloon ">q}"#), None);
+
+        let packet = aprs_parser::AprsPacket {
+            from: aprs_parser::Callsign::new("W3EAX-9").unwrap(),
+            via: vec![],
+            data: aprs_parser::AprsData::MicE(aprs_parser::AprsMicE {
+                latitude: aprs_parser::Latitude::new(39.0).unwrap(),
+                longitude: aprs_parser::Longitude::new(-77.0).unwrap(),
+                precision: aprs_parser::Precision::HundredthMinute,
+                message: aprs_parser::mic_e::Message::M0,
+                speed: aprs_parser::mic_e::Speed::new(10).unwrap(),
+                course: aprs_parser::mic_e::Course::new(90).unwrap(),
+                symbol_table: b'/',
+                symbol_code: b'O',
+                comment: br#"]">q}balloon"#.to_vec(),
+                current: true,
+            }),
+        };
+        let mut frame = vec![];
+        packet.encode_textual(&mut frame).unwrap();
+
+        let location = crate::location::BalloonLocation::from_aprs_frame(&frame, None).unwrap();
+        assert_eq!(location.location.altitude, Some(1000.0));
+        assert!((location.location.coord.y - 39.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn parse_no_altitude() {
+        let frame = br"W3EAX-8>APRS,WIDE2-1,qAR,W4VA-10:!3920.08N/07745.08W#PHG5360 digipeater";
+        let packet = crate::location::BalloonLocation::from_aprs_frame(frame, None).unwrap();
+
+        assert_eq!(packet.location.altitude, None);
+        assert!((packet.location.coord.x - -77.7513).abs() < 0.001);
+    }
+
+    #[test]
+    fn parse_object() {
+        let frame = br"W3EAX>APRS,WIDE2-1:;NS-111   *141737h3920.08N/07745.08WO/A=007931 balloon";
+        let packet = crate::location::BalloonLocation::from_aprs_frame(frame, None).unwrap();
+
+        assert_eq!(packet.data.callsign, Some(String::from("NS-111")));
+        assert!((packet.location.coord.x - -77.7513).abs() < 0.001);
+        assert_eq!(packet.location.altitude.unwrap(), 7931.0 * super::M_PER_FT);
+        assert_eq!(
+            packet.location.time.with_timezone(&chrono::Utc).time(),
+            chrono::NaiveTime::from_hms_opt(14, 17, 37).unwrap()
+        );
+
+        let killed = br"W3EAX>APRS,WIDE2-1:;NS-111   _141737h3920.08N/07745.08WO/A=007931";
+        assert!(crate::location::BalloonLocation::from_aprs_frame(killed, None).is_err());
+    }
+
+    #[test]
+    fn parse_item() {
+        let frame = br"W3EAX>APRS,WIDE2-1:)BALLOON!3920.08N/07745.08WO/A=007931";
+        let packet = crate::location::BalloonLocation::from_aprs_frame(frame, None).unwrap();
+
+        assert_eq!(packet.data.callsign, Some(String::from("BALLOON")));
+        assert!((packet.location.coord.y - 39.3347).abs() < 0.001);
+    }
 }
@@ -1,5 +1,9 @@
+use geo::{GeodesicBearing, GeodesicDistance};
+
 pub mod ais;
 pub mod aprs;
+pub mod phase;
+pub mod telemetry;
 pub mod track;
 
 #[derive(serde::Deserialize, Clone, Debug, serde::Serialize)]
@@ -10,40 +14,99 @@ pub struct Location {
     pub altitude: Option<f64>,
 }
 
+// a fixed position (i.e. a ground station or beacon) is given without a time, so it is taken to be current
+#[derive(serde::Deserialize)]
+struct FixedPosition {
+    coord: geo::Coord,
+    altitude: Option<f64>,
+}
+
+impl From<FixedPosition> for Location {
+    fn from(position: FixedPosition) -> Self {
+        Self {
+            time: chrono::Local::now(),
+            coord: position.coord,
+            altitude: position.altitude,
+        }
+    }
+}
+
+pub fn deserialize_fixed_position<'de, D>(deserializer: D) -> Result<Location, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    <FixedPosition as serde::Deserialize>::deserialize(deserializer).map(Location::from)
+}
+
+pub fn deserialize_optional_fixed_position<'de, D>(
+    deserializer: D,
+) -> Result<Option<Location>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    <Option<FixedPosition> as serde::Deserialize>::deserialize(deserializer)
+        .map(|position| position.map(Location::from))
+}
+
 impl PartialEq for Location {
     fn eq(&self, other: &Self) -> bool {
-        self.time.eq(&other.time)
-            && crate::utilities::approx_equal(self.coord.x, other.coord.x, 4)
-            && crate::utilities::approx_equal(self.coord.y, other.coord.y, 4)
-            && match self.altitude {
-                Some(altitude) => match other.altitude {
-                    Some(other_altitude) => {
-                        crate::utilities::approx_equal(altitude, other_altitude, 4)
-                    }
-                    None => false,
-                },
-                None => other.altitude.is_none(),
-            }
+        self.duplicate_of(other, 4, 0.0001)
     }
 }
 
 impl Eq for Location {}
 
 impl Location {
-    pub fn time_lag_of(&self, other: &Self) -> bool {
-        self.time.ne(&other.time)
-            && crate::utilities::approx_equal(self.coord.x, other.coord.x, 4)
-            && crate::utilities::approx_equal(self.coord.y, other.coord.y, 4)
+    // whether both positions match to the given decimal degrees and altitude tolerance (m)
+    pub fn coincides_with(
+        &self,
+        other: &Self,
+        coordinate_precision: u8,
+        altitude_tolerance: f64,
+    ) -> bool {
+        crate::utilities::approx_equal(self.coord.x, other.coord.x, coordinate_precision)
+            && crate::utilities::approx_equal(self.coord.y, other.coord.y, coordinate_precision)
             && match self.altitude {
                 Some(altitude) => match other.altitude {
-                    Some(other_altitude) => {
-                        crate::utilities::approx_equal(altitude, other_altitude, 4)
-                    }
+                    Some(other_altitude) => (altitude - other_altitude).abs() < altitude_tolerance,
                     None => false,
                 },
-                None => false,
+                None => other.altitude.is_none(),
             }
     }
+
+    pub fn duplicate_of(
+        &self,
+        other: &Self,
+        coordinate_precision: u8,
+        altitude_tolerance: f64,
+    ) -> bool {
+        self.time.eq(&other.time)
+            && self.coincides_with(other, coordinate_precision, altitude_tolerance)
+    }
+
+    // geodesic distance (m) to another location
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        let point: geo::Point = self.coord.into();
+        point.geodesic_distance(&other.coord.into())
+    }
+
+    // initial bearing (degrees clockwise from north) of the geodesic to another location
+    pub fn bearing_to(&self, other: &Self) -> f64 {
+        let point: geo::Point = self.coord.into();
+        (point.geodesic_bearing(other.coord.into()) + 360.0) % 360.0
+    }
+
+    pub fn time_lag_of(
+        &self,
+        other: &Self,
+        coordinate_precision: u8,
+        altitude_tolerance: f64,
+    ) -> bool {
+        self.time.ne(&other.time)
+            && self.altitude.is_some()
+            && self.coincides_with(other, coordinate_precision, altitude_tolerance)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize)]
@@ -52,7 +115,7 @@ pub struct BalloonLocation {
     pub data: BalloonData,
 }
 
-#[derive(Clone, Default, Debug, PartialEq, serde::Serialize)]
+#[derive(Clone, Default, Debug, serde::Serialize)]
 pub struct BalloonData {
     pub callsign: Option<String>,
     #[serde(skip)]
@@ -60,9 +123,70 @@ pub struct BalloonData {
     pub ais: Option<ais::AisData>,
     pub source: LocationSource,
     pub raw: Option<String>,
+    pub telemetry: Option<telemetry::Telemetry>,
+    /// flight phase reported in the packet comment or status
+    pub phase: Option<phase::FlightPhase>,
+    /// reported course over ground (degrees clockwise from north)
+    pub course: Option<f64>,
+    /// reported ground speed (m/s)
+    pub speed: Option<f64>,
     pub status: PacketStatus,
 }
 
+// the packet status is assigned while processing, so it does not distinguish packets
+impl PartialEq for BalloonData {
+    fn eq(&self, other: &Self) -> bool {
+        self.callsign == other.callsign
+            && self.aprs_packet == other.aprs_packet
+            && self.ais == other.ais
+            && self.source == other.source
+            && self.raw == other.raw
+            && self.telemetry == other.telemetry
+    }
+}
+
+impl BalloonLocation {
+    // fill in data missing from this packet from a duplicate of it; returns whether anything was filled in
+    pub fn merge(&mut self, other: &Self) -> bool {
+        let mut merged = false;
+
+        if self.location.altitude.is_none() && other.location.altitude.is_some() {
+            self.location.altitude = other.location.altitude;
+            merged = true;
+        }
+        if self.data.aprs_packet.is_none() && other.data.aprs_packet.is_some() {
+            self.data.aprs_packet = other.data.aprs_packet.to_owned();
+            merged = true;
+        }
+        if self.data.ais.is_none() && other.data.ais.is_some() {
+            self.data.ais = other.data.ais.to_owned();
+            merged = true;
+        }
+        if self.data.raw.is_none() && other.data.raw.is_some() {
+            self.data.raw = other.data.raw.to_owned();
+            merged = true;
+        }
+        if self.data.telemetry.is_none() && other.data.telemetry.is_some() {
+            self.data.telemetry = other.data.telemetry.to_owned();
+            merged = true;
+        }
+        if self.data.phase.is_none() && other.data.phase.is_some() {
+            self.data.phase = other.data.phase;
+            merged = true;
+        }
+        if self.data.course.is_none() && other.data.course.is_some() {
+            self.data.course = other.data.course;
+            merged = true;
+        }
+        if self.data.speed.is_none() && other.data.speed.is_some() {
+            self.data.speed = other.data.speed;
+            merged = true;
+        }
+
+        merged
+    }
+}
+
 impl BalloonData {
     pub fn new(
         callsign: Option<String>,
@@ -80,12 +204,25 @@ impl BalloonData {
             }
         }
 
+        let telemetry = match &aprs_packet {
+            Some(aprs_packet) => telemetry::Telemetry::from_aprs_packet(aprs_packet),
+            None => None,
+        };
+        let phase = match &aprs_packet {
+            Some(aprs_packet) => phase::FlightPhase::from_aprs_packet(aprs_packet),
+            None => None,
+        };
+
         Self {
             callsign,
             aprs_packet,
             ais,
             raw,
             source,
+            telemetry,
+            phase,
+            course: None,
+            speed: None,
             status: PacketStatus::None,
         }
     }
@@ -94,11 +231,16 @@ impl BalloonData {
 #[derive(Clone, Default, Debug, PartialEq, serde::Serialize)]
 pub enum LocationSource {
     AprsFi,
+    AprsIs(String),
+    AisStream,
+    SondeHub,
     Serial(String),
     TextFile(String),
     GeoJsonFile(String),
     Database(String),
     Prediction,
+    /// interpolated between received locations
+    Interpolated,
     #[default]
     None,
 }
@@ -107,6 +249,7 @@ pub enum LocationSource {
 pub enum PacketStatus {
     Duplicate,
     TimeLaggedDuplicate,
+    Outlier,
     #[default]
     None,
 }
@@ -133,4 +276,55 @@ mod tests {
 
         assert!(!locations.locations.is_empty());
     }
+
+    #[test]
+    fn test_duplicate_precision() {
+        let time = chrono::Local::now();
+        let location = Location {
+            time,
+            coord: geo::coord! { x: -77.00001, y: 39.00001 },
+            altitude: Some(100.0),
+        };
+        // GPS jitter of a few meters
+        let jittered = Location {
+            time,
+            coord: geo::coord! { x: -77.00004, y: 39.00003 },
+            altitude: Some(102.0),
+        };
+
+        assert!(!location.duplicate_of(&jittered, 5, 0.0001));
+        assert!(!location.duplicate_of(&jittered, 4, 0.0001));
+        assert!(location.duplicate_of(&jittered, 4, 5.0));
+
+        let later = Location {
+            time: time + chrono::Duration::seconds(60),
+            ..jittered
+        };
+        assert!(later.time_lag_of(&location, 4, 5.0));
+        assert!(!later.time_lag_of(&location, 5, 5.0));
+    }
+
+    #[test]
+    fn test_bearing_distance() {
+        let time = chrono::Local::now();
+        let station = Location {
+            time,
+            coord: geo::coord! { x: -77.0, y: 39.0 },
+            altitude: None,
+        };
+        let north = Location {
+            time,
+            coord: geo::coord! { x: -77.0, y: 39.1 },
+            altitude: Some(10000.0),
+        };
+        let west = Location {
+            time,
+            coord: geo::coord! { x: -77.1, y: 39.0 },
+            altitude: Some(10000.0),
+        };
+
+        assert!((station.distance_to(&north) - 11100.0).abs() < 100.0);
+        assert!(station.bearing_to(&north).abs() < 0.01);
+        assert!((station.bearing_to(&west) - 270.0).abs() < 0.1);
+    }
 }
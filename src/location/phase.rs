@@ -0,0 +1,85 @@
+// stage of the flight as reported by the payload itself, in its comment or status
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub enum FlightPhase {
+    Ascending,
+    Floating,
+    Descending,
+    Landed,
+    /// the payload reports an error
+    Fault,
+}
+
+lazy_static::lazy_static! {
+    // whole words only, so that i.e. `nearspace.umd.edu` or `upload` are not mistaken for a phase
+    static ref PHASE_PATTERNS: Vec<(FlightPhase, regex::Regex)> = [
+        (FlightPhase::Fault, r"(?i)\b(error|err|fault|fail|failed|failure)\b"),
+        (FlightPhase::Landed, r"(?i)\b(landed|recovered|on ground)\b"),
+        (FlightPhase::Descending, r"(?i)\b(descent|descending|desc|falling|burst|cutdown)\b"),
+        (FlightPhase::Floating, r"(?i)\b(float|floating)\b"),
+        (FlightPhase::Ascending, r"(?i)\b(ascent|ascending|asc|rising|climbing)\b"),
+    ]
+    .into_iter()
+    .map(|(phase, pattern)| (phase, regex::Regex::new(pattern).unwrap()))
+    .collect();
+}
+
+impl FlightPhase {
+    // the phase named in the text; faults come first, then later stages of the flight before earlier ones
+    pub fn from_text(text: &str) -> Option<Self> {
+        PHASE_PATTERNS
+            .iter()
+            .find(|(_, pattern)| pattern.is_match(text))
+            .map(|(phase, _)| *phase)
+    }
+
+    pub fn from_aprs_packet(packet: &aprs_parser::AprsPacket) -> Option<Self> {
+        let comment = match &packet.data {
+            aprs_parser::AprsData::Position(payload) => &payload.comment,
+            aprs_parser::AprsData::MicE(payload) => &payload.comment,
+            _ => return None,
+        };
+        Self::from_text(&String::from_utf8_lossy(comment))
+    }
+}
+
+impl std::fmt::Display for FlightPhase {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ascending => write!(fmt, "ascending"),
+            Self::Floating => write!(fmt, "floating"),
+            Self::Descending => write!(fmt, "descending"),
+            Self::Landed => write!(fmt, "landed"),
+            Self::Fault => write!(fmt, "fault"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase() {
+        assert_eq!(
+            FlightPhase::from_text("W3EAX balloon ASCENDING 5.2m/s"),
+            Some(FlightPhase::Ascending)
+        );
+        assert_eq!(
+            FlightPhase::from_text("burst at 28km, descending"),
+            Some(FlightPhase::Descending)
+        );
+        assert_eq!(
+            FlightPhase::from_text("Float 31C 1.6V"),
+            Some(FlightPhase::Floating)
+        );
+        assert_eq!(
+            FlightPhase::from_text("landed; GPS error"),
+            Some(FlightPhase::Fault)
+        );
+        assert_eq!(
+            FlightPhase::from_text("/A=053614|!g|  /W3EAX,313,0,21'C,nearspace.umd.edu"),
+            None
+        );
+        assert_eq!(FlightPhase::from_text("uploaded descriptions"), None);
+    }
+}
@@ -0,0 +1,97 @@
+#[derive(Clone, Default, Debug, PartialEq, serde::Serialize)]
+pub struct Telemetry {
+    /// battery voltage (V)
+    pub voltage: Option<f64>,
+    /// temperature (°C)
+    pub temperature: Option<f64>,
+    /// pressure (hPa)
+    pub pressure: Option<f64>,
+    /// relative humidity (%)
+    pub humidity: Option<f64>,
+    /// number of GPS satellites in view
+    pub satellites: Option<u8>,
+}
+
+impl Telemetry {
+    // recognize `1.55V`, `3C` / `21'C`, and `82725Pa` / `1013hPa` tokens in a packet comment
+    pub fn from_comment(comment: &str) -> Option<Self> {
+        let mut telemetry = Self::default();
+        for token in comment.split(|character: char| {
+            character == ',' || character == '/' || character.is_whitespace()
+        }) {
+            if let Some(voltage) = parse_token(token, &VOLTAGE_PATTERN) {
+                telemetry.voltage.get_or_insert(voltage);
+            } else if let Some(temperature) = parse_token(token, &TEMPERATURE_PATTERN) {
+                telemetry.temperature.get_or_insert(temperature);
+            } else if let Some(pressure) = parse_token(token, &PASCAL_PATTERN) {
+                telemetry.pressure.get_or_insert(pressure / 100.0);
+            } else if let Some(pressure) = parse_token(token, &HECTOPASCAL_PATTERN) {
+                telemetry.pressure.get_or_insert(pressure);
+            }
+        }
+
+        if telemetry == Self::default() {
+            None
+        } else {
+            Some(telemetry)
+        }
+    }
+
+    pub fn from_aprs_packet(packet: &aprs_parser::AprsPacket) -> Option<Self> {
+        let comment = match &packet.data {
+            aprs_parser::AprsData::Position(payload) => &payload.comment,
+            aprs_parser::AprsData::MicE(payload) => &payload.comment,
+            _ => return None,
+        };
+        Self::from_comment(&String::from_utf8_lossy(comment))
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref VOLTAGE_PATTERN: regex::Regex = regex::Regex::new(r"^(\d+\.\d+)V$").unwrap();
+    static ref TEMPERATURE_PATTERN: regex::Regex =
+        regex::Regex::new(r"^(-?\d+(?:\.\d+)?)'?C$").unwrap();
+    static ref PASCAL_PATTERN: regex::Regex = regex::Regex::new(r"^(\d+(?:\.\d+)?)Pa$").unwrap();
+    static ref HECTOPASCAL_PATTERN: regex::Regex =
+        regex::Regex::new(r"^(\d+(?:\.\d+)?)hPa$").unwrap();
+}
+
+fn parse_token(token: &str, pattern: &regex::Regex) -> Option<f64> {
+    pattern
+        .captures(token)
+        .and_then(|captures| captures[1].parse::<f64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_telemetry() {
+        assert_eq!(
+            Telemetry::from_comment(",StrTrk,255,9,1.55V,3C,82725Pa,"),
+            Some(Telemetry {
+                voltage: Some(1.55),
+                temperature: Some(3.0),
+                pressure: Some(827.25),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Telemetry::from_comment("GPS:0 3.70V -2C 0m QD17FW *083QIY JO40 3* 0kt"),
+            Some(Telemetry {
+                voltage: Some(3.70),
+                temperature: Some(-2.0),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Telemetry::from_comment("/A=053614|!g|  /W3EAX,313,0,21'C,nearspace.umd.edu"),
+            Some(Telemetry {
+                temperature: Some(21.0),
+                ..Default::default()
+            })
+        );
+        assert_eq!(Telemetry::from_comment("a=10326.1/R=47"), None);
+    }
+}
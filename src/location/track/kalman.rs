@@ -0,0 +1,222 @@
+const EARTH_RADIUS: f64 = 6_371_000.0;
+/// initial velocity uncertainty (m/s), large enough to accept whatever the first measurements imply
+const INITIAL_VELOCITY_UNCERTAINTY: f64 = 100.0;
+
+// position and velocity along one axis, with a constant-velocity model
+#[derive(Clone, Copy, Debug)]
+struct AxisFilter {
+    position: f64,
+    velocity: f64,
+    covariance: [[f64; 2]; 2],
+}
+
+impl AxisFilter {
+    fn new(position: f64, variance: f64) -> Self {
+        Self {
+            position,
+            velocity: 0.0,
+            covariance: [[variance, 0.0], [0.0, INITIAL_VELOCITY_UNCERTAINTY.powi(2)]],
+        }
+    }
+
+    // advance the state by the given number of seconds, allowing for random acceleration
+    fn predict(&mut self, seconds: f64, acceleration_variance: f64) {
+        let [[p00, p01], [_, p11]] = self.covariance;
+        let q00 = acceleration_variance * seconds.powi(4) / 4.0;
+        let q01 = acceleration_variance * seconds.powi(3) / 2.0;
+        let q11 = acceleration_variance * seconds.powi(2);
+
+        self.position += self.velocity * seconds;
+        let covariance = p01 + seconds * p11 + q01;
+        self.covariance = [
+            [
+                p00 + 2.0 * seconds * p01 + seconds.powi(2) * p11 + q00,
+                covariance,
+            ],
+            [covariance, p11 + q11],
+        ];
+    }
+
+    fn update(&mut self, measurement: f64, variance: f64) {
+        let [[p00, p01], [_, p11]] = self.covariance;
+        let innovation_variance = p00 + variance;
+        let position_gain = p00 / innovation_variance;
+        let velocity_gain = p01 / innovation_variance;
+
+        let residual = measurement - self.position;
+        self.position += position_gain * residual;
+        self.velocity += velocity_gain * residual;
+
+        let covariance = (1.0 - position_gain) * p01;
+        self.covariance = [
+            [(1.0 - position_gain) * p00, covariance],
+            [covariance, p11 - velocity_gain * p01],
+        ];
+    }
+}
+
+// positions smoothed with a Kalman filter over east, north, and altitude, in the order of the given locations;
+// the ground speed and course of each location are taken from the filtered velocity
+pub fn filtered(
+    locations: &[crate::location::BalloonLocation],
+    configuration: &crate::configuration::KalmanConfiguration,
+) -> super::LocationTrack {
+    let origin = match locations.first() {
+        Some(location) => location.location.coord,
+        None => return vec![],
+    };
+    // local east / north distances (m) from the first location
+    let meters_per_degree = EARTH_RADIUS.to_radians();
+    let meters_per_degree_longitude = meters_per_degree * origin.y.to_radians().cos();
+
+    let position_variance = configuration.position_noise.powi(2);
+    let altitude_variance = configuration.altitude_noise.powi(2);
+    let acceleration_variance = configuration.acceleration_noise.powi(2);
+
+    let mut filters: Option<(AxisFilter, AxisFilter)> = None;
+    let mut altitude_filter: Option<AxisFilter> = None;
+    let mut previous_time = locations[0].location.time;
+
+    let mut smoothed = vec![];
+    for location in locations {
+        let east = (location.location.coord.x - origin.x) * meters_per_degree_longitude;
+        let north = (location.location.coord.y - origin.y) * meters_per_degree;
+        let seconds =
+            ((location.location.time - previous_time).num_milliseconds() as f64 / 1000.0).max(0.0);
+        previous_time = location.location.time;
+
+        let (east_filter, north_filter) = match &mut filters {
+            Some((east_filter, north_filter)) => {
+                for (filter, measurement) in
+                    [(&mut *east_filter, east), (&mut *north_filter, north)]
+                {
+                    filter.predict(seconds, acceleration_variance);
+                    filter.update(measurement, position_variance);
+                }
+                (*east_filter, *north_filter)
+            }
+            None => *filters.insert((
+                AxisFilter::new(east, position_variance),
+                AxisFilter::new(north, position_variance),
+            )),
+        };
+
+        if let Some(altitude_filter) = &mut altitude_filter {
+            altitude_filter.predict(seconds, acceleration_variance);
+            if let Some(altitude) = location.location.altitude {
+                altitude_filter.update(altitude, altitude_variance);
+            }
+        } else if let Some(altitude) = location.location.altitude {
+            altitude_filter = Some(AxisFilter::new(altitude, altitude_variance));
+        }
+
+        let mut smoothed_location = location.to_owned();
+        smoothed_location.location.coord = geo::coord! {
+            x: origin.x + east_filter.position / meters_per_degree_longitude,
+            y: origin.y + north_filter.position / meters_per_degree,
+        };
+        smoothed_location.location.altitude = altitude_filter.map(|filter| filter.position);
+        smoothed_location.data.speed = Some(east_filter.velocity.hypot(north_filter.velocity));
+        smoothed_location.data.course = Some(
+            east_filter
+                .velocity
+                .atan2(north_filter.velocity)
+                .to_degrees()
+                .rem_euclid(360.0),
+        );
+        smoothed.push(smoothed_location);
+    }
+
+    smoothed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // deterministic noise in [-1, 1)
+    fn noise(seed: &mut u64) -> f64 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((*seed >> 11) as f64 / (1u64 << 53) as f64) * 2.0 - 1.0
+    }
+
+    #[test]
+    fn test_filtered_variance() {
+        let start = chrono::Local::now();
+        let origin: geo::Coord = geo::coord! { x: -77.0, y: 39.0 };
+        let meters_per_degree = EARTH_RADIUS.to_radians();
+        let meters_per_degree_longitude = meters_per_degree * origin.y.to_radians().cos();
+
+        // rising at 5 m/s and drifting east at 10 m/s, reported every 30 seconds with up to 50 m of error
+        let truth = |seconds: f64| {
+            (
+                geo::coord! {
+                    x: origin.x + 10.0 * seconds / meters_per_degree_longitude,
+                    y: origin.y,
+                },
+                1000.0 + 5.0 * seconds,
+            )
+        };
+
+        let mut seed = 1;
+        let locations: Vec<crate::location::BalloonLocation> = (0..120)
+            .map(|index| {
+                let seconds = index as f64 * 30.0;
+                let (coord, altitude) = truth(seconds);
+                crate::location::BalloonLocation {
+                    location: crate::location::Location {
+                        time: start + chrono::Duration::seconds(index * 30),
+                        coord: geo::coord! {
+                            x: coord.x + 50.0 * noise(&mut seed) / meters_per_degree_longitude,
+                            y: coord.y + 50.0 * noise(&mut seed) / meters_per_degree,
+                        },
+                        altitude: Some(altitude + 30.0 * noise(&mut seed)),
+                    },
+                    data: crate::location::BalloonData::new(
+                        Some("W3EAX-8".to_string()),
+                        None,
+                        None,
+                        None,
+                        crate::location::LocationSource::None,
+                    ),
+                }
+            })
+            .collect();
+
+        let configuration = crate::configuration::KalmanConfiguration {
+            enabled: true,
+            position_noise: 50.0,
+            altitude_noise: 30.0,
+            acceleration_noise: 0.01,
+        };
+        let smoothed = filtered(&locations, &configuration);
+        assert_eq!(smoothed.len(), locations.len());
+
+        // mean squared error from the true path, after the filter has settled
+        let errors = |track: &[crate::location::BalloonLocation]| {
+            let mut horizontal = 0.0;
+            let mut vertical = 0.0;
+            for (index, location) in track.iter().enumerate().skip(20) {
+                let (coord, altitude) = truth(index as f64 * 30.0);
+                horizontal +=
+                    (((location.location.coord.x - coord.x) * meters_per_degree_longitude).powi(2)
+                        + ((location.location.coord.y - coord.y) * meters_per_degree).powi(2))
+                        / 100.0;
+                vertical += (location.location.altitude.unwrap() - altitude).powi(2) / 100.0;
+            }
+            (horizontal, vertical)
+        };
+
+        let (raw_horizontal, raw_vertical) = errors(&locations);
+        let (filtered_horizontal, filtered_vertical) = errors(&smoothed);
+        assert!(filtered_horizontal < raw_horizontal / 2.0);
+        assert!(filtered_vertical < raw_vertical / 2.0);
+
+        // the filtered velocity approaches the true drift
+        let last = smoothed.last().unwrap();
+        assert!((last.data.speed.unwrap() - 10.0).abs() < 2.0);
+        assert!((last.data.course.unwrap() - 90.0).abs() < 15.0);
+    }
+}
@@ -1,33 +1,105 @@
-use geo::GeodesicDistance;
+use geo::{ConvexHull, GeodesicDistance};
+
+pub mod kalman;
 
 pub type LocationTrack = Vec<crate::location::BalloonLocation>;
 
+lazy_static::lazy_static! {
+    static ref VOLTAGE_TREND_SAMPLES: usize = 5;
+}
+
 pub struct BalloonTrack {
     pub locations: LocationTrack,
     pub prediction: Option<LocationTrack>,
+    /// warnings and other notes returned with the prediction
+    pub prediction_metadata: Option<crate::prediction::PredictionMetadata>,
+    /// landing locations predicted with perturbed profiles
+    pub predicted_landings: LocationTrack,
+    /// predictions of additional named profiles
+    pub predictions: Vec<(String, LocationTrack)>,
+    /// descent-only prediction from the latest location, kept until the next new location
+    pub descent_prediction: Option<LocationTrack>,
     pub name: String,
+    /// number of consecutive ascent rates to average
+    pub smoothing_window: usize,
+    /// ascent rate (m/s) separating ascent and descent from float
+    pub ascent_rate_threshold: f64,
+    /// number of latest ascent rates used to determine ascent or descent
+    pub ascent_rate_samples: usize,
+    pub landing: crate::configuration::LandingConfiguration,
+    /// distance (m) between the predicted and actual landing, recorded when the balloon lands
+    pub landing_error: Option<f64>,
+    // whether the balloon was descending when last checked for touchdown
+    was_descending: bool,
+    pub float: crate::configuration::FloatConfiguration,
+    /// range of altitudes (m) within which the latest locations of a floating balloon stay
+    pub float_uncertainty: f64,
+    /// descent under parachute, to recognize freefall and estimate the time to ground
+    pub terminal_velocity: crate::model::TerminalVelocity,
+    /// locations smoothed by the Kalman filter, if enabled
+    pub filtered: LocationTrack,
+    pub kalman: crate::configuration::KalmanConfiguration,
 }
 
 impl BalloonTrack {
     pub fn new(name: String) -> Self {
+        let ascent_rate = crate::configuration::AscentRateConfiguration::default();
         Self {
             locations: vec![],
             prediction: None,
+            prediction_metadata: None,
+            predicted_landings: vec![],
+            predictions: vec![],
+            descent_prediction: None,
             name,
+            smoothing_window: ascent_rate.smoothing_window,
+            ascent_rate_threshold: ascent_rate.threshold,
+            ascent_rate_samples: ascent_rate.samples,
+            landing: Default::default(),
+            landing_error: None,
+            was_descending: false,
+            float: Default::default(),
+            float_uncertainty: *crate::prediction::DEFAULT_FLOAT_UNCERTAINTY,
+            terminal_velocity: Default::default(),
+            filtered: vec![],
+            kalman: Default::default(),
         }
     }
 
-    pub fn push(&mut self, location: crate::location::BalloonLocation) {
-        if !self.contains(&location) {
+    // smooth the locations that are not outliers, if the Kalman filter is enabled
+    pub fn update_filtered(&mut self) {
+        self.filtered = if self.kalman.enabled {
+            kalman::filtered(&without_outliers(&self.locations), &self.kalman)
+        } else {
+            vec![]
+        };
+    }
+
+    // filtered locations if available, otherwise the received locations; for deriving velocities
+    pub fn kinematic_locations(&self) -> &[crate::location::BalloonLocation] {
+        if self.filtered.is_empty() {
+            &self.locations
+        } else {
+            &self.filtered
+        }
+    }
+
+    // insert the location in order of time, returning whether it was not already in the track
+    pub fn push(&mut self, location: crate::location::BalloonLocation) -> bool {
+        if self.contains(&location) {
+            false
+        } else {
             let needs_sorting = match self.locations.last() {
                 Some(current) => current.location.time > location.location.time,
                 None => false,
             };
             self.locations.push(location);
+            self.descent_prediction = None;
             if needs_sorting {
                 self.locations
                     .sort_by_key(|location| location.location.time);
             }
+            true
         }
     }
 
@@ -40,18 +112,15 @@ impl BalloonTrack {
         false
     }
 
+    // time to fall from the last altitude at the last descent rate
     pub fn estimated_time_to_ground(&self) -> Option<chrono::Duration> {
         if !self.locations.is_empty() && self.descending() {
-            let mut altitudes = vec![];
-            for location in &self.locations {
-                if let Some(altitude) = location.location.altitude {
-                    altitudes.push(altitude);
-                }
-            }
-            if altitudes.len() > 1 {
+            let locations = self.kinematic_locations();
+            let altitude = *altitudes(locations).last()?;
+            let ascent_rate = *ascent_rates(locations).last()?;
+            if ascent_rate < 0.0 {
                 Some(chrono::Duration::milliseconds(
-                    ((-ascent_rates(&self.locations).last().unwrap() / altitudes.last().unwrap())
-                        * 1000.0) as i64,
+                    ((altitude / -ascent_rate) * 1000.0) as i64,
                 ))
             } else {
                 None
@@ -61,21 +130,180 @@ impl BalloonTrack {
         }
     }
 
+    // ascent rates averaged over the smoothing window, to even out irregular packet timing
+    pub fn smoothed_ascent_rates(&self) -> Vec<f64> {
+        smoothed(&ascent_rates(&self.locations), self.smoothing_window)
+    }
+
+    // whether the latest ascent rates all satisfy the given condition
+    fn latest_ascent_rates(&self, condition: impl Fn(f64) -> bool) -> bool {
+        let ascent_rates = self.smoothed_ascent_rates();
+        !ascent_rates.is_empty()
+            && ascent_rates
+                .iter()
+                .rev()
+                .take(self.ascent_rate_samples.max(1))
+                .all(|ascent_rate| condition(*ascent_rate))
+    }
+
     pub fn ascending(&self) -> bool {
-        let ascent_rates = ascent_rates(&self.locations);
-        ascent_rates.iter().rev().take(2).all(|a| a > &0.2)
+        self.latest_ascent_rates(|ascent_rate| ascent_rate > self.ascent_rate_threshold)
     }
 
     pub fn descending(&self) -> bool {
-        let ascent_rates = ascent_rates(&self.locations);
-        ascent_rates.iter().rev().take(2).all(|a| a < &0.2)
+        self.latest_ascent_rates(|ascent_rate| ascent_rate < -self.ascent_rate_threshold)
+    }
+
+    // the part of the prediction after the latest received location, to show only where the balloon is going
+    pub fn remaining_prediction(&self) -> Option<LocationTrack> {
+        self.after_latest(self.prediction.as_ref()?)
+    }
+
+    // the remaining part of each named profile prediction, followed by the descent-only prediction
+    pub fn remaining_predictions(&self) -> Vec<(&str, LocationTrack)> {
+        self.named_predictions()
+            .filter_map(|(name, prediction)| Some((name, self.after_latest(prediction)?)))
+            .collect()
+    }
+
+    // each named profile prediction, followed by the descent-only prediction
+    pub fn named_predictions(&self) -> impl Iterator<Item = (&str, &LocationTrack)> {
+        self.predictions
+            .iter()
+            .map(|(name, prediction)| (name.as_str(), prediction))
+            .chain(
+                self.descent_prediction
+                    .iter()
+                    .map(|prediction| ("descent", prediction)),
+            )
+    }
+
+    fn after_latest(&self, prediction: &LocationTrack) -> Option<LocationTrack> {
+        let remaining: LocationTrack = match self.locations.last() {
+            Some(latest) => prediction
+                .iter()
+                .filter(|location| location.location.time >= latest.location.time)
+                .cloned()
+                .collect(),
+            None => prediction.to_owned(),
+        };
+        if remaining.len() > 1 {
+            Some(remaining)
+        } else {
+            None
+        }
+    }
+
+    // whether the latest locations have levelled off high above the ground
+    pub fn floating(&self) -> bool {
+        let locations = with_altitude(self.kinematic_locations());
+        let start = match locations.len().checked_sub(self.float.samples.max(2)) {
+            Some(start) => start,
+            None => return false,
+        };
+        let altitudes = altitudes(&locations[start..]);
+        let lowest = altitudes.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest = altitudes.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        lowest > self.float.minimum_altitude
+            && highest - lowest <= self.float_uncertainty
+            && !self.ascending()
+            && !self.descending()
+    }
+
+    // flag locations whose implied speed to both neighbors exceeds physical limits (m/s)
+    pub fn flag_outliers(&mut self, maximum_ground_speed: f64, maximum_ascent_rate: f64) {
+        let exceeds_limits = |from: &crate::location::BalloonLocation,
+                              to: &crate::location::BalloonLocation| {
+            let seconds = (to.location.time - from.location.time).num_seconds() as f64;
+            if seconds <= 0.0 {
+                return false;
+            }
+
+            let from_point: geo::Point = from.location.coord.into();
+            let to_point: geo::Point = to.location.coord.into();
+            if from_point.geodesic_distance(&to_point) / seconds > maximum_ground_speed {
+                return true;
+            }
+
+            match (from.location.altitude, to.location.altitude) {
+                (Some(from_altitude), Some(to_altitude)) => {
+                    (to_altitude - from_altitude).abs() / seconds > maximum_ascent_rate
+                }
+                _ => false,
+            }
+        };
+
+        let mut previous: Option<usize> = None;
+        for index in 0..self.locations.len() {
+            let location = &self.locations[index];
+            let outlier = match previous {
+                Some(previous) => {
+                    exceeds_limits(&self.locations[previous], location)
+                        && match self.locations.get(index + 1) {
+                            Some(next) => exceeds_limits(location, next),
+                            None => true,
+                        }
+                }
+                None => false,
+            };
+
+            self.locations[index].data.status = if outlier {
+                crate::location::PacketStatus::Outlier
+            } else {
+                previous = Some(index);
+                crate::location::PacketStatus::None
+            };
+        }
+    }
+
+    // highest location, once ascent has turned into a sustained descent after it
+    pub fn burst(&self) -> Option<&crate::location::BalloonLocation> {
+        if self.ascending() {
+            return None;
+        }
+
+        let mut highest: Option<(usize, f64)> = None;
+        for (index, location) in self.locations.iter().enumerate() {
+            if let Some(altitude) = location.location.altitude {
+                if highest.is_none_or(|(_, highest_altitude)| altitude > highest_altitude) {
+                    highest = Some((index, altitude));
+                }
+            }
+        }
+        let (index, altitude) = highest?;
+
+        let below = |location: &crate::location::BalloonLocation| {
+            location
+                .location
+                .altitude
+                .is_some_and(|other| other < altitude)
+        };
+        let descent_rates = smoothed(
+            &ascent_rates(&self.locations[index..]),
+            self.smoothing_window,
+        );
+        let descended = descent_rates
+            .windows(self.ascent_rate_samples.max(1))
+            .any(|window| {
+                window
+                    .iter()
+                    .all(|ascent_rate| *ascent_rate < -self.ascent_rate_threshold)
+            });
+        if descended && self.locations[..index].iter().any(below) {
+            Some(&self.locations[index])
+        } else {
+            None
+        }
     }
 
     pub fn falling(&self) -> Option<crate::model::FreefallEstimate> {
         let last_location: &crate::location::BalloonLocation = self.locations.last().unwrap();
 
         if last_location.location.altitude.is_some() && self.descending() {
-            let freefall_estimate = last_location.location.estimate_freefall();
+            let freefall_estimate = last_location
+                .location
+                .estimate_freefall(&self.terminal_velocity);
 
             if let Some(last_ascent_rate) = ascent_rates(&self.locations).last() {
                 if (last_ascent_rate - freefall_estimate.ascent_rate)
@@ -92,24 +320,302 @@ impl BalloonTrack {
             None
         }
     }
-}
 
-pub fn with_altitude(locations: &[super::BalloonLocation]) -> Vec<super::BalloonLocation> {
-    locations
-        .iter()
-        .filter_map(|location| {
-            if location.location.altitude.is_some() {
-                Some(location.to_owned())
+    // average rate of change of battery voltage (V/h) over the most recent packets
+    pub fn voltage_trend(&self) -> Option<f64> {
+        let locations = with_voltage(&self.locations);
+        let recent = &locations[locations.len().saturating_sub(*VOLTAGE_TREND_SAMPLES + 1)..];
+
+        if recent.len() > 1 {
+            let voltages = voltages(recent);
+            let hours = (recent.last().unwrap().location.time
+                - recent.first().unwrap().location.time)
+                .num_seconds() as f64
+                / 3600.0;
+            let rate = (voltages.last().unwrap() - voltages.first().unwrap()) / hours;
+            if rate.is_finite() {
+                Some(rate)
             } else {
                 None
             }
+        } else {
+            None
+        }
+    }
+
+    pub fn battery_warning(
+        &self,
+        minimum_voltage: Option<f64>,
+        maximum_decline_rate: f64,
+    ) -> Option<BatteryWarning> {
+        let voltage = *voltages(&self.locations).last()?;
+
+        if let Some(minimum_voltage) = minimum_voltage {
+            if voltage < minimum_voltage {
+                return Some(BatteryWarning::Low { voltage });
+            }
+        }
+
+        if let Some(rate) = self.voltage_trend() {
+            if -rate > maximum_decline_rate {
+                return Some(BatteryWarning::Declining { voltage, rate });
+            }
+        }
+
+        None
+    }
+
+    // area enclosing the predicted landing locations of the default and perturbed profiles
+    pub fn landing_zone(&self) -> Option<geo::Polygon> {
+        let mut points: Vec<geo::Point> = self
+            .predicted_landings
+            .iter()
+            .map(|landing| landing.location.coord.into())
+            .collect();
+        if let Some(landing) = self
+            .prediction
+            .as_ref()
+            .and_then(|prediction| prediction.last())
+        {
+            points.push(landing.location.coord.into());
+        }
+
+        if points.len() < 3 {
+            return None;
+        }
+        Some(geo::MultiPoint::from(points).convex_hull())
+    }
+
+    // first of the latest locations that have stayed low and stationary after having flown above the landing altitude
+    pub fn landing(&self) -> Option<crate::location::BalloonLocation> {
+        let locations = with_altitude(&self.locations);
+        let samples = self.landing.samples.max(2);
+        let start = locations.len().checked_sub(samples)?;
+        let (flight, latest) = locations.split_at(start);
+
+        let below = |location: &crate::location::BalloonLocation| {
+            location.location.altitude.unwrap() < self.landing.maximum_altitude
+        };
+        if !flight.iter().any(|location| !below(location)) || !latest.iter().all(below) {
+            return None;
+        }
+
+        let stationary = ground_speeds(latest)
+            .iter()
+            .all(|ground_speed| ground_speed < &self.landing.maximum_ground_speed)
+            && ascent_rates(latest)
+                .iter()
+                .all(|ascent_rate| ascent_rate.abs() < self.landing.maximum_ascent_rate);
+        if stationary {
+            latest.first().cloned()
+        } else {
+            None
+        }
+    }
+
+    pub fn landed(&self) -> bool {
+        self.landing().is_some()
+    }
+
+    // distance (m) from the landing of the current prediction to the final position of a landed balloon
+    pub fn prediction_error(&self) -> Option<f64> {
+        if !self.landed() {
+            return None;
+        }
+        let predicted = self.prediction.as_ref()?.last()?;
+        let actual = without_outliers(&self.locations).pop()?;
+        Some(predicted.location.distance_to(&actual.location))
+    }
+
+    // record the prediction error when the balloon has touched down since the last call, returning it;
+    // a track that is already landed when first read (i.e. after a restart) has no prediction from before touchdown
+    pub fn touchdown_error(&mut self) -> Option<f64> {
+        let touched_down = self.was_descending && self.landed();
+        self.was_descending = self.descending();
+        if !touched_down {
+            return None;
+        }
+        let error = self.prediction_error()?;
+        self.landing_error = Some(error);
+        Some(error)
+    }
+
+    // reported course of the last location, or the bearing between the last two positions
+    pub fn heading(&self) -> Option<f64> {
+        let locations = without_outliers(self.kinematic_locations());
+        let last = locations.last()?;
+        if let Some(course) = last.data.course {
+            return Some(course);
+        }
+
+        let previous = locations.get(locations.len().checked_sub(2)?)?;
+        if previous.location.coord == last.location.coord {
+            None
+        } else {
+            Some(previous.location.bearing_to(&last.location))
+        }
+    }
+
+    // total distance travelled over the ground (m)
+    pub fn total_overground_distance(&self) -> f64 {
+        overground_distances(&self.locations).iter().sum()
+    }
+
+    // total altitude gained, ignoring descents (m)
+    pub fn total_ascent(&self) -> f64 {
+        ascents(&self.locations)
+            .iter()
+            .filter(|ascent| ascent > &&0.0)
+            .sum()
+    }
+
+    // locations linearly interpolated at a regular interval from the first to the last location, skipping outliers;
+    // altitude is only interpolated between locations that both have one
+    pub fn resample(&self, interval: chrono::Duration) -> LocationTrack {
+        let locations = without_outliers(&self.locations);
+        let (first, last) = match (locations.first(), locations.last()) {
+            (Some(first), Some(last)) if interval > chrono::Duration::zero() => {
+                (first.location.time, last.location.time)
+            }
+            _ => return vec![],
+        };
+
+        let mut resampled = vec![];
+        let mut time = first;
+        let mut index = 0;
+        while time <= last {
+            // the pair of locations bracketing this time
+            while locations[index + 1..]
+                .first()
+                .is_some_and(|next| next.location.time < time)
+            {
+                index += 1;
+            }
+            let before = &locations[index];
+            let after = locations.get(index + 1).unwrap_or(before);
+
+            let span = (after.location.time - before.location.time).num_milliseconds() as f64;
+            let fraction = if span > 0.0 {
+                (time - before.location.time).num_milliseconds() as f64 / span
+            } else {
+                0.0
+            };
+            let interpolate = |start: f64, end: f64| start + (end - start) * fraction;
+
+            resampled.push(crate::location::BalloonLocation {
+                location: crate::location::Location {
+                    time,
+                    coord: geo::coord! {
+                        x: interpolate(before.location.coord.x, after.location.coord.x),
+                        y: interpolate(before.location.coord.y, after.location.coord.y),
+                    },
+                    altitude: match (before.location.altitude, after.location.altitude) {
+                        (Some(start), Some(end)) => Some(interpolate(start, end)),
+                        _ => None,
+                    },
+                },
+                data: crate::location::BalloonData::new(
+                    Some(self.name.to_owned()),
+                    None,
+                    None,
+                    None,
+                    crate::location::LocationSource::Interpolated,
+                ),
+            });
+            time += interval;
+        }
+
+        resampled
+    }
+
+    // name of the vessel, if this track follows one over AIS
+    pub fn vessel_name(&self) -> Option<&str> {
+        self.locations.iter().rev().find_map(|location| {
+            location
+                .data
+                .ais
+                .as_ref()
+                .and_then(|ais| ais.vessel_name.as_deref())
+        })
+    }
+
+    // i.e. `W3EAX-8`, or `AUGUSTSON (259000420)` for a vessel
+    pub fn display_name(&self) -> String {
+        match self.vessel_name() {
+            Some(vessel_name) => format!("{:} ({:})", vessel_name, self.name),
+            None => self.name.to_owned(),
+        }
+    }
+
+    pub fn max_altitude(&self) -> Option<f64> {
+        altitudes(&self.locations).into_iter().reduce(f64::max)
+    }
+
+    pub fn min_altitude(&self) -> Option<f64> {
+        altitudes(&self.locations).into_iter().reduce(f64::min)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BatteryWarning {
+    Low { voltage: f64 },
+    Declining { voltage: f64, rate: f64 },
+}
+
+impl std::fmt::Display for BatteryWarning {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low { voltage } => write!(fmt, "battery voltage is low ({:.2} V)", voltage),
+            Self::Declining { voltage, rate } => write!(
+                fmt,
+                "battery voltage is declining rapidly ({:.2} V, {:.2} V/h)",
+                voltage, rate
+            ),
+        }
+    }
+}
+
+// locations not flagged as outliers
+pub fn without_outliers(locations: &[super::BalloonLocation]) -> Vec<super::BalloonLocation> {
+    locations
+        .iter()
+        .filter(|location| location.data.status != super::PacketStatus::Outlier)
+        .map(|location| location.to_owned())
+        .collect()
+}
+
+pub fn with_voltage(locations: &[super::BalloonLocation]) -> Vec<super::BalloonLocation> {
+    without_outliers(locations)
+        .into_iter()
+        .filter(|location| {
+            location
+                .data
+                .telemetry
+                .as_ref()
+                .is_some_and(|telemetry| telemetry.voltage.is_some())
         })
         .collect()
 }
 
+pub fn voltages(locations: &[super::BalloonLocation]) -> Vec<f64> {
+    locations
+        .iter()
+        .filter_map(|location| location.data.telemetry.as_ref()?.voltage)
+        .collect()
+}
+
+pub fn with_altitude(locations: &[super::BalloonLocation]) -> Vec<super::BalloonLocation> {
+    without_outliers(locations)
+        .into_iter()
+        .filter(|location| location.location.altitude.is_some())
+        .collect()
+}
+
 pub fn intervals(locations: &[super::BalloonLocation]) -> Vec<chrono::Duration> {
     let mut values = vec![];
 
+    let locations = &without_outliers(locations);
+
     let mut index = 0;
     let mut current = match locations.first() {
         Some(first) => first,
@@ -134,7 +640,7 @@ pub fn intervals(locations: &[super::BalloonLocation]) -> Vec<chrono::Duration>
 }
 
 pub fn altitudes(locations: &[super::BalloonLocation]) -> Vec<f64> {
-    locations
+    without_outliers(locations)
         .iter()
         .filter_map(|locations| locations.location.altitude)
         .collect()
@@ -143,6 +649,8 @@ pub fn altitudes(locations: &[super::BalloonLocation]) -> Vec<f64> {
 pub fn ascents(locations: &[super::BalloonLocation]) -> Vec<f64> {
     let mut values = vec![];
 
+    let locations = &with_altitude(locations);
+
     let mut index = 0;
     let mut current = match locations.first() {
         Some(first) => first,
@@ -181,9 +689,24 @@ pub fn ascent_rates(locations: &[super::BalloonLocation]) -> Vec<f64> {
         .collect()
 }
 
+// centered moving average over the given number of values, truncated at either end
+pub fn smoothed(values: &[f64], window: usize) -> Vec<f64> {
+    let before = window.saturating_sub(1) / 2;
+    let after = window.saturating_sub(1) - before;
+    (0..values.len())
+        .map(|index| {
+            let neighbors =
+                &values[index.saturating_sub(before)..(index + after + 1).min(values.len())];
+            neighbors.iter().sum::<f64>() / neighbors.len() as f64
+        })
+        .collect()
+}
+
 pub fn overground_distances(locations: &[super::BalloonLocation]) -> Vec<f64> {
     let mut values = vec![];
 
+    let locations = &without_outliers(locations);
+
     let mut index = 0;
     let mut current = match locations.first() {
         Some(first) => first,
@@ -223,3 +746,553 @@ pub fn ground_speeds(locations: &[super::BalloonLocation]) -> Vec<f64> {
         .filter(|value| value.is_finite())
         .collect()
 }
+
+// east and north components (m/s) of the drift between consecutive locations with altitude, at the mean altitude of each pair;
+// while ascending or descending, this approximates the wind at that altitude
+pub fn drift_velocities(locations: &[super::BalloonLocation]) -> Vec<(f64, f64, f64)> {
+    let locations = with_altitude(locations);
+
+    locations
+        .windows(2)
+        .filter_map(|pair| {
+            let (current, next) = (&pair[0], &pair[1]);
+            let seconds =
+                (next.location.time - current.location.time).num_milliseconds() as f64 / 1000.0;
+            if seconds <= 0.0 {
+                return None;
+            }
+
+            let current_point: geo::Point = current.location.coord.into();
+            let next_point: geo::Point = next.location.coord.into();
+            let speed = current_point.geodesic_distance(&next_point) / seconds;
+            let bearing = if speed > 0.0 {
+                current.location.bearing_to(&next.location).to_radians()
+            } else {
+                0.0
+            };
+
+            Some((
+                (current.location.altitude? + next.location.altitude?) / 2.0,
+                speed * bearing.sin(),
+                speed * bearing.cos(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(seconds: i64, voltage: Option<f64>) -> crate::location::BalloonLocation {
+        location_at_altitude(seconds, 1000.0, voltage)
+    }
+
+    fn location_at_altitude(
+        seconds: i64,
+        altitude: f64,
+        voltage: Option<f64>,
+    ) -> crate::location::BalloonLocation {
+        let mut data = crate::location::BalloonData::new(
+            Some("W3EAX-11".to_string()),
+            None,
+            None,
+            None,
+            crate::location::LocationSource::None,
+        );
+        data.telemetry = voltage.map(|voltage| crate::location::telemetry::Telemetry {
+            voltage: Some(voltage),
+            ..Default::default()
+        });
+
+        crate::location::BalloonLocation {
+            location: crate::location::Location {
+                time: chrono::DateTime::parse_from_rfc3339("2023-05-19T12:00:00Z")
+                    .unwrap()
+                    .with_timezone(&chrono::Local)
+                    + chrono::Duration::seconds(seconds),
+                coord: geo::coord! { x: -77.0, y: 39.0 },
+                altitude: Some(altitude),
+            },
+            data,
+        }
+    }
+
+    #[test]
+    fn test_declining_voltage() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, voltage) in [1.60, 1.58, 1.55, 1.51, 1.46, 1.40].iter().enumerate() {
+            track.push(location(index as i64 * 600, Some(*voltage)));
+        }
+        // packet without telemetry should not affect the trend
+        track.push(location(3500, None));
+
+        let trend = track.voltage_trend().unwrap();
+        assert!((trend - -0.24).abs() < 1e-6);
+
+        assert_eq!(
+            track.battery_warning(None, 0.1),
+            Some(BatteryWarning::Declining {
+                voltage: 1.40,
+                rate: trend
+            })
+        );
+        assert_eq!(track.battery_warning(None, 0.5), None);
+        assert_eq!(
+            track.battery_warning(Some(1.45), 0.5),
+            Some(BatteryWarning::Low { voltage: 1.40 })
+        );
+    }
+
+    #[test]
+    fn test_no_voltage() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        track.push(location(0, None));
+        track.push(location(60, None));
+
+        assert_eq!(track.voltage_trend(), None);
+        assert_eq!(track.battery_warning(Some(1.0), 0.1), None);
+    }
+
+    #[test]
+    fn test_estimated_time_to_ground() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [20000.0, 19460.0, 18920.0].iter().enumerate() {
+            track.push(location_at_altitude(index as i64 * 60, *altitude, None));
+        }
+
+        // 18920 m at 9 m/s
+        assert_eq!(
+            track.estimated_time_to_ground(),
+            Some(chrono::Duration::milliseconds(2102222))
+        );
+
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [1000.0, 1300.0, 1600.0].iter().enumerate() {
+            track.push(location_at_altitude(index as i64 * 60, *altitude, None));
+        }
+        assert_eq!(track.estimated_time_to_ground(), None);
+    }
+
+    #[test]
+    fn test_burst() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [1000.0, 10000.0, 20000.0, 30000.0].iter().enumerate() {
+            track.push(location_at_altitude(index as i64 * 600, *altitude, None));
+        }
+        assert!(track.burst().is_none());
+
+        for (index, altitude) in [25000.0, 15000.0].iter().enumerate() {
+            track.push(location_at_altitude(
+                (index as i64 + 4) * 600,
+                *altitude,
+                None,
+            ));
+        }
+        let burst = track.burst().unwrap();
+        assert_eq!(burst.location.altitude, Some(30000.0));
+        assert_eq!(burst.location.time, track.locations[3].location.time);
+    }
+
+    #[test]
+    fn test_no_burst_when_floating() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [1000.0, 10000.0, 20000.0, 30000.0, 29990.0, 30000.0, 29995.0]
+            .iter()
+            .enumerate()
+        {
+            track.push(location_at_altitude(index as i64 * 600, *altitude, None));
+        }
+        assert!(!track.ascending());
+        assert!(track.burst().is_none());
+    }
+
+    #[test]
+    fn test_outliers() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [1000.0, 1300.0, 1600.0, 1900.0].iter().enumerate() {
+            track.push(location_at_altitude(index as i64 * 60, *altitude, None));
+        }
+        // corrupt position between two good packets
+        track.locations[2].location.coord = geo::coord! { x: -70.0, y: 39.0 };
+
+        track.flag_outliers(150.0, 60.0);
+        let statuses: Vec<&crate::location::PacketStatus> = track
+            .locations
+            .iter()
+            .map(|location| &location.data.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                &crate::location::PacketStatus::None,
+                &crate::location::PacketStatus::None,
+                &crate::location::PacketStatus::Outlier,
+                &crate::location::PacketStatus::None,
+            ]
+        );
+
+        assert_eq!(overground_distances(&track.locations), vec![0.0, 0.0]);
+        assert_eq!(ascent_rates(&track.locations), vec![5.0, 5.0]);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        assert_eq!(smoothed(&[1.0, 2.0, 3.0], 1), vec![1.0, 2.0, 3.0]);
+        assert_eq!(smoothed(&[1.0, 2.0, 3.0], 0), vec![1.0, 2.0, 3.0]);
+        assert_eq!(smoothed(&[3.0, 6.0, 0.0, 6.0], 3), vec![4.5, 3.0, 4.0, 3.0]);
+        assert_eq!(smoothed(&[], 3), Vec::<f64>::new());
+
+        // irregular packet timing makes the raw rates noisy
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (seconds, altitude) in [(0, 1000.0), (60, 1300.0), (61, 1300.0), (120, 1600.0)] {
+            track.push(location_at_altitude(seconds, altitude, None));
+        }
+        assert_eq!(ascent_rates(&track.locations), vec![5.0, 0.0, 300.0 / 59.0]);
+        track.smoothing_window = 1;
+        assert!(!track.ascending());
+
+        track.smoothing_window = 3;
+        let smoothed = track.smoothed_ascent_rates();
+        assert!(smoothed.iter().all(|ascent_rate| ascent_rate > &2.0));
+        assert!(track.ascending());
+    }
+
+    #[test]
+    fn test_ascent_thresholds() {
+        let track_with_rates = |ascent_rates: &[f64]| {
+            let mut track = BalloonTrack::new("W3EAX-11".to_string());
+            track.smoothing_window = 1;
+            let mut altitude = 10000.0;
+            track.push(location_at_altitude(0, altitude, None));
+            for (index, ascent_rate) in ascent_rates.iter().enumerate() {
+                altitude += ascent_rate * 100.0;
+                track.push(location_at_altitude(
+                    (index as i64 + 1) * 100,
+                    altitude,
+                    None,
+                ));
+            }
+            track
+        };
+
+        let track = track_with_rates(&[]);
+        assert!(!track.ascending());
+        assert!(!track.descending());
+
+        // floating, slowly rising or sinking
+        for ascent_rate in [0.1, 0.0, -0.1, 0.2, -0.2] {
+            let track = track_with_rates(&[ascent_rate, ascent_rate]);
+            assert!(!track.ascending(), "{:}", ascent_rate);
+            assert!(!track.descending(), "{:}", ascent_rate);
+        }
+
+        let track = track_with_rates(&[0.3, 0.3]);
+        assert!(track.ascending());
+        assert!(!track.descending());
+
+        let track = track_with_rates(&[-0.3, -0.3]);
+        assert!(!track.ascending());
+        assert!(track.descending());
+
+        // only the latest samples count
+        let mut track = track_with_rates(&[5.0, 5.0, -0.3]);
+        assert!(!track.ascending());
+        assert!(!track.descending());
+        track.ascent_rate_samples = 1;
+        assert!(track.descending());
+
+        let mut track = track_with_rates(&[0.3, 0.3]);
+        track.ascent_rate_threshold = 0.5;
+        assert!(!track.ascending());
+    }
+
+    #[test]
+    fn test_landed() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        // sitting on the launch pad
+        for index in 0..4 {
+            track.push(location_at_altitude(index * 60, 100.0, None));
+        }
+        assert!(!track.landed());
+
+        // flattening out after descent
+        for (index, altitude) in [20000.0, 8000.0, 1200.0, 600.0, 200.0, 200.0]
+            .iter()
+            .enumerate()
+        {
+            track.push(location_at_altitude(
+                (index as i64 + 4) * 600,
+                *altitude,
+                None,
+            ));
+        }
+        // still settling from 600 m
+        assert!(!track.landed());
+
+        track.push(location_at_altitude(6000, 200.0, None));
+        let landing = track.landing().unwrap();
+        assert_eq!(landing.location.altitude, Some(200.0));
+        assert_eq!(landing.location.time, track.locations[8].location.time);
+
+        // dragged along the ground
+        let mut dragged = location_at_altitude(6600, 200.0, None);
+        dragged.location.coord.x += 0.1;
+        track.push(dragged);
+        assert!(!track.landed());
+
+        // higher landing site
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [20000.0, 2500.0, 2500.0, 2500.0].iter().enumerate() {
+            track.push(location_at_altitude(index as i64 * 600, *altitude, None));
+        }
+        assert!(!track.landed());
+        track.landing.maximum_altitude = 3000.0;
+        assert!(track.landed());
+    }
+
+    #[test]
+    fn test_floating() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        // ascending at 5 m/s
+        for index in 0..5 {
+            track.push(location_at_altitude(
+                index * 600,
+                20000.0 + index as f64 * 3000.0,
+                None,
+            ));
+        }
+        assert!(track.ascending());
+        assert!(!track.floating());
+
+        // levelling off at 32 km, drifting a little up and down
+        for (index, altitude) in [32100.0, 31900.0, 32050.0, 31950.0].iter().enumerate() {
+            track.push(location_at_altitude(
+                (index as i64 + 5) * 600,
+                *altitude,
+                None,
+            ));
+        }
+        assert!(!track.ascending());
+        assert!(!track.descending());
+        assert!(track.floating());
+
+        // a tighter tolerance excludes the drift
+        track.float_uncertainty = 100.0;
+        assert!(!track.floating());
+        track.float_uncertainty = 500.0;
+
+        // level, but below the float altitude
+        track.float.minimum_altitude = 35000.0;
+        assert!(!track.floating());
+        track.float.minimum_altitude = 5000.0;
+
+        // burst
+        track.push(location_at_altitude(5400, 25000.0, None));
+        track.push(location_at_altitude(6000, 18000.0, None));
+        assert!(track.descending());
+        assert!(!track.floating());
+    }
+
+    #[test]
+    fn test_prediction_error() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        for (index, altitude) in [20000.0, 8000.0, 1200.0].iter().enumerate() {
+            track.push(location_at_altitude(index as i64 * 600, *altitude, None));
+        }
+        let mut predicted_landing = location_at_altitude(1800, 200.0, None);
+        predicted_landing.location.coord.y += 0.01;
+        track.prediction = Some(vec![predicted_landing]);
+        assert_eq!(track.prediction_error(), None);
+        assert_eq!(track.touchdown_error(), None);
+
+        for index in 3..6 {
+            track.push(location_at_altitude(index * 600, 200.0, None));
+        }
+        assert!(track.landed());
+        // 0.01 degrees of latitude
+        assert!((track.prediction_error().unwrap() - 1110.0).abs() < 5.0);
+        assert!((track.touchdown_error().unwrap() - 1110.0).abs() < 5.0);
+        assert_eq!(track.touchdown_error(), None);
+
+        // a track read after it landed was never seen descending
+        let mut restarted = BalloonTrack::new("W3EAX-11".to_string());
+        for location in &track.locations {
+            restarted.push(location.to_owned());
+        }
+        restarted.prediction = track.prediction.to_owned();
+        assert_eq!(restarted.touchdown_error(), None);
+    }
+
+    #[test]
+    fn test_heading() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        track.push(location(0, None));
+        assert_eq!(track.heading(), None);
+
+        // stationary
+        track.push(location(60, None));
+        assert_eq!(track.heading(), None);
+
+        let mut east = location(120, None);
+        east.location.coord.x += 0.01;
+        track.push(east);
+        assert!((track.heading().unwrap() - 90.0).abs() < 0.1);
+
+        let mut reported = location(180, None);
+        reported.location.coord.x += 0.02;
+        reported.data.course = Some(45.0);
+        track.push(reported);
+        assert_eq!(track.heading(), Some(45.0));
+    }
+
+    #[test]
+    fn test_drift_velocities() {
+        let mut locations = vec![];
+        for (index, altitude) in [1000.0, 1300.0, 1600.0].iter().enumerate() {
+            let mut location = location_at_altitude(index as i64 * 60, *altitude, None);
+            location.location.coord = geo::coord! { x: -77.0, y: 39.0 + index as f64 * 0.01 };
+            locations.push(location);
+        }
+        // drifting east at the same time as a repeated packet
+        let mut east = location_at_altitude(180, 1900.0, None);
+        east.location.coord = geo::coord! { x: -76.99, y: 39.02 };
+        locations.push(east.clone());
+        locations.push(east);
+
+        let velocities = drift_velocities(&locations);
+        assert_eq!(velocities.len(), 3);
+
+        // about 1110 m north every 60 seconds
+        let (altitude, east, north) = velocities[0];
+        assert_eq!(altitude, 1150.0);
+        assert!(east.abs() < 0.01);
+        assert!((north - 18.5).abs() < 0.1);
+
+        // about 865 m east every 60 seconds
+        let (altitude, east, north) = velocities[2];
+        assert_eq!(altitude, 1750.0);
+        assert!((east - 14.4).abs() < 0.1);
+        assert!(north.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_totals() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        assert_eq!(track.total_overground_distance(), 0.0);
+        assert_eq!(track.max_altitude(), None);
+
+        for (index, altitude) in [1000.0, 3000.0, 2500.0, 4000.0].iter().enumerate() {
+            let mut location = location_at_altitude(index as i64 * 60, *altitude, None);
+            location.location.coord = geo::coord! { x: -77.0, y: 39.0 + index as f64 * 0.01 };
+            track.push(location);
+        }
+
+        assert!((track.total_overground_distance() - 3331.0).abs() < 10.0);
+        assert_eq!(track.total_ascent(), 3500.0);
+        assert_eq!(track.max_altitude(), Some(4000.0));
+        assert_eq!(track.min_altitude(), Some(1000.0));
+    }
+
+    #[test]
+    fn test_resample() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        assert!(track.resample(chrono::Duration::seconds(30)).is_empty());
+
+        // irregular intervals of 20 and 100 seconds
+        for (seconds, altitude, latitude) in
+            [(0, 1000.0, 39.0), (20, 1100.0, 39.02), (120, 1600.0, 39.12)]
+        {
+            let mut location = location_at_altitude(seconds, altitude, None);
+            location.location.coord = geo::coord! { x: -77.0, y: latitude };
+            track.push(location);
+        }
+
+        let resampled = track.resample(chrono::Duration::seconds(30));
+        assert_eq!(resampled.len(), 5);
+        for (index, location) in resampled.iter().enumerate() {
+            assert_eq!(
+                location.location.time - track.locations[0].location.time,
+                chrono::Duration::seconds(index as i64 * 30)
+            );
+            assert_eq!(location.data.callsign, Some("W3EAX-11".to_string()));
+        }
+        assert_eq!(resampled[0].location.altitude, Some(1000.0));
+        assert_eq!(resampled[1].location.altitude, Some(1150.0));
+        assert!((resampled[4].location.coord.y - 39.12).abs() < 1e-9);
+        assert!((resampled[2].location.coord.y - 39.06).abs() < 1e-9);
+
+        assert!(track.resample(chrono::Duration::zero()).is_empty());
+    }
+
+    #[test]
+    fn test_remaining_prediction() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        track.prediction = Some(
+            (0..6)
+                .map(|index| location_at_altitude(index * 600, index as f64 * 3000.0, None))
+                .collect(),
+        );
+        assert_eq!(track.remaining_prediction().unwrap().len(), 6);
+
+        track.push(location_at_altitude(1500, 8000.0, None));
+        let remaining = track.remaining_prediction().unwrap();
+        assert_eq!(remaining.len(), 3);
+        assert_eq!(remaining[0].location.altitude, Some(9000.0));
+
+        // nothing left to show once the prediction has passed
+        track.push(location_at_altitude(2700, 16000.0, None));
+        assert!(track.remaining_prediction().is_none());
+    }
+
+    #[test]
+    fn test_descent_prediction() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        track.push(location_at_altitude(0, 20000.0, None));
+        track.descent_prediction = Some(
+            (0..4)
+                .map(|index| {
+                    location_at_altitude(index * 600, 20000.0 - index as f64 * 6000.0, None)
+                })
+                .collect(),
+        );
+        let remaining = track.remaining_predictions();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].0, "descent");
+        assert_eq!(remaining[0].1.len(), 4);
+
+        // a repeated packet keeps it, a new one discards it
+        track.push(location_at_altitude(0, 20000.0, None));
+        assert!(track.descent_prediction.is_some());
+        track.push(location_at_altitude(60, 20300.0, None));
+        assert!(track.descent_prediction.is_none());
+    }
+
+    #[test]
+    fn test_landing_zone() {
+        let mut track = BalloonTrack::new("W3EAX-11".to_string());
+        assert!(track.landing_zone().is_none());
+
+        let landing = |x: f64, y: f64| {
+            let mut location = location_at_altitude(3600, 0.0, None);
+            location.location.coord = geo::coord! { x: x, y: y };
+            location
+        };
+        track.prediction = Some(vec![location(0, None), landing(-77.0, 39.0)]);
+        track.predicted_landings = vec![
+            landing(-77.1, 39.0),
+            landing(-77.0, 39.1),
+            landing(-77.1, 39.1),
+            // inside the others
+            landing(-77.05, 39.05),
+        ];
+
+        let zone = track.landing_zone().unwrap();
+        // closed ring of the four corners
+        assert_eq!(zone.exterior().0.len(), 5);
+        assert!(geo::Contains::contains(
+            &zone,
+            &geo::point! { x: -77.05, y: 39.05 }
+        ));
+    }
+}
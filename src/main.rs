@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 #![allow(unused_assignments)]
 
+#[cfg(feature = "api")]
+mod api;
 mod configuration;
 mod connection;
 mod location;
@@ -10,7 +12,7 @@ mod retrieve;
 mod tui;
 mod utilities;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 
 lazy_static::lazy_static! {
     pub static ref DEFAULT_INTERVAL: chrono::Duration = chrono::Duration::seconds(60);
@@ -31,48 +33,137 @@ enum Command {
     Start {
         /// file path to configuration
         config_file: std::path::PathBuf,
+        /// run without the terminal interface, printing log messages to stdout
+        #[arg(long)]
+        headless: bool,
+        /// retrieve from every connection once, write the output files, print a summary of each track, and exit
+        #[arg(long)]
+        once: bool,
+    },
+    /// check a configuration file for errors without running it
+    Validate {
+        /// file path to configuration
+        config_file: std::path::PathBuf,
     },
     /// retrieve a balloon prediction from the given API - negative values must be preceded with a `-- `, i.e. `-- -79`
     Predict {
         /// start time i.e. `2023-08-16T10:00:00`
-        time: chrono::NaiveDateTime,
+        #[arg(required_unless_present_any = ["list_datasets", "config"])]
+        time: Option<chrono::NaiveDateTime>,
         /// start longitude
-        longitude: f64,
+        #[arg(required_unless_present_any = ["list_datasets", "config"])]
+        longitude: Option<f64>,
         /// start latitude
-        latitude: f64,
+        #[arg(required_unless_present_any = ["list_datasets", "config"])]
+        latitude: Option<f64>,
         /// start altitude
         #[arg(short, long)]
         altitude: Option<f64>,
         /// expected average ascent rate
-        ascent_rate: f64,
+        #[arg(required_unless_present_any = ["list_datasets", "config"])]
+        ascent_rate: Option<f64>,
         /// expected burst altitude
-        burst_altitude: f64,
+        #[arg(required_unless_present_any = ["list_datasets", "config"])]
+        burst_altitude: Option<f64>,
         /// descent rate at sea level
-        sea_level_descent_rate: f64,
+        #[arg(required_unless_present_any = ["list_datasets", "config"])]
+        sea_level_descent_rate: Option<f64>,
         /// desired float altitude
         #[arg(long)]
         float_altitude: Option<f64>,
         /// desired float duration in seconds
         #[arg(long)]
         float_duration: Option<f64>,
+        /// file path to write the prediction to (`.geojson` or `.csv`), instead of printing it
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// format in which to print the prediction
+        #[arg(long, value_enum, default_value_t = PredictionFormat::Text)]
+        format: PredictionFormat,
+        /// forecast dataset to use, by its time i.e. `2023-08-16T06:00:00Z`
+        #[arg(long)]
+        dataset: Option<chrono::DateTime<chrono::Utc>>,
+        /// list the forecast datasets available from the API, instead of retrieving a prediction
+        #[arg(long, exclusive = true)]
+        list_datasets: bool,
+        /// configuration file whose `prediction` supplies any values not given on the command line
+        #[arg(short, long)]
+        config: Option<std::path::PathBuf>,
     },
-    /// write an empty configuration file
+    /// write a configuration file with the default settings, annotated with a description of each section
     Write {
         /// file path to configuration
         filename: std::path::PathBuf,
     },
+    /// print a shell completion script
+    Completions {
+        /// shell for which to generate completions
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Clone, clap::ValueEnum)]
+enum PredictionFormat {
+    /// one line per point
+    Text,
+    /// JSON array of points
+    Json,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let arguments = PacketravenCommand::parse();
+fn main() {
+    // print errors as messages rather than as debug output
+    if let Err(error) = run(PacketravenCommand::parse()) {
+        eprintln!("{:}", error);
+        std::process::exit(1);
+    }
+}
 
+// print each problem with the configuration, failing if there are any
+fn check_configuration(
+    configuration: &configuration::RunConfiguration,
+    config_file: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let errors = configuration.validate();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        for error in &errors {
+            println!("{:}", error);
+        }
+        Err(format!(
+            "found {:} error(s) in {:}",
+            errors.len(),
+            config_file.to_string_lossy()
+        )
+        .into())
+    }
+}
+
+fn run(arguments: PacketravenCommand) -> Result<(), Box<dyn std::error::Error>> {
     match arguments.command {
-        Command::Start { config_file } => {
-            let file = std::fs::File::open(config_file).unwrap();
-            let configuration: crate::configuration::RunConfiguration =
-                serde_yaml::from_reader(file).expect("error reading configuration");
+        Command::Start {
+            config_file,
+            headless,
+            once,
+        } => {
+            let configuration = configuration::read_configuration(&config_file)?;
+
+            check_configuration(&configuration, &config_file)?;
 
-            tui::run(configuration, *LOG_LEVEL)?;
+            if once {
+                tui::run_once(configuration, *LOG_LEVEL)?;
+            } else if headless {
+                tui::run_headless(configuration, &config_file, *LOG_LEVEL)?;
+            } else {
+                tui::run(configuration, &config_file, *LOG_LEVEL)?;
+            }
+            Ok(())
+        }
+        Command::Validate { config_file } => {
+            let configuration = configuration::read_configuration(&config_file)?;
+
+            check_configuration(&configuration, &config_file)?;
+            println!("{:} is valid", config_file.to_string_lossy());
             Ok(())
         }
         Command::Predict {
@@ -85,50 +176,169 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             sea_level_descent_rate,
             float_altitude,
             float_duration,
+            output,
+            format,
+            dataset,
+            list_datasets,
+            config,
         } => {
+            let configuration = match &config {
+                Some(path) => Some(configuration::read_configuration(path)?),
+                None => None,
+            };
+            let http = configuration
+                .as_ref()
+                .map(|configuration| configuration.http.to_owned())
+                .unwrap_or_default();
+
+            if list_datasets {
+                for dataset in prediction::tawhiri::available_datasets(
+                    &prediction::tawhiri::DEFAULT_API_URL,
+                    &http,
+                )? {
+                    println!("{:}", dataset.to_rfc3339());
+                }
+                return Ok(());
+            }
+
+            let configured = match (&config, configuration) {
+                (Some(path), Some(configuration)) => match configuration.prediction {
+                    Some(prediction) => Some(prediction.predictions().0.to_owned()),
+                    None => {
+                        return Err(format!(
+                            "{:} has no `prediction` section",
+                            path.to_string_lossy()
+                        )
+                        .into())
+                    }
+                },
+                _ => None,
+            };
+
+            // values given on the command line take precedence over the configuration;
+            // one or the other is present unless listing datasets
             let start = location::Location {
-                time: time.and_local_timezone(chrono::Local).unwrap(),
-                coord: geo::coord! {x:longitude,y:latitude},
-                altitude,
+                time: match time {
+                    Some(time) => time.and_local_timezone(chrono::Local).unwrap(),
+                    None => configured.as_ref().unwrap().start.time,
+                },
+                coord: geo::coord! {
+                    x: longitude.unwrap_or_else(|| configured.as_ref().unwrap().start.coord.x),
+                    y: latitude.unwrap_or_else(|| configured.as_ref().unwrap().start.coord.y),
+                },
+                altitude: altitude.or_else(|| {
+                    configured
+                        .as_ref()
+                        .and_then(|prediction| prediction.start.altitude)
+                }),
             };
+            let float = configured
+                .as_ref()
+                .and_then(|prediction| prediction.float.as_ref());
             let profile = prediction::FlightProfile::new(
-                ascent_rate,
-                float_altitude,
-                match float_duration {
-                    Some(seconds) => Some(chrono::Duration::seconds(seconds as i64)),
-                    None => None,
-                },
-                None,
-                burst_altitude,
-                sea_level_descent_rate,
+                ascent_rate.unwrap_or_else(|| configured.as_ref().unwrap().profile.ascent_rate),
+                float_altitude.or(float.map(|float| float.altitude)),
+                float_duration
+                    .map(|seconds| chrono::Duration::seconds(seconds as i64))
+                    .or(float.map(|float| float.duration)),
+                float.and_then(|float| float.uncertainty),
+                burst_altitude
+                    .unwrap_or_else(|| configured.as_ref().unwrap().profile.burst_altitude),
+                sea_level_descent_rate
+                    .unwrap_or_else(|| configured.as_ref().unwrap().profile.descent_rate()),
             );
 
-            let query = prediction::tawhiri::TawhiriQuery::new(
-                &start, &profile, None, None, None, false, None,
+            let mut query = prediction::tawhiri::TawhiriQuery::new(
+                &start, &profile, dataset, None, None, false, None,
             );
+            if let Some(configured) = &configured {
+                if let Some(api_url) = &configured.api_url {
+                    query.query.api_url = api_url.to_owned();
+                }
+                if let Some(coordinate_precision) = configured.coordinate_precision {
+                    query.coordinate_precision = coordinate_precision;
+                }
+            }
 
-            match query.retrieve_prediction() {
-                Ok(prediction) => {
-                    for location in prediction {
-                        println!(
-                            "{:}, {:.1}, {:.1}, {:.1}",
-                            location.location.time.format("%Y-%m-%d %H:%M:%S"),
-                            location.location.coord.x,
-                            location.location.coord.y,
-                            location.location.altitude.unwrap_or(0.0)
-                        );
+            let prediction = match query.retrieve_prediction(&http) {
+                Ok((prediction, metadata)) => {
+                    for warning in metadata.warnings {
+                        eprintln!("warning: {:}", warning);
                     }
+                    prediction
                 }
                 Err(error) => return Err(Box::new(error)),
+            };
+
+            match output {
+                Some(path) => {
+                    let contents = match path.extension().and_then(|extension| extension.to_str()) {
+                        Some("geojson") | Some("json") => {
+                            connection::text::file::locations_geojson_featurecollection(
+                                prediction.iter().collect(),
+                            )
+                            .to_string()
+                        }
+                        Some("csv") => {
+                            connection::text::file::locations_csv(prediction.iter().collect())
+                        }
+                        _ => {
+                            return Err(format!(
+                                "unsupported output file extension: {:}",
+                                path.to_string_lossy()
+                            )
+                            .into())
+                        }
+                    };
+                    std::fs::write(path, contents)?;
+                }
+                None => match format {
+                    PredictionFormat::Text => {
+                        for location in &prediction {
+                            println!(
+                                "{:}, {:.1}, {:.1}, {:.1}",
+                                location.location.time.format("%Y-%m-%d %H:%M:%S"),
+                                location.location.coord.x,
+                                location.location.coord.y,
+                                location.location.altitude.unwrap_or(0.0)
+                            );
+                        }
+                    }
+                    PredictionFormat::Json => {
+                        let points: Vec<serde_json::Value> = prediction
+                            .iter()
+                            .map(|location| {
+                                serde_json::json!({
+                                    "time": location.location.time.to_rfc3339(),
+                                    "longitude": location.location.coord.x,
+                                    "latitude": location.location.coord.y,
+                                    "altitude": location.location.altitude,
+                                })
+                            })
+                            .collect();
+                        println!("{:}", serde_json::Value::Array(points));
+                    }
+                },
             }
 
             Ok(())
         }
         Command::Write { filename } => {
-            let configuration = configuration::RunConfiguration::default();
-            let file = std::fs::File::create(filename).unwrap();
-
-            serde_yaml::to_writer(file, &configuration).unwrap();
+            let failed = |message: String| configuration::ConfigurationError::WriteFailure {
+                path: filename.to_string_lossy().to_string(),
+                message,
+            };
+            std::fs::write(&filename, configuration::annotated_template())
+                .map_err(|error| failed(error.to_string()))?;
+            Ok(())
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut PacketravenCommand::command(),
+                env!("CARGO_PKG_NAME"),
+                &mut std::io::stdout(),
+            );
             Ok(())
         }
     }
@@ -1,38 +1,216 @@
-#[derive(Clone)]
-pub struct FreefallEstimate {
-    pub ascent_rate: f64,
-    pub ascent_rate_uncertainty: f64,
-    pub time_to_ground: chrono::Duration,
+lazy_static::lazy_static! {
+    // International Standard Atmosphere at sea level
+    pub static ref SEA_LEVEL_AIR_DENSITY: f64 = 1.225;
+    static ref SEA_LEVEL_TEMPERATURE: f64 = 288.15;
+    static ref SEA_LEVEL_PRESSURE: f64 = 101325.0;
+    // temperature lapse rate (K/m) of the troposphere, which ends at the tropopause (m)
+    static ref LAPSE_RATE: f64 = 0.0065;
+    static ref TROPOPAUSE: f64 = 11000.0;
+    static ref GAS_CONSTANT: f64 = 287.053;
+    pub static ref GRAVITY: f64 = 9.80665;
+    // sea-level descent rate (m/s) of a typical payload under parachute, based on historical flight data
+    static ref DEFAULT_SEA_LEVEL_DESCENT_RATE: f64 = 6.0;
+    // relative uncertainty of the descent rate, when inferred from historical flights or from the payload
+    static ref DEFAULT_UNCERTAINTY: f64 = 0.2;
+    static ref PAYLOAD_UNCERTAINTY: f64 = 0.1;
 }
 
-impl FreefallEstimate {
-    // estimation of freefall w/ parachute, based on historical flight data
-    pub fn new(altitude: f64) -> FreefallEstimate {
-        // `dh/dt` based on historical flight data
-        let ascent_rate = -5.8e-08 * altitude.powi(2) - 6.001;
+// density of air (kg/m^3) at the given altitude, from the International Standard Atmosphere;
+// the lower stratosphere is isothermal
+pub fn air_density(altitude: f64) -> f64 {
+    let altitude = altitude.max(0.0);
+    let exponent = *GRAVITY / (*GAS_CONSTANT * *LAPSE_RATE);
+    let tropopause_temperature = *SEA_LEVEL_TEMPERATURE - *LAPSE_RATE * *TROPOPAUSE;
+    let (temperature, pressure) = if altitude <= *TROPOPAUSE {
+        let temperature = *SEA_LEVEL_TEMPERATURE - *LAPSE_RATE * altitude;
+        (
+            temperature,
+            *SEA_LEVEL_PRESSURE * (temperature / *SEA_LEVEL_TEMPERATURE).powf(exponent),
+        )
+    } else {
+        let tropopause_pressure =
+            *SEA_LEVEL_PRESSURE * (tropopause_temperature / *SEA_LEVEL_TEMPERATURE).powf(exponent);
+        (
+            tropopause_temperature,
+            tropopause_pressure
+                * (-*GRAVITY * (altitude - *TROPOPAUSE) / (*GAS_CONSTANT * tropopause_temperature))
+                    .exp(),
+        )
+    };
+    pressure / (*GAS_CONSTANT * temperature)
+}
 
-        // TODO: propagate uncertainty
-        let ascent_rate_uncertainty = (0.2 * ascent_rate).abs();
+// terminal velocity scales with the inverse square root of air density,
+// which is also how Tawhiri extrapolates its sea-level descent rate to altitude
+pub fn sea_level_equivalent(descent_rate: f64, altitude: f64) -> f64 {
+    descent_rate * (air_density(altitude) / *SEA_LEVEL_AIR_DENSITY).sqrt()
+}
 
-        // integration of `(1/(dh/dt)) dh` based on historical flight data
-        // TODO make this model better with ML
-        let time_to_ground = chrono::Duration::milliseconds(
-            (1695.02 * (9.8311e-05 * altitude).atan() * 1000.0) as i64,
-        );
+/// terminal velocity of a payload under parachute, which is faster at altitude where the air is thinner;
+/// from the drag equation, `v = sqrt(2 m g / (rho Cd A))`, so the velocity scales with `sqrt(rho0 / rho)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TerminalVelocity {
+    /// descent rate (m/s) at sea level
+    pub sea_level_descent_rate: f64,
+    /// relative uncertainty of the descent rate
+    pub uncertainty: f64,
+}
+
+impl Default for TerminalVelocity {
+    // based on historical flight data
+    fn default() -> Self {
+        Self::new(*DEFAULT_SEA_LEVEL_DESCENT_RATE, *DEFAULT_UNCERTAINTY)
+    }
+}
+
+impl TerminalVelocity {
+    pub fn new(sea_level_descent_rate: f64, uncertainty: f64) -> Self {
+        Self {
+            sea_level_descent_rate: sea_level_descent_rate.abs(),
+            uncertainty,
+        }
+    }
+
+    // from the mass of the payload divided by its drag coefficient and area (kg/m^2)
+    pub fn from_ballistic_coefficient(ballistic_coefficient: f64) -> Self {
+        Self::new(
+            (2.0 * ballistic_coefficient * *GRAVITY / *SEA_LEVEL_AIR_DENSITY).sqrt(),
+            *PAYLOAD_UNCERTAINTY,
+        )
+    }
+
+    // from the mass (kg) of the payload, and the drag coefficient and area (m^2) of its parachute
+    pub fn from_payload(mass: f64, drag_coefficient: f64, drag_area: f64) -> Self {
+        Self::from_ballistic_coefficient(mass / (drag_coefficient * drag_area))
+    }
+
+    // descent rate (m/s, positive downwards) at the given altitude
+    pub fn descent_rate(&self, altitude: f64) -> f64 {
+        self.sea_level_descent_rate * (*SEA_LEVEL_AIR_DENSITY / air_density(altitude)).sqrt()
+    }
+
+    // time to fall from the given altitude to sea level, i.e. the integral of `sqrt(rho / rho0) / v0`,
+    // in closed form over the troposphere and the isothermal stratosphere above it
+    pub fn time_to_ground(&self, altitude: f64) -> chrono::Duration {
+        let altitude = altitude.max(0.0);
+
+        // in the troposphere, the density scales with `(T / T0)^(g / (R L) - 1)`
+        let exponent = (*GRAVITY / (*GAS_CONSTANT * *LAPSE_RATE) + 1.0) / 2.0;
+        let troposphere = altitude.min(*TROPOPAUSE);
+        // distance (m) that would take as long to fall at the sea-level rate
+        let mut distance = *SEA_LEVEL_TEMPERATURE / (*LAPSE_RATE * exponent)
+            * (1.0 - (1.0 - *LAPSE_RATE * troposphere / *SEA_LEVEL_TEMPERATURE).powf(exponent));
+
+        // in the stratosphere, the density decays exponentially with the scale height
+        if altitude > *TROPOPAUSE {
+            let tropopause_temperature = *SEA_LEVEL_TEMPERATURE - *LAPSE_RATE * *TROPOPAUSE;
+            let scale_height = *GAS_CONSTANT * tropopause_temperature / *GRAVITY;
+            distance += (air_density(*TROPOPAUSE) / air_density(0.0)).sqrt()
+                * 2.0
+                * scale_height
+                * (1.0 - (-(altitude - *TROPOPAUSE) / (2.0 * scale_height)).exp());
+        }
+
+        let seconds = distance * (air_density(0.0) / *SEA_LEVEL_AIR_DENSITY).sqrt()
+            / self.sea_level_descent_rate;
+        chrono::Duration::milliseconds((seconds * 1000.0) as i64)
+    }
+
+    pub fn freefall(&self, altitude: f64) -> FreefallEstimate {
+        let descent_rate = self.descent_rate(altitude);
+        let time_to_ground = self.time_to_ground(altitude);
 
         FreefallEstimate {
-            ascent_rate,
-            ascent_rate_uncertainty,
+            ascent_rate: -descent_rate,
+            ascent_rate_uncertainty: self.uncertainty * descent_rate,
             time_to_ground,
+            // the time to ground is inversely proportional to the descent rate
+            time_to_ground_uncertainty: chrono::Duration::milliseconds(
+                (time_to_ground.num_milliseconds() as f64 * self.uncertainty) as i64,
+            ),
         }
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct FreefallEstimate {
+    /// current ascent rate (m/s), which is negative
+    pub ascent_rate: f64,
+    pub ascent_rate_uncertainty: f64,
+    pub time_to_ground: chrono::Duration,
+    pub time_to_ground_uncertainty: chrono::Duration,
+}
+
+impl FreefallEstimate {
+    // estimation of freefall w/ parachute at the given altitude, with the default terminal velocity
+    pub fn new(altitude: f64) -> FreefallEstimate {
+        TerminalVelocity::default().freefall(altitude)
+    }
+}
+
 impl crate::location::Location {
-    pub fn estimate_freefall(&self) -> FreefallEstimate {
-        FreefallEstimate::new(
+    pub fn estimate_freefall(&self, terminal_velocity: &TerminalVelocity) -> FreefallEstimate {
+        terminal_velocity.freefall(
             self.altitude
                 .expect("location must have an altitude to estimate freefall"),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_air_density() {
+        assert!((air_density(0.0) - 1.225).abs() < 0.001);
+        assert!((air_density(11000.0) - 0.3639).abs() < 0.001);
+        assert!((air_density(20000.0) - 0.0889).abs() < 0.001);
+        assert_eq!(air_density(-100.0), air_density(0.0));
+    }
+
+    #[test]
+    fn test_terminal_velocity() {
+        let terminal_velocity = TerminalVelocity::from_payload(1.5, 1.5, 0.25);
+        assert!((terminal_velocity.sea_level_descent_rate - 8.0).abs() < 0.01);
+        assert!((terminal_velocity.descent_rate(0.0) - 8.0).abs() < 0.01);
+        assert!(terminal_velocity.descent_rate(20000.0) > 3.0 * 8.0);
+
+        assert_eq!(
+            terminal_velocity.time_to_ground(0.0),
+            chrono::Duration::zero()
+        );
+        // slightly faster than at the sea-level rate, since the air is thinner on the way down
+        let seconds = terminal_velocity.time_to_ground(1000.0).num_seconds();
+        assert!((115..125).contains(&seconds));
+        // most of the time is spent in the thick air near the ground
+        assert!(
+            terminal_velocity.time_to_ground(30000.0)
+                < terminal_velocity.time_to_ground(10000.0) * 3
+        );
+
+        // matches integrating over thin layers of the atmosphere
+        for altitude in [500.0, 11000.0, 35000.0] {
+            let step = 1.0;
+            let seconds: f64 = (0..(altitude / step) as usize)
+                .map(|layer| step / terminal_velocity.descent_rate((layer as f64 + 0.5) * step))
+                .sum();
+            let closed_form = terminal_velocity
+                .time_to_ground(altitude)
+                .num_milliseconds() as f64;
+            assert!((closed_form / 1000.0 - seconds).abs() < 0.1);
+        }
+
+        // the time is bounded no matter the altitude
+        assert!(terminal_velocity.time_to_ground(f64::MAX) < chrono::Duration::hours(1));
+        assert!(terminal_velocity.time_to_ground(f64::INFINITY) < chrono::Duration::hours(1));
+
+        let freefall = FreefallEstimate::new(0.0);
+        assert!((freefall.ascent_rate + *DEFAULT_SEA_LEVEL_DESCENT_RATE).abs() < 0.001);
+        assert!((freefall.ascent_rate_uncertainty - 1.2).abs() < 0.001);
+
+        let freefall = FreefallEstimate::new(25000.0);
+        assert!(freefall.ascent_rate < -30.0);
+        assert!(freefall.time_to_ground_uncertainty > chrono::Duration::minutes(5));
+    }
+}
@@ -1,7 +1,7 @@
 pub mod tawhiri;
 
 lazy_static::lazy_static! {
-    static ref DEFAULT_FLOAT_UNCERTAINTY: f64 = 500.0;
+    pub static ref DEFAULT_FLOAT_UNCERTAINTY: f64 = 500.0;
 }
 
 #[derive(Clone)]
@@ -67,6 +67,50 @@ impl FlightProfile {
     }
 }
 
+// notes from the prediction service about how a prediction was made
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PredictionMetadata {
+    /// i.e. that the flight left the bounds of the forecast dataset
+    pub warnings: Vec<String>,
+    /// model run of the wind forecast used for the prediction
+    pub dataset_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// when the service finished the prediction
+    pub completed: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// predicted locations, along with the metadata returned with them
+pub type PredictedTrack = (crate::location::track::LocationTrack, PredictionMetadata);
+
+// a service that predicts a balloon flight
+pub trait PredictionBackend {
+    // inputs that determine the prediction, so that an earlier prediction can be reused
+    fn cache_key(&self) -> String;
+
+    fn retrieve_prediction(
+        &self,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<PredictedTrack, tawhiri::TawhiriError>;
+}
+
+// query each backend in turn, moving on to the next only when a backend returns an HTTP error
+pub fn retrieve_with_fallback(
+    backends: &[&dyn PredictionBackend],
+    cache: &mut tawhiri::PredictionCache,
+    http: &crate::configuration::HttpConfiguration,
+) -> Result<PredictedTrack, tawhiri::TawhiriError> {
+    let mut result = Err(tawhiri::TawhiriError::RequestError {
+        message: "no prediction backend configured".to_string(),
+    });
+    for backend in backends {
+        result = cache.prediction(*backend, http);
+        match result {
+            Err(tawhiri::TawhiriError::HttpError { .. }) => continue,
+            _ => break,
+        }
+    }
+    result
+}
+
 pub struct BalloonPredictionQuery {
     pub api_url: String,
     pub start: crate::location::Location,
@@ -95,3 +139,77 @@ impl BalloonPredictionQuery {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockBackend {
+        key: String,
+        available: bool,
+    }
+
+    impl PredictionBackend for MockBackend {
+        fn cache_key(&self) -> String {
+            self.key.to_owned()
+        }
+
+        fn retrieve_prediction(
+            &self,
+            _: &crate::configuration::HttpConfiguration,
+        ) -> Result<PredictedTrack, tawhiri::TawhiriError> {
+            if self.available {
+                let mut location = crate::location::BalloonLocation {
+                    location: crate::location::Location {
+                        time: chrono::Local::now(),
+                        coord: geo::coord! { x: -77.547824, y: 39.359031 },
+                        altitude: None,
+                    },
+                    data: crate::location::BalloonData::default(),
+                };
+                location.data.callsign = Some(self.key.to_owned());
+                Ok((vec![location], PredictionMetadata::default()))
+            } else {
+                Err(tawhiri::TawhiriError::HttpError {
+                    status: 404,
+                    description: "No matching dataset found.".to_string(),
+                    url: self.key.to_owned(),
+                })
+            }
+        }
+    }
+
+    #[test]
+    fn test_fallback() {
+        let primary = MockBackend {
+            key: String::from("primary"),
+            available: false,
+        };
+        let secondary = MockBackend {
+            key: String::from("secondary"),
+            available: true,
+        };
+        let mut cache = tawhiri::PredictionCache::default();
+
+        let prediction =
+            retrieve_with_fallback(&[&primary, &secondary], &mut cache, &Default::default())
+                .unwrap();
+        assert_eq!(
+            prediction.0[0].data.callsign,
+            Some(String::from("secondary"))
+        );
+
+        assert!(matches!(
+            retrieve_with_fallback(&[&primary], &mut cache, &Default::default()),
+            Err(tawhiri::TawhiriError::HttpError { .. })
+        ));
+
+        let prediction =
+            retrieve_with_fallback(&[&secondary, &primary], &mut cache, &Default::default())
+                .unwrap();
+        assert_eq!(
+            prediction.0[0].data.callsign,
+            Some(String::from("secondary"))
+        );
+    }
+}
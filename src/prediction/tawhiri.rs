@@ -1,7 +1,62 @@
+lazy_static::lazy_static! {
+    pub static ref DEFAULT_API_URL: String = String::from("https://api.v2.sondehub.org/tawhiri");
+    pub static ref DEFAULT_COORDINATE_PRECISION: usize = 2;
+    static ref DESCENT_ONLY_COORDINATE_PRECISION: usize = 6;
+    // start altitudes within this distance (m) of each other reuse the same cached prediction
+    static ref CACHE_ALTITUDE_TOLERANCE: f64 = 10.0;
+    // retrieve a new prediction after this long, even if the inputs are unchanged
+    static ref CACHE_LIFETIME: chrono::Duration = chrono::Duration::minutes(10);
+}
+
+// predictions from previous queries, keyed on their rounded inputs
+#[derive(Default)]
+pub struct PredictionCache {
+    entries: std::collections::HashMap<
+        String,
+        (
+            chrono::DateTime<chrono::Local>,
+            crate::prediction::PredictedTrack,
+        ),
+    >,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl PredictionCache {
+    pub fn prediction(
+        &mut self,
+        query: &dyn crate::prediction::PredictionBackend,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<crate::prediction::PredictedTrack, TawhiriError> {
+        self.get_or_retrieve(query.cache_key(), || query.retrieve_prediction(http))
+    }
+
+    fn get_or_retrieve(
+        &mut self,
+        key: String,
+        retrieve: impl FnOnce() -> Result<crate::prediction::PredictedTrack, TawhiriError>,
+    ) -> Result<crate::prediction::PredictedTrack, TawhiriError> {
+        let now = chrono::Local::now();
+        self.entries
+            .retain(|_, (retrieved, _)| now - *retrieved < *CACHE_LIFETIME);
+
+        if let Some((_, prediction)) = self.entries.get(&key) {
+            self.hits += 1;
+            return Ok(prediction.to_owned());
+        }
+
+        self.misses += 1;
+        let prediction = retrieve()?;
+        self.entries.insert(key, (now, prediction.to_owned()));
+        Ok(prediction)
+    }
+}
+
 pub struct TawhiriQuery {
     pub query: crate::prediction::BalloonPredictionQuery,
     pub dataset_time: Option<chrono::DateTime<chrono::Utc>>,
     pub version: Option<f64>,
+    pub coordinate_precision: usize,
 }
 
 impl TawhiriQuery {
@@ -16,7 +71,7 @@ impl TawhiriQuery {
     ) -> TawhiriQuery {
         TawhiriQuery {
             query: crate::prediction::BalloonPredictionQuery::new(
-                String::from("https://api.v2.sondehub.org/tawhiri"),
+                DEFAULT_API_URL.to_owned(),
                 start,
                 profile,
                 name,
@@ -25,9 +80,51 @@ impl TawhiriQuery {
             ),
             dataset_time,
             version,
+            coordinate_precision: *DEFAULT_COORDINATE_PRECISION,
+        }
+    }
+
+    // number of decimal places with which to send the start coordinates
+    fn launch_coordinate_precision(&self) -> usize {
+        if self.query.descent_only {
+            // a descent near the ground needs more precision than a full flight
+            self.coordinate_precision
+                .max(*DESCENT_ONLY_COORDINATE_PRECISION)
+        } else {
+            self.coordinate_precision
         }
     }
 
+    // inputs that determine the prediction, rounded so that insignificant changes share a key
+    fn cache_key(&self) -> String {
+        let precision = self.launch_coordinate_precision();
+        let altitude = self.query.start.altitude.map(|altitude| {
+            (altitude / *CACHE_ALTITUDE_TOLERANCE).round() * *CACHE_ALTITUDE_TOLERANCE
+        });
+        let profile = &self.query.profile;
+
+        format!(
+            "{:} {:.*},{:.*} {:?} {:.2}/{:.2}/{:.2} {:?}/{:?}/{:?} {:} {:?} {:?}",
+            self.query.api_url,
+            precision,
+            self.query.start.coord.x,
+            precision,
+            self.query.start.coord.y,
+            altitude,
+            profile.ascent_rate,
+            profile.burst_altitude,
+            profile.sea_level_descent_rate,
+            profile.float_altitude,
+            profile
+                .float_duration
+                .map(|duration| duration.num_seconds()),
+            self.query.float_start,
+            self.query.descent_only,
+            self.dataset_time,
+            self.version,
+        )
+    }
+
     fn parameters(&self) -> Result<Vec<(&str, String)>, TawhiriError> {
         // CUSF API requires longitude in 0-360 format
         let mut start_location = self.query.start.coord;
@@ -51,9 +148,16 @@ impl TawhiriQuery {
             false => self.query.profile.burst_altitude,
         };
 
+        let precision = self.launch_coordinate_precision();
         let mut parameters = vec![
-            ("launch_longitude", format!("{:.2}", start_location.x)),
-            ("launch_latitude", format!("{:.2}", start_location.y)),
+            (
+                "launch_longitude",
+                format!("{:.*}", precision, start_location.x),
+            ),
+            (
+                "launch_latitude",
+                format!("{:.*}", precision, start_location.y),
+            ),
             (
                 "launch_datetime",
                 self.query
@@ -100,12 +204,12 @@ impl TawhiriQuery {
 
                 parameters.push(("float_altitude", format!("{:.2}", float_altitude)));
 
-                let float_start_time = self.query.float_start.unwrap_or({
+                // time to ascend from the launch altitude to the float altitude
+                let float_start_time = self.query.float_start.unwrap_or_else(|| {
                     self.query.start.time
                         + chrono::Duration::seconds(
-                            (float_altitude
-                                - self.query.start.altitude.unwrap_or(0.0)
-                                    / self.query.profile.ascent_rate)
+                            ((float_altitude - launch_altitude.unwrap_or(0.0))
+                                / self.query.profile.ascent_rate)
                                 as i64,
                         )
                 });
@@ -121,26 +225,33 @@ impl TawhiriQuery {
         Ok(parameters)
     }
 
-    fn get(&self) -> Result<TawhiriResponse, TawhiriError> {
-        let client = reqwest::blocking::Client::builder()
-            .user_agent(crate::connection::USER_AGENT.to_owned())
-            .timeout(Some(std::time::Duration::from_secs(10)))
-            .build()
-            .unwrap();
+    fn get(
+        &self,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<TawhiriResponse, TawhiriError> {
+        let client = crate::connection::http_client(http)
+            .map_err(|message| TawhiriError::RequestError { message })?;
 
         let parameters = self.parameters();
-        let response = client
-            .get(&self.query.api_url)
-            .query(&parameters?)
-            .send()
-            .expect("error retrieving prediction");
+        let response = crate::connection::send_with_retry(
+            client.get(&self.query.api_url).query(&parameters?),
+            *crate::connection::RETRY_ATTEMPTS,
+            *crate::connection::RETRY_BACKOFF,
+        )
+        .map_err(|error| TawhiriError::RequestError {
+            message: error.to_string(),
+        })?;
         let url = response.url().to_string();
 
         match &response.status() {
             &reqwest::StatusCode::OK => {
                 // deserialize JSON into struct
                 let mut tawhiri_response: TawhiriResponse =
-                    response.json().expect("error parsing response JSON");
+                    response
+                        .json()
+                        .map_err(|error| TawhiriError::ParsingError {
+                            message: error.to_string(),
+                        })?;
 
                 // since tawhiri does not currently include a descent stage when querying a float profile,
                 // we need to query one from the end of the float stage and append it to the prediction
@@ -163,7 +274,7 @@ impl TawhiriQuery {
                                     let descent_query = TawhiriQuery::new(
                                         &float_end_location.location,
                                         &crate::prediction::FlightProfile::new_standard(
-                                            10.0,
+                                            self.query.profile.ascent_rate,
                                             float_end_location.location.altitude.unwrap(),
                                             self.query.profile.sea_level_descent_rate,
                                         ),
@@ -173,7 +284,8 @@ impl TawhiriQuery {
                                         true,
                                         None,
                                     );
-                                    let descent: TawhiriResponse = descent_query.get().unwrap();
+                                    let descent: TawhiriResponse = descent_query.get(http)?;
+                                    tawhiri_response.warnings.extend(descent.warnings);
                                     for stage in descent.prediction {
                                         if stage.stage == "descent" {
                                             tawhiri_response.prediction.push(stage);
@@ -227,8 +339,14 @@ impl TawhiriQuery {
 
     pub fn retrieve_prediction(
         &self,
-    ) -> Result<crate::location::track::LocationTrack, TawhiriError> {
-        let response = self.get()?;
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<crate::prediction::PredictedTrack, TawhiriError> {
+        let response = self.get(http)?;
+        let metadata = crate::prediction::PredictionMetadata {
+            warnings: response.warnings(),
+            dataset_time: response.request.dataset_time(),
+            completed: parse_time(&response.metadata.complete_datetime),
+        };
 
         let mut locations = vec![];
 
@@ -238,15 +356,31 @@ impl TawhiriQuery {
             }
         }
 
-        Ok(locations)
+        Ok((locations, metadata))
+    }
+}
+
+impl crate::prediction::PredictionBackend for TawhiriQuery {
+    fn cache_key(&self) -> String {
+        self.cache_key()
+    }
+
+    fn retrieve_prediction(
+        &self,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<crate::prediction::PredictedTrack, TawhiriError> {
+        self.retrieve_prediction(http)
     }
 }
 
 impl crate::location::track::BalloonTrack {
     pub fn prediction(
         &self,
-        profile: &super::FlightProfile,
-    ) -> Result<crate::location::track::LocationTrack, TawhiriError> {
+        configuration: &crate::configuration::prediction::Prediction,
+        cache: &mut PredictionCache,
+        http: &crate::configuration::HttpConfiguration,
+    ) -> Result<crate::prediction::PredictedTrack, TawhiriError> {
+        let profile = &configuration.flight_profile();
         let mut descending = self.descending() || self.falling().is_some();
 
         let float_start = if let Some(float_altitude) = profile.float_altitude {
@@ -286,17 +420,72 @@ impl crate::location::track::BalloonTrack {
             None
         };
 
-        let query = crate::prediction::tawhiri::TawhiriQuery::new(
-            &self.locations.last().unwrap().location,
-            profile,
-            None,
-            None,
-            None,
-            descending,
-            float_start,
-        );
+        let queries: Vec<TawhiriQuery> = [
+            configuration.api_url.to_owned(),
+            configuration.fallback_api_url.to_owned(),
+        ]
+        .into_iter()
+        .enumerate()
+        // the first query uses the default API if none is configured
+        .filter(|(index, api_url)| *index == 0 || api_url.is_some())
+        .map(|(_, api_url)| {
+            let mut query = TawhiriQuery::new(
+                &self.kinematic_locations().last().unwrap().location,
+                profile,
+                None,
+                None,
+                None,
+                descending,
+                float_start,
+            );
+            if let Some(api_url) = api_url {
+                query.query.api_url = api_url;
+            }
+            if let Some(coordinate_precision) = configuration.coordinate_precision {
+                query.coordinate_precision = coordinate_precision;
+            }
+            query
+        })
+        .collect();
+
+        let backends: Vec<&dyn crate::prediction::PredictionBackend> = queries
+            .iter()
+            .map(|query| query as &dyn crate::prediction::PredictionBackend)
+            .collect();
+        crate::prediction::retrieve_with_fallback(&backends, cache, http)
+    }
+}
 
-        query.retrieve_prediction()
+// times of the forecast datasets available from the given API, to pass as `dataset_time`
+pub fn available_datasets(
+    api_url: &str,
+    http: &crate::configuration::HttpConfiguration,
+) -> Result<Vec<chrono::DateTime<chrono::Utc>>, TawhiriError> {
+    let client = crate::connection::http_client(http)
+        .map_err(|message| TawhiriError::RequestError { message })?;
+
+    let response = crate::connection::send_with_retry(
+        client.get(format!("{:}/datasets", api_url.trim_end_matches('/'))),
+        *crate::connection::RETRY_ATTEMPTS,
+        *crate::connection::RETRY_BACKOFF,
+    )
+    .map_err(|error| TawhiriError::RequestError {
+        message: error.to_string(),
+    })?;
+    let url = response.url().to_string();
+
+    match response.status() {
+        reqwest::StatusCode::OK => response
+            .json::<TawhiriDatasetsResponse>()
+            .map_err(|error| TawhiriError::ParsingError {
+                message: error.to_string(),
+            })?
+            .times(),
+        status => Err(TawhiriError::HttpError {
+            status: status.as_u16(),
+            description: String::from("could not retrieve datasets"),
+            url,
+        }),
     }
 }
 
@@ -314,7 +503,47 @@ struct TawhiriResponse {
     metadata: TawhiriMetadata,
     request: TawhiriRequest,
     prediction: Vec<TawhiriPrediction>,
-    warnings: std::collections::HashMap<String, String>,
+    // each warning is either a description or an object with a `description` and a `count`
+    warnings: std::collections::HashMap<String, serde_json::Value>,
+}
+
+impl TawhiriResponse {
+    fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self
+            .warnings
+            .iter()
+            .map(
+                |(name, warning)| match warning.get("description").unwrap_or(warning).as_str() {
+                    Some(description) => format!("{:} - {:}", name, description),
+                    None => name.to_owned(),
+                },
+            )
+            .collect();
+        warnings.sort();
+        warnings
+    }
+}
+
+// `{"datasets": [...]}`, naming each dataset by its RFC 3339 time, as in the `dataset` request parameter
+#[derive(serde::Deserialize)]
+struct TawhiriDatasetsResponse {
+    datasets: Vec<String>,
+}
+
+impl TawhiriDatasetsResponse {
+    fn times(self) -> Result<Vec<chrono::DateTime<chrono::Utc>>, TawhiriError> {
+        let mut times = self
+            .datasets
+            .iter()
+            .map(|dataset| {
+                parse_time(dataset).ok_or_else(|| TawhiriError::ParsingError {
+                    message: format!("unrecognized dataset {:}", dataset),
+                })
+            })
+            .collect::<Result<Vec<chrono::DateTime<chrono::Utc>>, TawhiriError>>()?;
+        times.sort();
+        Ok(times)
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -364,6 +593,22 @@ enum TawhiriRequest {
     },
 }
 
+impl TawhiriRequest {
+    fn dataset_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self {
+            Self::StandardProfile { dataset, .. } | Self::FloatProfile { dataset, .. } => {
+                parse_time(dataset)
+            }
+        }
+    }
+}
+
+fn parse_time(time: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&chrono::Utc))
+}
+
 #[derive(serde::Deserialize, Clone)]
 struct TawhiriPrediction {
     stage: String,
@@ -407,6 +652,214 @@ impl TawhiriLocation {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_coordinate_precision() {
+        let start = crate::location::Location {
+            time: chrono::Local::now(),
+            coord: geo::coord! { x: -77.547824, y: 39.359031 },
+            altitude: Some(2000.0),
+        };
+        let profile = crate::prediction::FlightProfile::new_standard(5.5, 28000.0, 9.0);
+
+        let query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
+        let parameters = query.parameters().unwrap();
+        assert!(parameters.contains(&("launch_longitude", "282.45".to_string())));
+        assert!(parameters.contains(&("launch_latitude", "39.36".to_string())));
+
+        let query = TawhiriQuery::new(&start, &profile, None, None, None, true, None);
+        let parameters = query.parameters().unwrap();
+        assert!(parameters.contains(&("launch_longitude", "282.452176".to_string())));
+        assert!(parameters.contains(&("launch_latitude", "39.359031".to_string())));
+
+        let mut query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
+        query.coordinate_precision = 4;
+        let parameters = query.parameters().unwrap();
+        assert!(parameters.contains(&("launch_longitude", "282.4522".to_string())));
+        assert!(parameters.contains(&("launch_latitude", "39.3590".to_string())));
+    }
+
+    #[test]
+    fn test_float_stop_time() {
+        let start = crate::location::Location {
+            time: chrono::Local::now(),
+            coord: geo::coord! { x: -77.547824, y: 39.359031 },
+            altitude: Some(1000.0),
+        };
+        let profile = crate::prediction::FlightProfile::new_float(
+            5.0,
+            Some(26000.0),
+            chrono::Duration::hours(1),
+            None,
+            28000.0,
+            9.0,
+        );
+
+        // 25 km at 5 m/s is 5000 seconds of ascent before the float
+        let query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
+        let parameters = query.parameters().unwrap();
+        assert!(parameters.contains(&(
+            "stop_datetime",
+            (start.time + chrono::Duration::seconds(5000) + chrono::Duration::hours(1))
+                .to_rfc3339()
+        )));
+
+        // an observed float start takes precedence
+        let float_start = start.time + chrono::Duration::seconds(4000);
+        let query = TawhiriQuery::new(&start, &profile, None, None, None, false, Some(float_start));
+        let parameters = query.parameters().unwrap();
+        assert!(parameters.contains(&(
+            "stop_datetime",
+            (float_start + chrono::Duration::hours(1)).to_rfc3339()
+        )));
+    }
+
+    #[test]
+    fn test_cache() {
+        let start = crate::location::Location {
+            time: chrono::Local::now(),
+            coord: geo::coord! { x: -77.547824, y: 39.359031 },
+            altitude: Some(2000.0),
+        };
+        let profile = crate::prediction::FlightProfile::new_standard(5.5, 28000.0, 9.0);
+        let query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
+
+        // a later packet that barely moved
+        let mut nearby = start.to_owned();
+        nearby.time += chrono::Duration::seconds(30);
+        nearby.coord.x += 0.0001;
+        nearby.altitude = Some(2003.0);
+        let nearby_query = TawhiriQuery::new(&nearby, &profile, None, None, None, false, None);
+        assert_eq!(query.cache_key(), nearby_query.cache_key());
+
+        // a packet that moved significantly
+        let mut moved = start.to_owned();
+        moved.coord.y += 0.05;
+        let moved_query = TawhiriQuery::new(&moved, &profile, None, None, None, false, None);
+        assert_ne!(query.cache_key(), moved_query.cache_key());
+
+        let mut cache = PredictionCache::default();
+        let predicted = vec![crate::location::BalloonLocation {
+            location: start.to_owned(),
+            data: crate::location::BalloonData::new(
+                None,
+                None,
+                None,
+                None,
+                crate::location::LocationSource::Prediction,
+            ),
+        }];
+        let mut retrievals = 0;
+        for key in [
+            query.cache_key(),
+            nearby_query.cache_key(),
+            moved_query.cache_key(),
+        ] {
+            let prediction = cache
+                .get_or_retrieve(key, || {
+                    retrievals += 1;
+                    Ok((
+                        predicted.to_owned(),
+                        crate::prediction::PredictionMetadata::default(),
+                    ))
+                })
+                .unwrap();
+            assert_eq!(prediction.0.len(), 1);
+        }
+
+        assert_eq!(retrievals, 2);
+        assert_eq!(cache.hits, 1);
+        assert_eq!(cache.misses, 2);
+
+        // errors are not cached
+        assert!(cache
+            .get_or_retrieve(String::from("failing"), || Err(
+                TawhiriError::NoDescentStage
+            ))
+            .is_err());
+        assert_eq!(cache.misses, 3);
+    }
+
+    #[test]
+    fn test_response_metadata() {
+        let response: TawhiriResponse = serde_json::from_str(
+            r#"{
+                "metadata": {
+                    "start_datetime": "2023-08-16T12:00:00.000000Z",
+                    "complete_datetime": "2023-08-16T12:00:00.500000Z"
+                },
+                "request": {
+                    "profile": "standard_profile",
+                    "ascent_rate": 5.5,
+                    "burst_altitude": 28000.0,
+                    "dataset": "2023-08-16T06:00:00Z",
+                    "descent_rate": 9.0,
+                    "launch_altitude": 0.0,
+                    "launch_datetime": "2023-08-16T12:00:00Z",
+                    "launch_latitude": 39.36,
+                    "launch_longitude": 282.45,
+                    "version": 1
+                },
+                "prediction": [],
+                "warnings": {
+                    "dataset_edge": "Prediction is near the end of the dataset",
+                    "altitude_too_high": {"count": 1, "description": "The altitude went too high."}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            response.warnings(),
+            vec![
+                "altitude_too_high - The altitude went too high.",
+                "dataset_edge - Prediction is near the end of the dataset",
+            ]
+        );
+        assert_eq!(
+            response.request.dataset_time(),
+            chrono::NaiveDate::from_ymd_opt(2023, 8, 16)
+                .unwrap()
+                .and_hms_opt(6, 0, 0)
+                .map(|time| time.and_utc())
+        );
+        assert_eq!(
+            parse_time(&response.metadata.complete_datetime),
+            chrono::NaiveDate::from_ymd_opt(2023, 8, 16)
+                .unwrap()
+                .and_hms_milli_opt(12, 0, 0, 500)
+                .map(|time| time.and_utc())
+        );
+    }
+
+    #[test]
+    fn test_datasets() {
+        let expected = vec![
+            chrono::NaiveDate::from_ymd_opt(2023, 8, 16)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc(),
+            chrono::NaiveDate::from_ymd_opt(2023, 8, 16)
+                .unwrap()
+                .and_hms_opt(6, 0, 0)
+                .unwrap()
+                .and_utc(),
+        ];
+
+        let response: TawhiriDatasetsResponse = serde_json::from_str(
+            r#"{"datasets": ["2023-08-16T06:00:00Z", "2023-08-16T00:00:00Z"]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.times().unwrap(), expected);
+
+        let response: TawhiriDatasetsResponse =
+            serde_json::from_str(r#"{"datasets": ["2023081606"]}"#).unwrap();
+        assert!(response.times().is_err());
+        assert!(
+            serde_json::from_str::<TawhiriDatasetsResponse>(r#"["2023-08-16T06:00:00Z"]"#).is_err()
+        );
+    }
+
     #[test]
     #[ignore]
     fn test_ground_prediction() {
@@ -419,8 +872,8 @@ mod tests {
 
         let query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
 
-        let response = query.get().unwrap();
-        let prediction = query.retrieve_prediction();
+        let response = query.get(&Default::default()).unwrap();
+        let prediction = query.retrieve_prediction(&Default::default());
 
         let mut stages = vec![];
         for stage in response.prediction {
@@ -431,7 +884,7 @@ mod tests {
             assert!(stages.contains(&stage));
         }
         assert!(prediction.is_ok());
-        assert!(!prediction.unwrap().is_empty());
+        assert!(!prediction.unwrap().0.is_empty());
     }
 
     #[test]
@@ -446,8 +899,8 @@ mod tests {
 
         let query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
 
-        let response = query.get().unwrap();
-        let prediction = query.retrieve_prediction();
+        let response = query.get(&Default::default()).unwrap();
+        let prediction = query.retrieve_prediction(&Default::default());
 
         let mut stages = vec![];
         for stage in response.prediction {
@@ -458,7 +911,7 @@ mod tests {
             assert!(stages.contains(&stage));
         }
         assert!(prediction.is_ok());
-        assert!(!prediction.unwrap().is_empty());
+        assert!(!prediction.unwrap().0.is_empty());
     }
 
     #[test]
@@ -474,8 +927,8 @@ mod tests {
 
         let query = TawhiriQuery::new(&start, &profile, None, None, None, true, None);
 
-        let response = query.get().unwrap();
-        let prediction = query.retrieve_prediction();
+        let response = query.get(&Default::default()).unwrap();
+        let prediction = query.retrieve_prediction(&Default::default());
 
         let mut stages = vec![];
         for stage in response.prediction {
@@ -484,7 +937,7 @@ mod tests {
 
         assert!(stages.contains(&"descent".to_string()));
         assert!(prediction.is_ok());
-        assert!(!prediction.unwrap().is_empty());
+        assert!(!prediction.unwrap().0.is_empty());
     }
 
     #[test]
@@ -506,8 +959,8 @@ mod tests {
 
         let query = TawhiriQuery::new(&start, &profile, None, None, None, false, None);
 
-        let response = query.get().unwrap();
-        let prediction = query.retrieve_prediction();
+        let response = query.get(&Default::default()).unwrap();
+        let prediction = query.retrieve_prediction(&Default::default());
 
         let mut stages = vec![];
         for stage in response.prediction {
@@ -522,6 +975,6 @@ mod tests {
             assert!(stages.contains(&stage));
         }
         assert!(prediction.is_ok());
-        assert!(!prediction.unwrap().is_empty());
+        assert!(!prediction.unwrap().0.is_empty());
     }
 }
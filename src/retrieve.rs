@@ -1,20 +1,98 @@
+pub type LogMessage = (chrono::DateTime<chrono::Local>, String, log::Level);
+
+// a location added to a track, with the name of the track
+pub type NewLocation = (String, crate::location::BalloonLocation);
+
 pub fn retrieve_locations(
-    connections: &mut Vec<crate::connection::Connection>,
+    connections: &mut [crate::connection::ScheduledConnection],
     tracks: &mut Vec<crate::location::track::BalloonTrack>,
-    start_time: Option<chrono::DateTime<chrono::Local>>,
-    end_time: Option<chrono::DateTime<chrono::Local>>,
-) -> Vec<(chrono::DateTime<chrono::Local>, String, log::Level)> {
+    configuration: &crate::configuration::RunConfiguration,
+    online: bool,
+) -> (
+    Vec<LogMessage>,
+    Vec<crate::connection::ConnectionOutcome>,
+    Vec<NewLocation>,
+) {
+    let start_time = configuration.time.start;
+    let end_time = configuration.time.end;
+    let duplicates = &configuration.duplicates;
+
     let mut new_packets: Vec<crate::location::BalloonLocation> = vec![];
-    let mut messages = Vec::<(chrono::DateTime<chrono::Local>, String, log::Level)>::new();
+    let mut messages = Vec::<LogMessage>::new();
+    let mut outcomes = Vec::<crate::connection::ConnectionOutcome>::new();
+    let mut new_locations = Vec::<NewLocation>::new();
 
-    for connection in connections {
-        match connection.retrieve_locations() {
-            Ok(packets) => new_packets.extend(packets),
+    let now = chrono::Local::now();
+    let http = &configuration.http;
+
+    // read from each connection in its own thread, so that one slow connection does not hold up the others
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = connections
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, scheduled)| scheduled.due(now))
+            .map(|(index, scheduled)| {
+                if !online && scheduled.connection.requires_internet() {
+                    messages.push((
+                        chrono::Local::now(),
+                        format!("offline; skipped {:?}", scheduled.connection),
+                        log::Level::Debug,
+                    ));
+                    outcomes.push(crate::connection::ConnectionOutcome {
+                        index,
+                        time: now,
+                        result: Err(String::from("offline")),
+                    });
+                    None
+                } else {
+                    Some((
+                        index,
+                        scope.spawn(move || {
+                            let result = scheduled.connection.retrieve_locations(http);
+                            if result.is_ok() {
+                                scheduled.last_access = Some(now);
+                            }
+                            result
+                        }),
+                    ))
+                }
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flatten()
+            .map(|(index, handle)| (index, handle.join().unwrap()))
+            .collect::<Vec<_>>()
+    });
+
+    for scheduled in connections.iter_mut() {
+        for (notice, level) in scheduled.connection.take_notices() {
+            messages.push((chrono::Local::now(), notice, level));
+        }
+    }
+
+    for (index, result) in results {
+        match result {
+            Ok(packets) => {
+                outcomes.push(crate::connection::ConnectionOutcome {
+                    index,
+                    time: now,
+                    result: Ok(packets.len()),
+                });
+                new_packets.extend(packets);
+            }
             Err(error) => {
+                outcomes.push(crate::connection::ConnectionOutcome {
+                    index,
+                    time: now,
+                    result: Err(error.to_string()),
+                });
                 messages.push((chrono::Local::now(), error.to_string(), log::Level::Error));
             }
         }
     }
+    new_packets.sort_by_key(|packet| packet.location.time);
 
     let num_new_packets = new_packets.len();
     messages.push((
@@ -29,9 +107,24 @@ pub fn retrieve_locations(
             packet_track_lengths.insert(track.name.to_owned(), track.locations.len());
         }
 
+        let aliases: Vec<(String, crate::connection::CallsignFilter)> = configuration
+            .aliases
+            .iter()
+            .map(|(name, callsigns)| {
+                (
+                    name.to_owned(),
+                    crate::connection::CallsignFilter::new(callsigns),
+                )
+            })
+            .collect();
+
         let mut num_duplicates: usize = 0;
+        let mut num_merged: usize = 0;
         let mut num_time_lagged_duplicates: usize = 0;
 
+        #[cfg(feature = "postgres")]
+        let mut archive_packets: Vec<crate::location::BalloonLocation> = vec![];
+
         let mut track: &mut crate::location::track::BalloonTrack;
         for mut packet in new_packets {
             if let Some(start_time) = start_time {
@@ -62,10 +155,7 @@ pub fn retrieve_locations(
                 }
             }
 
-            let name = match &packet.data.callsign {
-                Some(callsign) => callsign.to_owned(),
-                None => "other".to_owned(),
-            };
+            let name = track_name(&packet.data.callsign, &aliases);
 
             track = match tracks.iter_mut().find(|track| track.name == name) {
                 Some(track) => track,
@@ -76,17 +166,22 @@ pub fn retrieve_locations(
                         log::Level::Debug,
                     ));
                     packet_track_lengths.insert(name.to_owned(), 0);
-                    tracks.push(crate::location::track::BalloonTrack::new(name.to_owned()));
+                    let mut new_track = crate::location::track::BalloonTrack::new(name.to_owned());
+                    new_track.smoothing_window = configuration.ascent_rate.smoothing_window;
+                    new_track.ascent_rate_threshold = configuration.ascent_rate.threshold;
+                    new_track.ascent_rate_samples = configuration.ascent_rate.samples;
+                    new_track.landing = configuration.landing.to_owned();
+                    new_track.float = configuration.float.to_owned();
+                    new_track.float_uncertainty = configuration.float_uncertainty();
+                    new_track.terminal_velocity = configuration.terminal_velocity();
+                    new_track.kalman = configuration.kalman.to_owned();
+                    tracks.push(new_track);
                     tracks.last_mut().unwrap()
                 }
             };
 
-            for existing_packet in &track.locations {
-                if packet.eq(existing_packet) {
-                    packet.data.status = crate::location::PacketStatus::Duplicate;
-                } else if packet.location.time_lag_of(&existing_packet.location) {
-                    packet.data.status = crate::location::PacketStatus::TimeLaggedDuplicate;
-                }
+            if flag_duplicate(&mut track.locations, &mut packet, duplicates) {
+                num_merged += 1;
             }
 
             match packet.data.status {
@@ -99,7 +194,17 @@ pub fn retrieve_locations(
                     continue;
                 }
                 _ => {
-                    track.push(packet);
+                    #[cfg(feature = "postgres")]
+                    if !matches!(
+                        packet.data.source,
+                        crate::location::LocationSource::Database(_)
+                    ) {
+                        archive_packets.push(packet.to_owned());
+                    }
+                    let inserted = packet.to_owned();
+                    if track.push(packet) {
+                        new_locations.push((name, inserted));
+                    }
                 }
             }
         }
@@ -112,6 +217,17 @@ pub fn retrieve_locations(
             ));
         }
 
+        if num_merged > 0 {
+            messages.push((
+                chrono::Local::now(),
+                format!(
+                    "filled in missing data from {:} duplicate packet(s)",
+                    num_merged
+                ),
+                log::Level::Debug,
+            ));
+        }
+
         if num_time_lagged_duplicates > 0 {
             messages.push((
                 chrono::Local::now(),
@@ -123,6 +239,29 @@ pub fn retrieve_locations(
             ));
         }
 
+        // archive packets from other sources to any configured database
+        #[cfg(feature = "postgres")]
+        if !archive_packets.is_empty() {
+            for scheduled in connections.iter_mut() {
+                if let crate::connection::Connection::PacketDatabase(database) =
+                    &mut scheduled.connection
+                {
+                    match database.insert(&archive_packets) {
+                        Ok(num_inserted) => messages.push((
+                            chrono::Local::now(),
+                            format!("archived {:} packet(s) to {:?}", num_inserted, database),
+                            log::Level::Debug,
+                        )),
+                        Err(error) => messages.push((
+                            chrono::Local::now(),
+                            error.to_string(),
+                            log::Level::Error,
+                        )),
+                    }
+                }
+            }
+        }
+
         for track in tracks {
             if track.locations.len() - packet_track_lengths.get(&track.name.to_owned()).unwrap() > 0
             {
@@ -135,10 +274,52 @@ pub fn retrieve_locations(
         }
     }
 
-    messages
+    (messages, outcomes, new_locations)
 }
 
-fn location_update(track: &crate::location::track::BalloonTrack) -> String {
+// flag the packet if it duplicates one already in the track; a duplicate from the same time and position
+// (treating a missing altitude as matching) fills in any data that the existing packet lacks, and this returns whether it did
+fn flag_duplicate(
+    locations: &mut [crate::location::BalloonLocation],
+    packet: &mut crate::location::BalloonLocation,
+    duplicates: &crate::configuration::DuplicateConfiguration,
+) -> bool {
+    let mut merged = false;
+    for existing_packet in locations.iter_mut() {
+        let mut horizontal = packet.location.to_owned();
+        if horizontal.altitude.is_none() || existing_packet.location.altitude.is_none() {
+            horizontal.altitude = existing_packet.location.altitude;
+        }
+
+        if packet.location.duplicate_of(
+            &existing_packet.location,
+            duplicates.coordinate_precision,
+            duplicates.altitude_tolerance,
+        ) && packet.data.eq(&existing_packet.data)
+        {
+            packet.data.status = crate::location::PacketStatus::Duplicate;
+        } else if horizontal.duplicate_of(
+            &existing_packet.location,
+            duplicates.coordinate_precision,
+            duplicates.altitude_tolerance,
+        ) {
+            packet.data.status = crate::location::PacketStatus::Duplicate;
+            merged |= existing_packet.merge(packet);
+        } else if packet.location.time_lag_of(
+            &existing_packet.location,
+            duplicates.coordinate_precision,
+            duplicates.altitude_tolerance,
+        ) {
+            packet.data.status = crate::location::PacketStatus::TimeLaggedDuplicate;
+        }
+    }
+    merged
+}
+
+fn location_update(
+    track: &crate::location::track::BalloonTrack,
+    units: &crate::configuration::Units,
+) -> String {
     let last_location = match track.locations.last() {
         Some(location) => location,
         None => {
@@ -161,7 +342,7 @@ fn location_update(track: &crate::location::track::BalloonTrack) -> String {
         &last_location.location.coord.x, &last_location.location.coord.y,
     );
     if let Some(altitude) = last_location.location.altitude {
-        message += &format!(", {:.2} m", altitude,)
+        message += &format!(", {:}", units.length(altitude, 2))
     };
     message += &String::from(")");
 
@@ -172,19 +353,28 @@ fn location_update(track: &crate::location::track::BalloonTrack) -> String {
 
     if track.locations.len() > 1 {
         message += &format!(
-            " ({:.2} since the previous packet); traveled {:.2} m ({:.2} m/s) over the ground and {:.2} m ({:.2} m/s) vertically",
+            " ({:.2} since the previous packet); traveled {:} ({:}) over the ground",
             crate::utilities::duration_string(intervals.last().unwrap()),
-            overground_distances.last().unwrap(),
-            ground_speeds.last().unwrap(),
-            ascents.last().unwrap(),
-            ascent_rates.last().unwrap(),
+            units.length(*overground_distances.last().unwrap(), 2),
+            units.speed(*ground_speeds.last().unwrap(), 2),
         );
+        // packets without an altitude have no vertical movement
+        if let (Some(ascent), Some(ascent_rate)) = (ascents.last(), ascent_rates.last()) {
+            message += &format!(
+                " and {:} ({:}) vertically",
+                units.length(*ascent, 2),
+                units.vertical_speed(*ascent_rate, 2)
+            );
+        }
     }
 
     message
 }
 
-fn track_update(track: &crate::location::track::BalloonTrack) -> String {
+fn track_update(
+    track: &crate::location::track::BalloonTrack,
+    units: &crate::configuration::Units,
+) -> String {
     let last_location = track.locations.last().unwrap();
 
     let intervals = crate::location::track::intervals(&track.locations);
@@ -192,10 +382,13 @@ fn track_update(track: &crate::location::track::BalloonTrack) -> String {
     let ascent_rates = crate::location::track::ascent_rates(&track.locations);
 
     let mut message = format!(
-        "{: <8} - {:} packets - current altitude: {:.2} m",
+        "{: <8} - {:} packets - current altitude: {:}",
         track.name,
         track.locations.len(),
-        last_location.location.altitude.unwrap()
+        match last_location.location.altitude {
+            Some(altitude) => units.length(altitude, 2),
+            None => String::from("unknown"),
+        }
     );
 
     if track.locations.len() > 1 {
@@ -215,10 +408,19 @@ fn track_update(track: &crate::location::track::BalloonTrack) -> String {
             .fold(chrono::Duration::zero(), |sum, duration| sum + *duration);
 
         message += &format!(
-            " - avg. ascent rate: {:.2} m/s - avg. descent rate: {:.2} m/s - avg. ground speed: {:.2} m/s - avg. packet interval: {:.2} s",
-            positive_ascent_rates.iter().sum::<f64>() / positive_ascent_rates.len() as f64,
-            negative_ascent_rates.iter().sum::<f64>() / negative_ascent_rates.len() as f64,
-            ground_speeds.iter().sum::<f64>() / ground_speeds.len() as f64,
+            " - avg. ascent rate: {:} - avg. descent rate: {:} - avg. ground speed: {:} - avg. packet interval: {:.2} s",
+            units.vertical_speed(
+                positive_ascent_rates.iter().sum::<f64>() / positive_ascent_rates.len() as f64,
+                2
+            ),
+            units.vertical_speed(
+                negative_ascent_rates.iter().sum::<f64>() / negative_ascent_rates.len() as f64,
+                2
+            ),
+            units.speed(
+                ground_speeds.iter().sum::<f64>() / ground_speeds.len() as f64,
+                2
+            ),
             duration.num_seconds() as f64 / intervals.len() as f64,
         );
     }
@@ -233,8 +435,8 @@ fn track_update(track: &crate::location::track::BalloonTrack) -> String {
             }
         }
         message += &format!(
-            " - max altitude: {:.2} - estimated landing: {:} s ({:})",
-            altitudes.iter().max_by(|a, b| a.total_cmp(b)).unwrap(),
+            " - max altitude: {:} - estimated landing: {:} s ({:})",
+            units.length(*altitudes.iter().max_by(|a, b| a.total_cmp(b)).unwrap(), 2),
             time_to_ground_from_now.num_seconds(),
             landing_time.format(&crate::DATETIME_FORMAT),
         );
@@ -242,3 +444,172 @@ fn track_update(track: &crate::location::track::BalloonTrack) -> String {
 
     message
 }
+
+// name of the track to which packets from the given callsign belong
+fn track_name(
+    callsign: &Option<String>,
+    aliases: &[(String, crate::connection::CallsignFilter)],
+) -> String {
+    match callsign {
+        Some(callsign) => aliases
+            .iter()
+            .find(|(_, callsigns)| callsigns.matches(callsign))
+            .map(|(name, _)| name)
+            .unwrap_or(callsign)
+            .to_owned(),
+        None => "other".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_name() {
+        let aliases = vec![(
+            String::from("NS-111"),
+            crate::connection::CallsignFilter::new(&[
+                String::from("W3EAX-11"),
+                String::from("W3EAX-12"),
+            ]),
+        )];
+
+        assert_eq!(
+            track_name(&Some(String::from("W3EAX-11")), &aliases),
+            "NS-111"
+        );
+        assert_eq!(
+            track_name(&Some(String::from("W3EAX-12")), &aliases),
+            "NS-111"
+        );
+        assert_eq!(
+            track_name(&Some(String::from("W3EAX-8")), &aliases),
+            "W3EAX-8"
+        );
+        assert_eq!(track_name(&None, &aliases), "other");
+    }
+
+    #[test]
+    fn test_merge_duplicates() {
+        let time = chrono::Local::now();
+        let location = |altitude: Option<f64>, source: crate::location::LocationSource| {
+            crate::location::BalloonLocation {
+                location: crate::location::Location {
+                    time,
+                    coord: geo::coord! { x: -77.7513, y: 39.3347 },
+                    altitude,
+                },
+                data: crate::location::BalloonData::new(
+                    Some(String::from("W3EAX-8")),
+                    None,
+                    None,
+                    None,
+                    source,
+                ),
+            }
+        };
+
+        let mut locations = vec![location(None, crate::location::LocationSource::AprsFi)];
+        let mut duplicate = location(Some(2417.2), crate::location::LocationSource::SondeHub);
+        duplicate.data.raw = Some(String::from("W3EAX-8>APRS:..."));
+
+        assert!(flag_duplicate(
+            &mut locations,
+            &mut duplicate,
+            &crate::configuration::DuplicateConfiguration::default()
+        ));
+        assert_eq!(
+            duplicate.data.status,
+            crate::location::PacketStatus::Duplicate
+        );
+        assert_eq!(locations[0].location.altitude, Some(2417.2));
+        assert_eq!(locations[0].data.raw, duplicate.data.raw);
+        assert_eq!(
+            locations[0].data.source,
+            crate::location::LocationSource::AprsFi
+        );
+
+        // an exact duplicate adds nothing
+        let mut exact = locations[0].to_owned();
+        assert!(!flag_duplicate(
+            &mut locations,
+            &mut exact,
+            &crate::configuration::DuplicateConfiguration::default()
+        ));
+        assert_eq!(exact.data.status, crate::location::PacketStatus::Duplicate);
+    }
+
+    #[test]
+    #[cfg(feature = "aprsis")]
+    fn test_concurrent_retrieval() {
+        use std::io::{Read, Write};
+
+        // APRS-IS server that takes a while to send its only packet
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut login = vec![0; 256];
+            let _ = socket.read(&mut login).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            socket
+                .write_all(b"W3EAX-9>APRS,WIDE1-1,WIDE2-1,qAR,K3DO-11:!/:Gh=:j)#O   /A=026909|!Q|  /W3EAX,262,0,18'C,http://www.umd.edu\r\n")
+                .unwrap();
+        });
+
+        let connections = vec![
+            crate::connection::Connection::AprsIs(crate::connection::aprs_is::AprsIsStream::new(
+                Some("127.0.0.1".to_string()),
+                Some(port),
+                "KC3SKW".to_string(),
+                None,
+                None,
+                None,
+            )),
+            crate::connection::Connection::AprsTextFile(
+                crate::connection::text::file::AprsTextFile::new(
+                    format!(
+                        "{:}/{:}",
+                        env!("CARGO_MANIFEST_DIR"),
+                        "data/aprs/W3EAX-8_raw_NS-111.txt"
+                    ),
+                    None,
+                )
+                .unwrap(),
+            ),
+        ];
+        let mut connections: Vec<crate::connection::ScheduledConnection> = connections
+            .into_iter()
+            .map(|connection| {
+                crate::connection::ScheduledConnection::new(
+                    connection,
+                    chrono::Duration::seconds(10),
+                )
+            })
+            .collect();
+
+        let mut tracks = vec![];
+        let (_, outcomes, new_locations) = retrieve_locations(
+            &mut connections,
+            &mut tracks,
+            &crate::configuration::RunConfiguration::default(),
+            true,
+        );
+        server.join().unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|outcome| outcome.result.is_ok()));
+        assert_eq!(
+            new_locations.len(),
+            tracks
+                .iter()
+                .map(|track| track.locations.len())
+                .sum::<usize>()
+        );
+
+        let mut names: Vec<&String> = tracks.iter().map(|track| &track.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["W3EAX-8", "W3EAX-9"]);
+    }
+}
@@ -1,13 +1,190 @@
+use std::io::Write;
+
+// alerts raised for a track, in the order in which a flight raises them
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum FlightAlert {
+    Descending,
+    NearingLanding,
+}
+
+impl std::fmt::Display for FlightAlert {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Descending => write!(fmt, "DESCENDING"),
+            Self::NearingLanding => write!(fmt, "NEARING LANDING"),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    // narrowest chart window, as a fraction of the full extent
+    static ref MINIMUM_CHART_WIDTH: f64 = 1.0 / 64.0;
+}
+
+// portion of a chart's x-axis to show, as fractions of its full extent, so that it applies to every chart
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartView {
+    pub width: f64,
+    pub center: f64,
+}
+
+impl Default for ChartView {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            center: 0.5,
+        }
+    }
+}
+
+impl ChartView {
+    // the visible part of the given range
+    pub fn window(&self, range: [f64; 2]) -> [f64; 2] {
+        let extent = range[1] - range[0];
+        let start = range[0] + extent * (self.center - self.width / 2.0);
+        [start, start + extent * self.width]
+    }
+
+    pub fn zoom(&mut self, factor: f64) {
+        self.width = (self.width * factor).clamp(*MINIMUM_CHART_WIDTH, 1.0);
+        self.clamp();
+    }
+
+    // move by the given fraction of the visible width
+    pub fn pan(&mut self, fraction: f64) {
+        self.center += self.width * fraction;
+        self.clamp();
+    }
+
+    // keep the window within the full extent
+    fn clamp(&mut self) {
+        self.center = self.center.clamp(self.width / 2.0, 1.0 - self.width / 2.0);
+    }
+}
+
+// order of the track tabs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TrackOrder {
+    /// in the order in which each track was first received
+    #[default]
+    Received,
+    /// most recently updated first
+    Recent,
+    /// highest latest altitude first
+    Altitude,
+    /// alphabetical
+    Name,
+}
+
+impl TrackOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Received => Self::Recent,
+            Self::Recent => Self::Altitude,
+            Self::Altitude => Self::Name,
+            Self::Name => Self::Received,
+        }
+    }
+}
+
+impl std::fmt::Display for TrackOrder {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Received => write!(fmt, "first received"),
+            Self::Recent => write!(fmt, "most recent"),
+            Self::Altitude => write!(fmt, "highest altitude"),
+            Self::Name => write!(fmt, "name"),
+        }
+    }
+}
+
+// configured primary tracks first, in the order listed, then the rest in the given order
+fn compare_tracks(
+    a: &crate::location::track::BalloonTrack,
+    b: &crate::location::track::BalloonTrack,
+    order: TrackOrder,
+    primary: &[String],
+) -> std::cmp::Ordering {
+    let rank = |track: &crate::location::track::BalloonTrack| {
+        primary
+            .iter()
+            .position(|name| name == &track.name)
+            .unwrap_or(primary.len())
+    };
+    let latest_altitude = |track: &crate::location::track::BalloonTrack| {
+        crate::location::track::altitudes(&track.locations)
+            .last()
+            .copied()
+    };
+
+    rank(a).cmp(&rank(b)).then_with(|| match order {
+        TrackOrder::Received => std::cmp::Ordering::Equal,
+        TrackOrder::Recent => {
+            let time = |track: &crate::location::track::BalloonTrack| {
+                track
+                    .locations
+                    .last()
+                    .map(|location| location.location.time)
+            };
+            time(b).cmp(&time(a))
+        }
+        // tracks without altitude last
+        TrackOrder::Altitude => match (latest_altitude(a), latest_altitude(b)) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        },
+        TrackOrder::Name => a.name.cmp(&b.name),
+    })
+}
+
 pub struct PacketravenApp {
     pub configuration: crate::configuration::RunConfiguration,
-    pub connections: Vec<crate::connection::Connection>,
+    pub connections: Vec<crate::connection::ScheduledConnection>,
     pub tracks: Vec<crate::location::track::BalloonTrack>,
     pub tab_index: usize,
     pub chart_index: usize,
+    /// zoomed chart window, or `None` to show the full extent
+    pub chart_view: Option<ChartView>,
     pub log_messages: Vec<(chrono::DateTime<chrono::Local>, String, log::Level)>,
     pub log_messages_scroll_offset: u16,
     pub log_level: log::Level,
     pub should_quit: bool,
+    pub show_map: bool,
+    /// show the raw and decoded packet of the latest location in place of the chart
+    pub show_raw: bool,
+    pub paused: bool,
+    pub show_help: bool,
+    /// only show tabs for tracks whose names contain this text
+    pub track_filter: String,
+    /// keys are typed into the track filter instead of triggering actions
+    pub editing_filter: bool,
+    pub track_order: TrackOrder,
+    /// brief confirmation shown in the tab bar, with when it was set
+    pub status: Option<(chrono::DateTime<chrono::Local>, String)>,
+    // kept open, since on some platforms the copied text is only available while the clipboard is
+    clipboard: Option<arboard::Clipboard>,
+    connectivity: Option<crate::connection::ConnectivityProbe>,
+    prediction_cache: crate::prediction::tawhiri::PredictionCache,
+    battery_warnings: std::collections::HashMap<String, chrono::DateTime<chrono::Local>>,
+    /// latest alert raised for each track
+    pub alerts: std::collections::HashMap<String, FlightAlert>,
+    log_file: Option<std::io::BufWriter<std::fs::File>>,
+    num_logged_messages: usize,
+    configuration_watcher: Option<ConfigurationWatcher>,
+    #[cfg(feature = "mqtt")]
+    mqtt: Option<crate::connection::mqtt::MqttPublisher>,
+    #[cfg(feature = "api")]
+    api: Option<crate::api::ApiServer>,
+    /// track selected in the previous run, to select once its first packet is received
+    restored_track: Option<String>,
+}
+
+// notifies of changes to the configuration file on disk
+struct ConfigurationWatcher {
+    path: std::path::PathBuf,
+    // events stop when the watcher is dropped
+    _watcher: notify::RecommendedWatcher,
+    events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
 }
 
 impl PacketravenApp {
@@ -19,16 +196,10 @@ impl PacketravenApp {
 
         let mut configuration = configuration;
         let mut log_messages = vec![];
-        let mut connections = vec![];
         let mut tracks = vec![];
 
+        let mut log_file = None;
         if let Some(path) = &mut configuration.log_file {
-            // TODO
-            log_messages.push((
-                chrono::Local::now(),
-                "logging to file is not implemented".to_owned(),
-                log::Level::Warn,
-            ));
             if path.is_dir() {
                 path.push(format!(
                     "{:}_log_{:}.txt",
@@ -36,6 +207,31 @@ impl PacketravenApp {
                     program_start_time.format(&crate::DATETIME_FORMAT),
                 ));
             }
+
+            // append to an existing log file instead of overwriting it
+            match std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+            {
+                Ok(file) => {
+                    log_messages.push((
+                        chrono::Local::now(),
+                        format!("logging to {:}", path.to_string_lossy()),
+                        log::Level::Info,
+                    ));
+                    log_file = Some(std::io::BufWriter::new(file));
+                }
+                Err(error) => log_messages.push((
+                    chrono::Local::now(),
+                    format!(
+                        "could not open log file {:} - {:}",
+                        path.to_string_lossy(),
+                        error
+                    ),
+                    log::Level::Error,
+                )),
+            }
         }
 
         if let Some(path) = &mut configuration.output_file {
@@ -54,55 +250,45 @@ impl PacketravenApp {
                     log::Level::Debug,
                 ));
                 crate::retrieve::retrieve_locations(
-                    &mut vec![crate::connection::Connection::GeoJsonFile(
-                        crate::connection::text::file::GeoJsonFile {
-                            path: format!("{:}", path.to_string_lossy()),
-                        },
+                    &mut [crate::connection::ScheduledConnection::new(
+                        crate::connection::text::file::locations_file_connection(path),
+                        configuration.time.interval,
                     )],
                     &mut tracks,
-                    configuration.time.start,
-                    configuration.time.end,
+                    &configuration,
+                    true,
                 );
             }
         }
 
         if let Some(prediction) = &mut configuration.prediction {
-            match prediction {
-                crate::configuration::prediction::PredictionConfiguration::Single(prediction) => {
-                    if let Some(path) = &mut prediction.output_file {
-                        if path.is_dir() {
-                            path.push(format!(
-                                "{:}_predict_{:}.geojson",
-                                configuration.name,
-                                program_start_time.format(&crate::DATETIME_FORMAT)
-                            ));
-                        }
-                    }
-                }
-                crate::configuration::prediction::PredictionConfiguration::Cloud { .. } => {
-                    log_messages.push((
-                        chrono::Local::now(),
-                        "cloud prediction not implemented".to_string(),
-                        log::Level::Error,
-                    ))
+            if let Some(path) = &mut prediction.default_prediction_mut().output_file {
+                if path.is_dir() {
+                    path.push(format!(
+                        "{:}_predict_{:}.geojson",
+                        configuration.name,
+                        program_start_time.format(&crate::DATETIME_FORMAT)
+                    ));
                 }
             }
         }
 
+        let time_string = |time: &chrono::DateTime<chrono::Local>| {
+            crate::utilities::display_time(time, configuration.timezone, &crate::DATETIME_FORMAT)
+        };
         let mut filter_message = "retrieving packets".to_string();
         if let Some(start) = configuration.time.start {
             if let Some(end) = configuration.time.end {
                 filter_message += &format!(
                     " sent between {:} and {:}",
-                    start.format(&crate::DATETIME_FORMAT),
-                    end.format(&crate::DATETIME_FORMAT)
+                    time_string(&start),
+                    time_string(&end)
                 );
             } else {
-                filter_message +=
-                    &format!(" sent after {:}", start.format(&crate::DATETIME_FORMAT),);
+                filter_message += &format!(" sent after {:}", time_string(&start));
             }
         } else if let Some(end) = configuration.time.end {
-            filter_message += &format!(" sent before {:}", end.format(&crate::DATETIME_FORMAT));
+            filter_message += &format!(" sent before {:}", time_string(&end));
         }
         if let Some(callsigns) = &configuration.callsigns {
             if !callsigns.is_empty() {
@@ -151,97 +337,65 @@ impl PacketravenApp {
             }
         }
 
-        if let Some(text_configuration) = &configuration.connections.text.to_owned() {
-            for text_stream in text_configuration {
-                let connection = match text_stream {
-                    crate::connection::text::TextStream::GeoJsonFile(connection) => {
-                        let connection = connection.to_owned();
-                        log_messages.push((
-                            chrono::Local::now(),
-                            format!("reading GeoJSON file: {:}", connection.path),
-                            log::Level::Info,
-                        ));
-
-                        crate::connection::Connection::GeoJsonFile(connection)
-                    }
-                    crate::connection::text::TextStream::AprsTextFile(connection) => {
-                        let mut connection = connection.to_owned();
-                        if connection.callsigns.is_none() {
-                            if let Some(callsigns) = &configuration.callsigns {
-                                connection.callsigns = Some(callsigns.to_owned());
-                            }
-                        }
-                        log_messages.push((
-                            chrono::Local::now(),
-                            format!("reading text file of APRS frames: {:}", connection.path),
-                            log::Level::Info,
-                        ));
-                        crate::connection::Connection::AprsTextFile(connection)
-                    }
-                    #[cfg(feature = "serial")]
-                    crate::connection::text::TextStream::AprsSerial(connection) => {
-                        let mut connection = connection.to_owned();
-                        if connection.callsigns.is_none() {
-                            if let Some(callsigns) = &configuration.callsigns {
-                                connection.callsigns = Some(callsigns.to_owned());
-                            }
-                        }
-                        crate::connection::Connection::AprsSerial(connection)
-                    }
-                };
-                connections.push(connection);
-            }
-        }
+        #[allow(unused_mut)]
+        let mut connections = configured_connections(&configuration, &mut log_messages);
+        #[cfg(feature = "serial")]
+        detect_baud_rates(&mut connections, &mut log_messages);
 
-        #[cfg(feature = "aprsfi")]
-        if let Some(aprs_fi_query) = &configuration.connections.aprs_fi {
-            if let Some(callsigns) = &configuration.callsigns {
-                let mut connection = aprs_fi_query.to_owned();
-                if connection.callsigns.is_none() {
-                    connection.callsigns = Some(callsigns.to_owned());
+        let connectivity = configuration
+            .connectivity
+            .as_ref()
+            .and_then(|connectivity| match connectivity.timeout.to_std() {
+                Ok(timeout) => Some(crate::connection::ConnectivityProbe::new(
+                    connectivity.address.to_owned(),
+                    timeout,
+                )),
+                Err(error) => {
+                    log_messages.push((
+                        chrono::Local::now(),
+                        format!("not checking connectivity - invalid timeout: {:}", error),
+                        log::Level::Error,
+                    ));
+                    None
                 }
-                connections.push(crate::connection::Connection::AprsFi(connection));
-            } else {
-                log_messages.push((
-                    chrono::Local::now(),
-                    "APRS.fi requires a list of callsigns".to_string(),
-                    log::Level::Error,
-                ));
-            }
+            });
+
+        #[cfg(feature = "mqtt")]
+        let mqtt = configuration.connections.mqtt.to_owned();
+        #[cfg(feature = "mqtt")]
+        if let Some(mqtt) = &mqtt {
+            log_messages.push((
+                chrono::Local::now(),
+                format!("publishing new packets to {:?}", mqtt),
+                log::Level::Info,
+            ));
         }
 
-        #[cfg(feature = "sondehub")]
-        if let Some(connection) = &configuration.connections.sondehub {
-            if let Some(callsigns) = &configuration.callsigns {
-                let mut connection = connection.to_owned();
-                if connection.callsigns.is_none() {
-                    connection.callsigns = Some(callsigns.to_owned());
-                }
-                if connection.start.is_none() {
-                    connection.start = configuration.time.start;
+        #[cfg(feature = "api")]
+        let api = match &configuration.api {
+            Some(api) => match crate::api::ApiServer::start(api) {
+                Ok(server) => {
+                    log_messages.push((
+                        chrono::Local::now(),
+                        format!("serving tracks at http://{:}/tracks", server.address),
+                        log::Level::Info,
+                    ));
+                    Some(server)
                 }
-                if connection.end.is_none() {
-                    connection.end = configuration.time.end;
+                Err(error) => {
+                    log_messages.push((chrono::Local::now(), error.to_string(), log::Level::Error));
+                    None
                 }
+            },
+            None => None,
+        };
 
-                connections.push(crate::connection::Connection::SondeHub(connection));
-            } else {
-                log_messages.push((
-                    chrono::Local::now(),
-                    "SondeHub requires a list of callsigns".to_string(),
-                    log::Level::Error,
-                ));
-            }
-        }
-
-        #[cfg(feature = "postgres")]
-        if let Some(database_credentials) = &configuration.connections.database {
-            connections.push(crate::connection::Connection::PacketDatabase(
-                crate::connection::postgres::PacketDatabase::from_credentials(
-                    &database_credentials,
-                ),
-            ));
-        }
+        let connections: Vec<crate::connection::ScheduledConnection> = connections
+            .into_iter()
+            .map(|connection| {
+                crate::connection::ScheduledConnection::new(connection, configuration.time.interval)
+            })
+            .collect();
 
         if !connections.is_empty() {
             log_messages.push((
@@ -254,10 +408,14 @@ impl PacketravenApp {
                 log::Level::Info,
             ));
 
-            for connection in &connections {
+            for scheduled in &connections {
                 log_messages.push((
                     chrono::Local::now(),
-                    format!("{:?}", connection),
+                    format!(
+                        "{:?} every {:}",
+                        scheduled.connection,
+                        crate::utilities::duration_string(&scheduled.interval)
+                    ),
                     log::Level::Debug,
                 ));
             }
@@ -269,222 +427,1551 @@ impl PacketravenApp {
             ));
         }
 
-        PacketravenApp {
+        let mut app = PacketravenApp {
             configuration,
             connections,
             tracks,
             tab_index: 0,
             chart_index: 0,
+            chart_view: None,
             log_messages,
             log_messages_scroll_offset: 0,
             log_level,
             should_quit: false,
+            show_map: false,
+            show_raw: false,
+            paused: false,
+            show_help: false,
+            track_filter: String::new(),
+            editing_filter: false,
+            track_order: TrackOrder::default(),
+            status: None,
+            clipboard: None,
+            connectivity,
+            prediction_cache: crate::prediction::tawhiri::PredictionCache::default(),
+            battery_warnings: std::collections::HashMap::new(),
+            alerts: std::collections::HashMap::new(),
+            log_file,
+            num_logged_messages: 0,
+            configuration_watcher: None,
+            #[cfg(feature = "mqtt")]
+            mqtt,
+            #[cfg(feature = "api")]
+            api,
+            restored_track: None,
+        };
+        app.restore_state();
+        #[cfg(feature = "api")]
+        if let Some(api) = &app.api {
+            api.update(&app.tracks);
         }
+        app.write_log_file();
+
+        app
     }
 
-    pub fn add_log_message(&mut self, message: String, level: log::Level) {
-        self.log_messages
-            .push((chrono::Local::now(), message, level));
+    // mirror log messages that have not yet been written to the log file
+    fn write_log_file(&mut self) {
+        if let Some(log_file) = &mut self.log_file {
+            let mut result = Ok(());
+            for (time, message, level) in &self.log_messages[self.num_logged_messages..] {
+                result = writeln!(
+                    log_file,
+                    "{:}",
+                    log_line(time, message, level, self.configuration.timezone)
+                );
+                if result.is_err() {
+                    break;
+                }
+            }
+            if let Err(error) = result.and_then(|_| log_file.flush()) {
+                self.log_file = None;
+                self.add_log_message(
+                    format!("stopped writing to log file - {:}", error),
+                    log::Level::Error,
+                );
+            }
+            self.num_logged_messages = self.log_messages.len();
+        }
     }
 
-    pub fn next_tab(&mut self) {
-        if self.tab_index < self.tracks.len() {
-            self.tab_index += 1;
-        } else {
-            self.tab_index = 0;
+    // return to the tab, chart, and pause state saved by the previous run
+    fn restore_state(&mut self) {
+        let path = match &self.configuration.state_file {
+            Some(path) if path.exists() => path.to_owned(),
+            _ => return,
+        };
+
+        match super::state::SessionState::read(&path) {
+            Ok(state) => {
+                if let Some(chart) = state.chart {
+                    if let Some(index) = super::draw::CHARTS.iter().position(|name| name == &chart)
+                    {
+                        self.chart_index = index;
+                    }
+                }
+                self.paused = state.paused;
+                self.log_messages_scroll_offset = state.log_scroll;
+                self.restored_track = state.track;
+                self.select_restored_track();
+
+                self.add_log_message(
+                    format!("restored interface state from {:}", path.to_string_lossy()),
+                    log::Level::Debug,
+                );
+                if self.paused {
+                    self.add_log_message(
+                        "paused automatic retrieval".to_string(),
+                        log::Level::Info,
+                    );
+                }
+            }
+            Err(error) => self.add_log_message(error.to_string(), log::Level::Warn),
         }
     }
 
-    pub fn previous_tab(&mut self) {
-        if self.tab_index > 0 {
-            self.tab_index -= 1;
-        } else {
-            self.tab_index = self.tracks.len();
+    // select the track from the previous run, once it exists
+    fn select_restored_track(&mut self) {
+        if let Some(name) = &self.restored_track {
+            if let Some(index) = self.tracks.iter().position(|track| &track.name == name) {
+                self.tab_index = index + 1;
+                self.restored_track = None;
+            }
         }
     }
 
-    pub fn up(&mut self) {
-        if self.tab_index == 0 {
-            if self.log_messages_scroll_offset > 0 {
-                self.log_messages_scroll_offset -= 1;
+    // save the tab, chart, and pause state for the next run
+    pub fn save_state(&mut self) {
+        if let Some(path) = &self.configuration.state_file {
+            let state = super::state::SessionState {
+                track: self
+                    .tab_index
+                    .checked_sub(1)
+                    .and_then(|index| self.tracks.get(index))
+                    .map(|track| track.name.to_owned())
+                    .or_else(|| self.restored_track.to_owned()),
+                chart: super::draw::CHARTS.get(self.chart_index).cloned(),
+                paused: self.paused,
+                log_scroll: self.log_messages_scroll_offset,
+            };
+            if let Err(error) = state.write(path) {
+                self.add_log_message(error.to_string(), log::Level::Warn);
+                self.write_log_file();
             }
-        } else if self.chart_index < super::draw::CHARTS.len() - 1 {
-            self.chart_index += 1;
-        } else {
-            self.chart_index = 0;
         }
     }
 
-    pub fn down(&mut self) {
-        if self.tab_index == 0 {
-            self.log_messages_scroll_offset += 1;
-        } else if self.chart_index > 0 {
-            self.chart_index -= 1;
-        } else {
-            self.chart_index = super::draw::CHARTS.len() - 1;
+    // reload the configuration whenever the given file changes on disk
+    pub fn watch_configuration(&mut self, path: &std::path::Path) {
+        use notify::Watcher;
+
+        // watch the directory, since editors often replace the file instead of writing to it
+        let directory = match path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => std::path::Path::new("."),
+        };
+
+        let (sender, events) = std::sync::mpsc::channel();
+        match notify::recommended_watcher(sender).and_then(|mut watcher| {
+            watcher
+                .watch(directory, notify::RecursiveMode::NonRecursive)
+                .map(|_| watcher)
+        }) {
+            Ok(watcher) => {
+                self.configuration_watcher = Some(ConfigurationWatcher {
+                    path: path.to_path_buf(),
+                    _watcher: watcher,
+                    events,
+                })
+            }
+            Err(error) => self.add_log_message(
+                format!(
+                    "cannot watch configuration file {:} - {:}",
+                    path.to_string_lossy(),
+                    error
+                ),
+                log::Level::Warn,
+            ),
         }
     }
 
-    pub fn on_key(&mut self, key: crossterm::event::KeyCode) {
-        match key {
-            crossterm::event::KeyCode::Esc => {
-                self.should_quit = true;
+    // re-read the configuration file if it changed since the last check
+    pub fn reload_configuration_if_changed(&mut self) {
+        let path = match &self.configuration_watcher {
+            Some(watcher) => {
+                let mut changed = false;
+                // drain every pending event, so that a burst of writes causes a single reload
+                for event in watcher.events.try_iter().flatten() {
+                    if (event.kind.is_modify() || event.kind.is_create())
+                        && event
+                            .paths
+                            .iter()
+                            .any(|path| path.file_name() == watcher.path.file_name())
+                    {
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    return;
+                }
+                watcher.path.to_owned()
             }
-            crossterm::event::KeyCode::Char(character) => match character {
-                'q' => {
-                    self.should_quit = true;
+            None => return,
+        };
+
+        match crate::configuration::read_configuration(&path) {
+            Ok(configuration) => {
+                let errors = configuration.validate();
+                if errors.is_empty() {
+                    self.add_log_message(
+                        format!("reloading configuration from {:}", path.to_string_lossy()),
+                        log::Level::Info,
+                    );
+                    self.apply_configuration(configuration);
+                } else {
+                    self.add_log_message(
+                        format!("ignoring changed configuration - {:}", errors.join("; ")),
+                        log::Level::Error,
+                    );
                 }
-                'r' | ' ' => self.on_tick(),
-                _ => {}
-            },
-            crossterm::event::KeyCode::BackTab => self.previous_tab(),
-            crossterm::event::KeyCode::Tab => self.next_tab(),
-            crossterm::event::KeyCode::Left => self.previous_tab(),
-            crossterm::event::KeyCode::Right => self.next_tab(),
-            crossterm::event::KeyCode::Up => self.up(),
-            crossterm::event::KeyCode::Down => self.down(),
-            _ => {}
+            }
+            Err(error) => self.add_log_message(
+                format!("ignoring changed configuration - {:}", error),
+                log::Level::Error,
+            ),
         }
     }
 
-    pub fn on_tick(&mut self) {
-        let tracks = &mut self.tracks;
+    // apply a changed configuration while running, keeping existing tracks;
+    // settings that cannot change while running keep their current values
+    pub fn apply_configuration(&mut self, configuration: crate::configuration::RunConfiguration) {
+        let mut configuration = configuration;
+        let mut messages = vec![];
+        let mut cannot_change = |setting: &str| {
+            messages.push((
+                chrono::Local::now(),
+                format!(
+                    "cannot change `{:}` while running; restart to apply",
+                    setting
+                ),
+                log::Level::Warn,
+            ))
+        };
 
-        let mut messages = crate::retrieve::retrieve_locations(
-            &mut self.connections,
-            tracks,
-            self.configuration.time.start,
-            self.configuration.time.end,
-        );
+        if configuration.name != self.configuration.name {
+            cannot_change("name");
+            configuration.name = self.configuration.name.to_owned();
+        }
+        if !same_path(&configuration.log_file, &self.configuration.log_file) {
+            cannot_change("log_file");
+        }
+        configuration.log_file = self.configuration.log_file.to_owned();
+        if !same_path(&configuration.output_file, &self.configuration.output_file) {
+            cannot_change("output_file");
+        }
+        configuration.output_file = self.configuration.output_file.to_owned();
+        if let Some(prediction) = &mut configuration.prediction {
+            let current = self
+                .configuration
+                .prediction
+                .as_ref()
+                .and_then(|prediction| prediction.predictions().0.output_file.to_owned());
+            let output_file = &mut prediction.default_prediction_mut().output_file;
+            if !same_path(output_file, &current) {
+                cannot_change("prediction.output_file");
+            }
+            *output_file = current;
+        }
+        if configuration.connectivity != self.configuration.connectivity {
+            cannot_change("connectivity");
+            configuration.connectivity = self.configuration.connectivity.to_owned();
+        }
+        #[cfg(feature = "api")]
+        if configuration.api != self.configuration.api {
+            cannot_change("api");
+            configuration.api = self.configuration.api.to_owned();
+        }
 
-        if let Some(prediction_configuration) = &self.configuration.prediction {
-            match prediction_configuration {
-                crate::configuration::prediction::PredictionConfiguration::Single(
-                    prediction_configuration,
-                ) => {
-                    let existing_prediction =
-                        if let Some(path) = &prediction_configuration.output_file {
-                            // read from an existing prediction output file
-                            if path.exists() {
-                                let mut existing_prediction_file =
-                                    crate::connection::Connection::GeoJsonFile(
-                                        crate::connection::text::file::GeoJsonFile {
-                                            path: format!("{:}", path.to_string_lossy()),
-                                        },
-                                    );
-                                messages.push((
-                                    chrono::Local::now(),
-                                    format!(
-                                        "reading existing prediction output file: {:}",
-                                        path.to_string_lossy()
-                                    ),
-                                    log::Level::Debug,
-                                ));
-                                match existing_prediction_file.retrieve_locations() {
-                                    Ok(locations) => Some(locations),
-                                    Err(_) => None,
-                                }
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        };
+        // only report problems with the connections, rather than repeating the startup messages
+        let mut connection_messages = vec![];
+        let configured = configured_connections(&configuration, &mut connection_messages);
+        messages.extend(
+            connection_messages
+                .into_iter()
+                .filter(|(_, _, level)| *level <= log::Level::Warn),
+        );
 
-                    let profile = prediction_configuration.to_tawhiri_query().query.profile;
-                    for track in tracks {
-                        let prediction = match track.prediction(&profile) {
-                            Ok(retrieved_prediction) => Some(retrieved_prediction),
-                            Err(error) => {
-                                messages.push((
-                                    chrono::Local::now(),
-                                    error.to_string(),
-                                    log::Level::Error,
-                                ));
-                                existing_prediction.to_owned()
-                            }
-                        };
+        let mut connections = vec![];
+        for connection in configured {
+            #[allow(unused_mut)]
+            let mut configured = crate::connection::ScheduledConnection::new(
+                connection,
+                configuration.time.interval,
+            );
+            let name = configured.connection.name();
+            match self
+                .connections
+                .iter()
+                .position(|scheduled| scheduled.connection.name() == name)
+            {
+                Some(index) => {
+                    let mut scheduled = self.connections.remove(index);
 
-                        if let Some(prediction) = &prediction {
-                            if prediction.is_empty() {
-                                continue;
-                            }
+                    // keep a detected baud rate
+                    #[cfg(feature = "serial")]
+                    if let (
+                        crate::connection::Connection::AprsSerial(existing),
+                        crate::connection::Connection::AprsSerial(connection),
+                    ) = (&scheduled.connection, &mut configured.connection)
+                    {
+                        if connection.baud_rate == existing.baud_rate {
+                            connection.detected_baud_rate = existing.detected_baud_rate;
                         }
-
-                        track.prediction = prediction;
                     }
 
-                    if let Some(path) = &prediction_configuration.output_file {
-                        let mut locations = vec![];
-                        for track in &self.tracks {
-                            if let Some(prediction) = &track.prediction {
-                                let track_locations: Vec<&crate::location::BalloonLocation> =
-                                    prediction.iter().collect();
-                                locations.extend(track_locations);
-                            }
-                        }
-
-                        let feature_collection =
-                            crate::connection::text::file::locations_geojson_featurecollection(
-                                locations,
-                            );
-
-                        match std::fs::write(path, feature_collection.to_string()) {
-                            Ok(_) => messages.push((
-                                chrono::Local::now(),
-                                format!("wrote predictions to {:}", path.to_string_lossy()),
-                                log::Level::Debug,
-                            )),
-                            Err(error) => messages.push((
-                                chrono::Local::now(),
-                                error.to_string(),
-                                log::Level::Error,
-                            )),
-                        };
+                    if scheduled.connection != configured.connection {
+                        messages.push((
+                            chrono::Local::now(),
+                            format!("updated connection {:}", name),
+                            log::Level::Info,
+                        ));
+                        scheduled.connection = configured.connection;
+                        scheduled.last_access = None;
+                    }
+                    if scheduled.interval != configured.interval {
+                        messages.push((
+                            chrono::Local::now(),
+                            format!(
+                                "reading {:} every {:}",
+                                name,
+                                crate::utilities::duration_string(&configured.interval)
+                            ),
+                            log::Level::Info,
+                        ));
+                        scheduled.interval = configured.interval;
                     }
+                    connections.push(scheduled);
                 }
-                crate::configuration::prediction::PredictionConfiguration::Cloud { .. } => {
-                    self.add_log_message(
-                        "cloud prediction not implemented".to_string(),
-                        log::Level::Error,
+                None => {
+                    #[cfg(feature = "serial")]
+                    detect_baud_rates(
+                        std::slice::from_mut(&mut configured.connection),
+                        &mut messages,
                     );
+                    messages.push((
+                        chrono::Local::now(),
+                        format!("started connection {:}", name),
+                        log::Level::Info,
+                    ));
+                    connections.push(configured);
                 }
             }
         }
+        for removed in &self.connections {
+            messages.push((
+                chrono::Local::now(),
+                format!("stopped connection {:}", removed.connection.name()),
+                log::Level::Info,
+            ));
+        }
+        self.connections = connections;
 
-        if let Some(path) = &self.configuration.output_file {
-            let mut locations = vec![];
-            for track in &self.tracks {
-                let track_locations: Vec<&crate::location::BalloonLocation> =
-                    track.locations.iter().collect();
-                locations.extend(track_locations);
-            }
-            let feature_collection =
-                crate::connection::text::file::locations_geojson_featurecollection(locations);
-
-            match std::fs::write(path, feature_collection.to_string()) {
-                Ok(_) => messages.push((
-                    chrono::Local::now(),
-                    format!("wrote telemetry to {:}", path.to_string_lossy()),
-                    log::Level::Debug,
-                )),
-                Err(error) => {
-                    messages.push((chrono::Local::now(), error.to_string(), log::Level::Error))
-                }
-            };
+        #[cfg(feature = "mqtt")]
+        if configuration.connections.mqtt != self.configuration.connections.mqtt {
+            self.mqtt = configuration.connections.mqtt.to_owned();
+            messages.push((
+                chrono::Local::now(),
+                match &self.mqtt {
+                    Some(mqtt) => format!("publishing new packets to {:?}", mqtt),
+                    None => String::from("stopped publishing to MQTT"),
+                },
+                log::Level::Info,
+            ));
         }
 
-        match self.log_level {
-            log::Level::Debug => {
-                self.log_messages.extend(messages);
-            }
-            _ => {
-                for (time, message, level) in messages {
-                    if level != log::Level::Debug {
-                        self.log_messages.push((time, message, level));
-                    }
-                }
-            }
+        for track in self.tracks.iter_mut() {
+            track.smoothing_window = configuration.ascent_rate.smoothing_window;
+            track.ascent_rate_threshold = configuration.ascent_rate.threshold;
+            track.ascent_rate_samples = configuration.ascent_rate.samples;
+            track.landing = configuration.landing.to_owned();
+            track.float = configuration.float.to_owned();
+            track.float_uncertainty = configuration.float_uncertainty();
+            track.terminal_velocity = configuration.terminal_velocity();
+            track.kalman = configuration.kalman.to_owned();
+            track.update_filtered();
         }
+
+        self.configuration = configuration;
+        self.log_messages.extend(messages);
+        self.write_log_file();
+    }
+
+    // how often to tick, so that each connection is read at its own interval
+    pub fn tick_interval(&self) -> chrono::Duration {
+        self.connections
+            .iter()
+            .map(|scheduled| scheduled.interval)
+            .filter(|interval| *interval > chrono::Duration::zero())
+            .fold(self.configuration.time.interval, chrono::Duration::min)
+    }
+
+    // the tick interval to sleep for, falling back to `time.interval` (which is validated to be positive)
+    pub fn tick_rate(&self) -> std::time::Duration {
+        self.tick_interval()
+            .to_std()
+            .or_else(|_| self.configuration.time.interval.to_std())
+            .unwrap_or(std::time::Duration::from_secs(1))
+    }
+
+    pub fn add_log_message(&mut self, message: String, level: log::Level) {
+        self.log_messages
+            .push((chrono::Local::now(), message, level));
+    }
+
+    // indices of the tracks shown as tabs, in tab order
+    pub fn visible_tracks(&self) -> Vec<usize> {
+        let filter = self.track_filter.to_lowercase();
+        let mut indices: Vec<usize> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| track.display_name().to_lowercase().contains(&filter))
+            .map(|(index, _)| index)
+            .collect();
+        // stable, so that ties keep the order in which they were received
+        indices.sort_by(|a, b| {
+            compare_tracks(
+                &self.tracks[*a],
+                &self.tracks[*b],
+                self.track_order,
+                &self.configuration.primary_callsigns,
+            )
+        });
+        indices
+    }
+
+    pub fn next_track_order(&mut self) {
+        self.track_order = self.track_order.next();
+        self.status = Some((
+            chrono::Local::now(),
+            format!("tabs ordered by {:}", self.track_order),
+        ));
+    }
+
+    // `tab_index` of each shown tab, starting with the log
+    fn tabs(&self) -> Vec<usize> {
+        std::iter::once(0)
+            .chain(self.visible_tracks().into_iter().map(|index| index + 1))
+            .collect()
+    }
+
+    // position of the selected tab in the tab bar
+    pub fn selected_tab(&self) -> usize {
+        self.tabs()
+            .iter()
+            .position(|tab| tab == &self.tab_index)
+            .unwrap_or(0)
+    }
+
+    pub fn next_tab(&mut self) {
+        self.restored_track = None;
+        let tabs = self.tabs();
+        self.tab_index = tabs[(self.selected_tab() + 1) % tabs.len()];
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.restored_track = None;
+        let tabs = self.tabs();
+        self.tab_index = tabs[(self.selected_tab() + tabs.len() - 1) % tabs.len()];
+    }
+
+    // apply a change to the track filter, moving off of the selected track if it is now hidden
+    fn set_track_filter(&mut self, filter: String) {
+        self.track_filter = filter;
+        let tabs = self.tabs();
+        if !tabs.contains(&self.tab_index) {
+            self.tab_index = tabs.get(1).copied().unwrap_or(0);
+        }
+    }
+
+    fn on_filter_key(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        match key {
+            KeyCode::Char(character) => {
+                let mut filter = self.track_filter.to_owned();
+                filter.push(character);
+                self.set_track_filter(filter);
+            }
+            KeyCode::Backspace => {
+                let mut filter = self.track_filter.to_owned();
+                filter.pop();
+                self.set_track_filter(filter);
+            }
+            KeyCode::Enter => self.editing_filter = false,
+            // clear the filter to show every track
+            KeyCode::Esc => {
+                self.set_track_filter(String::new());
+                self.editing_filter = false;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn next_chart(&mut self) {
+        if self.chart_index < super::draw::CHARTS.len() - 1 {
+            self.chart_index += 1;
+        } else {
+            self.chart_index = 0;
+        }
+    }
+
+    pub fn previous_chart(&mut self) {
+        if self.chart_index > 0 {
+            self.chart_index -= 1;
+        } else {
+            self.chart_index = super::draw::CHARTS.len() - 1;
+        }
+    }
+
+    pub fn zoom_chart(&mut self, factor: f64) {
+        let mut view = self.chart_view.unwrap_or_default();
+        view.zoom(factor);
+        // return to autoscaling once fully zoomed out
+        self.chart_view = if view.width < 1.0 { Some(view) } else { None };
+    }
+
+    pub fn pan_chart(&mut self, fraction: f64) {
+        if let Some(view) = &mut self.chart_view {
+            view.pan(fraction);
+        }
+    }
+
+    pub fn on_key(&mut self, key: crossterm::event::KeyCode) {
+        use crate::configuration::keybindings::KeyAction;
+
+        // any key dismisses the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+        if self.editing_filter {
+            self.on_filter_key(key);
+            return;
+        }
+
+        let log_tab = self.tab_index == 0;
+        for action in self.configuration.keybindings.actions(&key) {
+            match action {
+                KeyAction::Quit => self.should_quit = true,
+                KeyAction::Refresh => self.refresh(),
+                KeyAction::NextTab => self.next_tab(),
+                KeyAction::PreviousTab => self.previous_tab(),
+                // scrolling applies to the log, and charts to the track tabs
+                KeyAction::ScrollUp if log_tab => {
+                    self.log_messages_scroll_offset =
+                        self.log_messages_scroll_offset.saturating_sub(1)
+                }
+                KeyAction::ScrollDown if log_tab => self.log_messages_scroll_offset += 1,
+                KeyAction::NextChart if !log_tab => self.next_chart(),
+                KeyAction::PreviousChart if !log_tab => self.previous_chart(),
+                KeyAction::ToggleMap => self.show_map = !self.show_map,
+                KeyAction::ToggleRaw if !log_tab => self.show_raw = !self.show_raw,
+                KeyAction::CopyPosition if !log_tab => self.copy_position(false),
+                KeyAction::CopyLanding if !log_tab => self.copy_position(true),
+                KeyAction::PredictDescent if !log_tab => self.predict_descent(),
+                KeyAction::ZoomIn if !log_tab => self.zoom_chart(0.5),
+                KeyAction::ZoomOut if !log_tab => self.zoom_chart(2.0),
+                KeyAction::PanLeft if !log_tab => self.pan_chart(-0.25),
+                KeyAction::PanRight if !log_tab => self.pan_chart(0.25),
+                KeyAction::ResetView if !log_tab => self.chart_view = None,
+                KeyAction::Filter => self.editing_filter = true,
+                KeyAction::Sort => self.next_track_order(),
+                KeyAction::Help => self.show_help = true,
+                KeyAction::Pause => {
+                    self.paused = !self.paused;
+                    self.add_log_message(
+                        String::from(if self.paused {
+                            "paused automatic retrieval"
+                        } else {
+                            "resumed automatic retrieval"
+                        }),
+                        log::Level::Info,
+                    );
+                }
+                _ => continue,
+            }
+            // only the first applicable action
+            break;
+        }
+    }
+
+    // copy the latest position of the current track (or its predicted landing) to the clipboard, as `lat, lon`
+    pub fn copy_position(&mut self, landing: bool) {
+        let track = match self
+            .tab_index
+            .checked_sub(1)
+            .and_then(|index| self.tracks.get(index))
+        {
+            Some(track) => track,
+            None => return,
+        };
+        let (description, location) = if landing {
+            (
+                "predicted landing",
+                track
+                    .prediction
+                    .as_ref()
+                    .and_then(|prediction| prediction.last().cloned()),
+            )
+        } else {
+            (
+                "latest position",
+                crate::location::track::without_outliers(&track.locations)
+                    .last()
+                    .cloned(),
+            )
+        };
+        let description = format!("{:} {:}", track.name, description);
+
+        let location = match location {
+            Some(location) => location,
+            None => {
+                self.status = Some((chrono::Local::now(), format!("no {:} to copy", description)));
+                return;
+            }
+        };
+        let text = format!(
+            "{:.5}, {:.5}",
+            location.location.coord.y, location.location.coord.x
+        );
+
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+        let result = match &mut self.clipboard {
+            Some(clipboard) => clipboard
+                .set_text(text.to_owned())
+                .map_err(|error| error.to_string()),
+            None => Err(String::from("clipboard is not available")),
+        };
+
+        match result {
+            Ok(_) => {
+                let message = format!("copied {:} ({:})", description, text);
+                self.status = Some((chrono::Local::now(), message.to_owned()));
+                self.add_log_message(message, log::Level::Info);
+            }
+            Err(error) => {
+                let message = format!("could not copy {:} - {:}", description, error);
+                self.status = Some((chrono::Local::now(), message.to_owned()));
+                self.add_log_message(message, log::Level::Warn);
+            }
+        }
+    }
+
+    // predict the landing of the selected track as if it were already descending from its latest location
+    pub fn predict_descent(&mut self) {
+        let index = match self
+            .tab_index
+            .checked_sub(1)
+            .filter(|index| *index < self.tracks.len())
+        {
+            Some(index) => index,
+            None => return,
+        };
+        let name = self.tracks[index].name.to_owned();
+
+        let start = match self.tracks[index].kinematic_locations().last() {
+            Some(location) => location.location.to_owned(),
+            None => {
+                self.status = Some((
+                    chrono::Local::now(),
+                    format!("{:} has no location to predict from", name),
+                ));
+                return;
+            }
+        };
+
+        let prediction_configuration = self
+            .configuration
+            .prediction
+            .as_ref()
+            .map(|prediction| prediction.predictions().0);
+        let sea_level_descent_rate = match prediction_configuration {
+            Some(prediction) => prediction.profile.descent_rate(),
+            None => crate::configuration::prediction::default_sea_level_descent_rate(),
+        };
+        // the balloon bursts where it is now
+        let profile = crate::prediction::FlightProfile::new_standard(
+            0.0,
+            start.altitude.unwrap_or_default(),
+            sea_level_descent_rate,
+        );
+
+        let mut query = crate::prediction::tawhiri::TawhiriQuery::new(
+            &start,
+            &profile,
+            None,
+            None,
+            Some(format!("{:} descent", name)),
+            true,
+            None,
+        );
+        if let Some(prediction) = prediction_configuration {
+            if let Some(api_url) = &prediction.api_url {
+                query.query.api_url = api_url.to_owned();
+            }
+            if let Some(coordinate_precision) = prediction.coordinate_precision {
+                query.coordinate_precision = coordinate_precision;
+            }
+        }
+
+        match self
+            .prediction_cache
+            .prediction(&query, &self.configuration.http)
+        {
+            Ok((prediction, metadata)) => {
+                let message = match prediction.last() {
+                    Some(landing) => format!(
+                        "{:} descent from {:.0} m predicts landing at {:.5}, {:.5} at {:}",
+                        name,
+                        start.altitude.unwrap_or_default(),
+                        landing.location.coord.y,
+                        landing.location.coord.x,
+                        landing.location.time.format("%H:%M:%S"),
+                    ),
+                    None => format!("{:} descent prediction is empty", name),
+                };
+                self.status = Some((chrono::Local::now(), message.to_owned()));
+                self.add_log_message(message, log::Level::Info);
+                if !prediction.is_empty() {
+                    let warnings = prediction_warnings(&self.tracks[index], Some(&metadata));
+                    self.log_messages.extend(warnings);
+                    self.tracks[index].descent_prediction = Some(prediction);
+                }
+            }
+            Err(error) => {
+                let message = format!("{:} - {:}", name, error);
+                self.status = Some((chrono::Local::now(), message.to_owned()));
+                self.add_log_message(message, log::Level::Error);
+            }
+        }
+    }
+
+    // read from every connection now, regardless of their intervals
+    pub fn refresh(&mut self) {
+        for scheduled in self.connections.iter_mut() {
+            scheduled.last_access = None;
+        }
+        self.on_tick();
+    }
+
+    pub fn on_tick(&mut self) {
+        // nothing to do until at least one connection is due to be read
+        let now = chrono::Local::now();
+        if !self.connections.is_empty()
+            && !self.connections.iter().any(|scheduled| scheduled.due(now))
+        {
+            return;
+        }
+
+        let tracks = &mut self.tracks;
+
+        let mut messages = vec![];
+
+        let online = match &mut self.connectivity {
+            Some(probe) => {
+                match probe.check() {
+                    Some(crate::connection::ConnectivityChange::Lost) => messages.push((
+                        chrono::Local::now(),
+                        "network connection lost; skipping online connections and predictions"
+                            .to_string(),
+                        log::Level::Warn,
+                    )),
+                    Some(crate::connection::ConnectivityChange::Restored) => messages.push((
+                        chrono::Local::now(),
+                        "network connection restored".to_string(),
+                        log::Level::Info,
+                    )),
+                    None => {}
+                }
+                probe.online()
+            }
+            None => true,
+        };
+
+        #[cfg_attr(
+            not(any(feature = "mqtt", feature = "influxdb", feature = "api")),
+            allow(unused_variables)
+        )]
+        let (retrieval_messages, outcomes, new_locations) = crate::retrieve::retrieve_locations(
+            &mut self.connections,
+            tracks,
+            &self.configuration,
+            online,
+        );
+        messages.extend(retrieval_messages);
+        for outcome in &outcomes {
+            self.connections[outcome.index].status.update(outcome);
+        }
+
+        for track in tracks.iter_mut() {
+            track.flag_outliers(
+                self.configuration.outliers.maximum_ground_speed,
+                self.configuration.outliers.maximum_ascent_rate,
+            );
+            track.update_filtered();
+        }
+
+        // each location added this tick, at its place in its track
+        #[cfg(any(feature = "mqtt", feature = "influxdb"))]
+        let added: Vec<(&crate::location::track::BalloonTrack, usize)> = new_locations
+            .iter()
+            .filter_map(|(name, location)| {
+                let track = tracks.iter().find(|track| &track.name == name)?;
+                let index = track
+                    .locations
+                    .iter()
+                    .position(|existing| existing == location)?;
+                Some((track, index))
+            })
+            .collect();
+
+        #[cfg(feature = "mqtt")]
+        if let Some(mqtt) = &mut self.mqtt {
+            let new_locations: Vec<(&str, &crate::location::BalloonLocation)> = added
+                .iter()
+                .map(|(track, index)| (track.name.as_str(), &track.locations[*index]))
+                .collect();
+            match mqtt.publish(&new_locations) {
+                Ok(0) => {}
+                Ok(num_published) => messages.push((
+                    chrono::Local::now(),
+                    format!("published {:} packet(s) to MQTT", num_published),
+                    log::Level::Debug,
+                )),
+                Err(error) => {
+                    messages.push((chrono::Local::now(), error.to_string(), log::Level::Error))
+                }
+            }
+            // queued packets are sent once the broker is reachable again
+            if let Some(error) = mqtt.take_error() {
+                messages.push((chrono::Local::now(), error.to_string(), log::Level::Warn));
+            }
+        }
+
+        #[cfg(feature = "influxdb")]
+        if let Some(influxdb) = &self.configuration.connections.influxdb {
+            match influxdb.write(&added, &self.configuration.http) {
+                Ok(0) => {}
+                Ok(num_written) => messages.push((
+                    chrono::Local::now(),
+                    format!("wrote {:} packet(s) to {:?}", num_written, influxdb),
+                    log::Level::Debug,
+                )),
+                Err(error) => {
+                    messages.push((chrono::Local::now(), error.to_string(), log::Level::Error))
+                }
+            }
+        }
+
+        for track in tracks.iter() {
+            if let Some(last_location) = track.locations.last() {
+                // only warn once per packet
+                if self.battery_warnings.get(&track.name) == Some(&last_location.location.time) {
+                    continue;
+                }
+                if let Some(warning) = track.battery_warning(
+                    self.configuration.battery.minimum_voltage,
+                    self.configuration.battery.maximum_decline_rate,
+                ) {
+                    messages.push((
+                        chrono::Local::now(),
+                        format!("{:} - {:}", track.name, warning),
+                        log::Level::Warn,
+                    ));
+                    self.battery_warnings
+                        .insert(track.name.to_owned(), last_location.location.time);
+                }
+            }
+        }
+
+        if self.configuration.alerts.enabled {
+            let mut ring = false;
+            for track in tracks.iter() {
+                if track.landed() {
+                    continue;
+                }
+                let alert = match track.estimated_time_to_ground() {
+                    Some(time_to_ground)
+                        if time_to_ground < self.configuration.alerts.time_to_ground =>
+                    {
+                        FlightAlert::NearingLanding
+                    }
+                    _ if track.descending() => FlightAlert::Descending,
+                    _ => continue,
+                };
+
+                // only alert once for each stage of the flight
+                if self
+                    .alerts
+                    .get(&track.name)
+                    .is_some_and(|raised| raised >= &alert)
+                {
+                    continue;
+                }
+                messages.push((
+                    chrono::Local::now(),
+                    format!("{:} - {:}", track.name, alert),
+                    log::Level::Warn,
+                ));
+                self.alerts.insert(track.name.to_owned(), alert);
+                ring = true;
+            }
+            if ring && self.configuration.alerts.bell {
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+            }
+        }
+
+        // compare the prediction from before each balloon landed to where it came down
+        for track in tracks.iter_mut() {
+            if let Some(error) = track.touchdown_error() {
+                messages.push((
+                    chrono::Local::now(),
+                    format!(
+                        "{:} - prediction error: {:}",
+                        track.name,
+                        self.configuration.units.distance(error, 1)
+                    ),
+                    log::Level::Info,
+                ));
+                if let Some(path) = &self.configuration.landing.error_file {
+                    if let Err(error) =
+                        append_prediction_error(path, &self.configuration.name, track, error)
+                    {
+                        messages.push((
+                            chrono::Local::now(),
+                            format!(
+                                "could not write to {:} - {:}",
+                                path.to_string_lossy(),
+                                error
+                            ),
+                            log::Level::Error,
+                        ));
+                    }
+                }
+            }
+        }
+
+        if !online {
+            messages.push((
+                chrono::Local::now(),
+                "offline; skipped prediction".to_string(),
+                log::Level::Debug,
+            ));
+        } else if let Some(prediction_configuration) = &self.configuration.prediction {
+            let profiles = prediction_configuration.profiles();
+            let (prediction_configuration, perturbations) = prediction_configuration.predictions();
+
+            let existing_prediction = if let Some(path) = &prediction_configuration.output_file {
+                // read from an existing prediction output file
+                if path.exists() {
+                    let mut existing_prediction_file =
+                        crate::connection::text::file::locations_file_connection(path);
+                    messages.push((
+                        chrono::Local::now(),
+                        format!(
+                            "reading existing prediction output file: {:}",
+                            path.to_string_lossy()
+                        ),
+                        log::Level::Debug,
+                    ));
+                    existing_prediction_file
+                        .retrieve_locations(&self.configuration.http)
+                        .ok()
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            for track in tracks {
+                // keep the prediction from before touchdown
+                if track.landed() {
+                    continue;
+                }
+
+                // landing points of each perturbed profile
+                let mut predicted_landings = vec![];
+                for perturbation in &perturbations {
+                    match track.prediction(
+                        perturbation,
+                        &mut self.prediction_cache,
+                        &self.configuration.http,
+                    ) {
+                        Ok((prediction, _)) => {
+                            predicted_landings.extend(prediction.last().cloned())
+                        }
+                        Err(error) => messages.push((
+                            chrono::Local::now(),
+                            format!("{:} - {:}", perturbation.name, error),
+                            log::Level::Error,
+                        )),
+                    }
+                }
+                track.predicted_landings = predicted_landings;
+
+                // additional named profiles, shown alongside the default prediction
+                let mut profile_predictions = vec![];
+                for profile in profiles {
+                    match track.prediction(
+                        profile,
+                        &mut self.prediction_cache,
+                        &self.configuration.http,
+                    ) {
+                        Ok((prediction, _)) => {
+                            if !prediction.is_empty() {
+                                profile_predictions.push((profile.name.to_owned(), prediction));
+                            }
+                        }
+                        Err(error) => messages.push((
+                            chrono::Local::now(),
+                            format!("{:} - {:}", profile.name, error),
+                            log::Level::Error,
+                        )),
+                    }
+                }
+                track.predictions = profile_predictions;
+
+                let (prediction, metadata) = match track.prediction(
+                    prediction_configuration,
+                    &mut self.prediction_cache,
+                    &self.configuration.http,
+                ) {
+                    Ok((retrieved_prediction, metadata)) => {
+                        (Some(retrieved_prediction), Some(metadata))
+                    }
+                    Err(error) => {
+                        messages.push((chrono::Local::now(), error.to_string(), log::Level::Error));
+                        (existing_prediction.to_owned(), None)
+                    }
+                };
+
+                if let Some(prediction) = &prediction {
+                    if prediction.is_empty() {
+                        continue;
+                    }
+                }
+
+                messages.extend(prediction_warnings(track, metadata.as_ref()));
+                track.prediction = prediction;
+                track.prediction_metadata = metadata;
+            }
+            messages.push((
+                chrono::Local::now(),
+                format!(
+                    "prediction cache: {:} hit(s), {:} miss(es)",
+                    self.prediction_cache.hits, self.prediction_cache.misses
+                ),
+                log::Level::Debug,
+            ));
+
+            // the default prediction and each named profile are written to their own output files
+            let outputs = std::iter::once((prediction_configuration, None)).chain(
+                profiles
+                    .iter()
+                    .map(|profile| (profile, Some(profile.name.as_str()))),
+            );
+            for (configuration, profile_name) in outputs {
+                if let Some(path) = &configuration.output_file {
+                    let mut locations = vec![];
+                    for track in &self.tracks {
+                        let prediction = match profile_name {
+                            None => track.prediction.as_ref(),
+                            Some(profile_name) => track
+                                .predictions
+                                .iter()
+                                .find(|(name, _)| name == profile_name)
+                                .map(|(_, prediction)| prediction),
+                        };
+                        if let Some(prediction) = prediction {
+                            locations.extend(prediction.iter());
+                        }
+                    }
+
+                    match crate::connection::text::file::write_locations(path, locations) {
+                        Ok(_) => messages.push((
+                            chrono::Local::now(),
+                            format!("wrote predictions to {:}", path.to_string_lossy()),
+                            log::Level::Debug,
+                        )),
+                        Err(error) => messages.push((
+                            chrono::Local::now(),
+                            error.to_string(),
+                            log::Level::Error,
+                        )),
+                    };
+                }
+            }
+        }
+
+        if let Some(path) = &self.configuration.output_file {
+            match crate::connection::text::file::write_tracks(path, &self.tracks) {
+                Ok(_) => messages.push((
+                    chrono::Local::now(),
+                    format!("wrote telemetry to {:}", path.to_string_lossy()),
+                    log::Level::Debug,
+                )),
+                Err(error) => {
+                    messages.push((chrono::Local::now(), error.to_string(), log::Level::Error))
+                }
+            };
+        }
+
+        if let Some(resampled_output) = &self.configuration.resampled_output {
+            match crate::connection::text::file::write_resampled_tracks(
+                &resampled_output.path,
+                &self.tracks,
+                resampled_output.interval,
+            ) {
+                Ok(_) => messages.push((
+                    chrono::Local::now(),
+                    format!(
+                        "wrote resampled telemetry to {:}",
+                        resampled_output.path.to_string_lossy()
+                    ),
+                    log::Level::Debug,
+                )),
+                Err(error) => {
+                    messages.push((chrono::Local::now(), error.to_string(), log::Level::Error))
+                }
+            };
+        }
+
+        match self.log_level {
+            log::Level::Debug => {
+                self.log_messages.extend(messages);
+            }
+            _ => {
+                for (time, message, level) in messages {
+                    if level != log::Level::Debug {
+                        self.log_messages.push((time, message, level));
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "api")]
+        if let Some(api) = &self.api {
+            api.update(&self.tracks);
+            // push new locations, along with the predictions made from them
+            for track in &self.tracks {
+                let track_locations: Vec<&crate::location::BalloonLocation> = new_locations
+                    .iter()
+                    .filter(|(name, _)| name == &track.name)
+                    .map(|(_, location)| location)
+                    .collect();
+                if !track_locations.is_empty() {
+                    api.push(track, &track_locations);
+                }
+            }
+        }
+
+        self.select_restored_track();
+        self.write_log_file();
+    }
+}
+
+pub fn log_line(
+    time: &chrono::DateTime<chrono::Local>,
+    message: &String,
+    level: &log::Level,
+    timezone: Option<chrono_tz::Tz>,
+) -> String {
+    format!(
+        "{:} {:<5} {:}",
+        crate::utilities::display_time(time, timezone, &crate::DATETIME_FORMAT),
+        level,
+        message
+    )
+}
+
+// append the predicted and actual landing of a landed track to a CSV file, writing a header to a new file
+fn append_prediction_error(
+    path: &std::path::Path,
+    flight: &str,
+    track: &crate::location::track::BalloonTrack,
+    error: f64,
+) -> std::io::Result<()> {
+    let (predicted, actual) = match (
+        track
+            .prediction
+            .as_ref()
+            .and_then(|prediction| prediction.last()),
+        crate::location::track::without_outliers(&track.locations).pop(),
+    ) {
+        (Some(predicted), Some(actual)) => (predicted, actual),
+        _ => return Ok(()),
+    };
+
+    let exists = path.exists();
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    // names may contain commas or quotes
+    let mut writer = csv::Writer::from_writer(file);
+    if !exists {
+        writer.write_record([
+            "flight",
+            "name",
+            "predicted_time",
+            "predicted_longitude",
+            "predicted_latitude",
+            "time",
+            "longitude",
+            "latitude",
+            "error_m",
+        ])?;
+    }
+    writer.write_record([
+        flight.to_string(),
+        track.name.to_owned(),
+        predicted.location.time.to_rfc3339(),
+        format!("{:.5}", predicted.location.coord.x),
+        format!("{:.5}", predicted.location.coord.y),
+        actual.location.time.to_rfc3339(),
+        format!("{:.5}", actual.location.coord.x),
+        format!("{:.5}", actual.location.coord.y),
+        format!("{:.0}", error),
+    ])?;
+    writer.flush()
+}
+
+// log messages for warnings returned with a prediction, skipping those already returned with the track's previous prediction
+fn prediction_warnings(
+    track: &crate::location::track::BalloonTrack,
+    metadata: Option<&crate::prediction::PredictionMetadata>,
+) -> Vec<(chrono::DateTime<chrono::Local>, String, log::Level)> {
+    let previous = match &track.prediction_metadata {
+        Some(previous) => previous.warnings.as_slice(),
+        None => &[],
+    };
+    metadata
+        .map(|metadata| metadata.warnings.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|warning| !previous.contains(warning))
+        .map(|warning| {
+            (
+                chrono::Local::now(),
+                format!("{:} prediction - {:}", track.name, warning),
+                log::Level::Warn,
+            )
+        })
+        .collect()
+}
+
+// connections described by the given configuration, filling in the global callsigns and time range where not given
+fn configured_connections(
+    configuration: &crate::configuration::RunConfiguration,
+    log_messages: &mut Vec<(chrono::DateTime<chrono::Local>, String, log::Level)>,
+) -> Vec<crate::connection::Connection> {
+    let mut connections = vec![];
+
+    if let Some(text_configuration) = &configuration.connections.text.to_owned() {
+        for text_stream in text_configuration {
+            let connection = match text_stream {
+                crate::connection::text::TextStream::GeoJsonFile(connection) => {
+                    let connection = connection.to_owned();
+                    log_messages.push((
+                        chrono::Local::now(),
+                        format!("reading GeoJSON file: {:}", connection.path),
+                        log::Level::Info,
+                    ));
+
+                    crate::connection::Connection::GeoJsonFile(connection)
+                }
+                crate::connection::text::TextStream::AprsTextFile(connection) => {
+                    let mut connection = connection.to_owned();
+                    if connection.callsigns.is_none() {
+                        if let Some(callsigns) = &configuration.callsigns {
+                            connection.callsigns = Some(callsigns.to_owned());
+                        }
+                    }
+                    log_messages.push((
+                        chrono::Local::now(),
+                        format!("reading text file of APRS frames: {:}", connection.path),
+                        log::Level::Info,
+                    ));
+                    crate::connection::Connection::AprsTextFile(connection)
+                }
+                #[cfg(feature = "serial")]
+                crate::connection::text::TextStream::AprsSerial(connection) => {
+                    let mut connection = connection.to_owned();
+                    if connection.callsigns.is_none() {
+                        if let Some(callsigns) = &configuration.callsigns {
+                            connection.callsigns = Some(callsigns.to_owned());
+                        }
+                    }
+                    crate::connection::Connection::AprsSerial(connection)
+                }
+            };
+            connections.push(connection);
+        }
+    }
+
+    #[cfg(feature = "aprsfi")]
+    if let Some(aprs_fi_query) = &configuration.connections.aprs_fi {
+        if let Some(callsigns) = &configuration.callsigns {
+            let mut connection = aprs_fi_query.to_owned();
+            if connection.callsigns.is_none() {
+                connection.callsigns = Some(callsigns.to_owned());
+            }
+            connections.push(crate::connection::Connection::AprsFi(connection));
+        } else {
+            log_messages.push((
+                chrono::Local::now(),
+                "APRS.fi requires a list of callsigns".to_string(),
+                log::Level::Error,
+            ));
+        }
+    }
+
+    #[cfg(feature = "aprsis")]
+    if let Some(connection) = &configuration.connections.aprs_is {
+        let mut connection = connection.to_owned();
+        if connection.callsigns.is_none() {
+            if let Some(callsigns) = &configuration.callsigns {
+                connection.callsigns = Some(callsigns.to_owned());
+            }
+        }
+        log_messages.push((
+            chrono::Local::now(),
+            format!(
+                "listening to APRS-IS server {:}:{:}",
+                connection.hostname, connection.port
+            ),
+            log::Level::Info,
+        ));
+        connections.push(crate::connection::Connection::AprsIs(connection));
+    }
+
+    #[cfg(feature = "ais")]
+    if let Some(connection) = &configuration.connections.ais {
+        log_messages.push((
+            chrono::Local::now(),
+            format!(
+                "listening to AISStream for vessels {:}",
+                connection.mmsi.join(", ")
+            ),
+            log::Level::Info,
+        ));
+        connections.push(crate::connection::Connection::AisStream(
+            connection.to_owned(),
+        ));
+    }
+
+    #[cfg(feature = "sondehub")]
+    if let Some(connection) = &configuration.connections.sondehub {
+        if let Some(callsigns) = &configuration.callsigns {
+            let mut connection = connection.to_owned();
+            if connection.callsigns.is_none() {
+                connection.callsigns = Some(callsigns.to_owned());
+            }
+            if connection.start.is_none() {
+                connection.start = configuration.time.start;
+            }
+            if connection.end.is_none() {
+                connection.end = configuration.time.end;
+            }
+
+            connections.push(crate::connection::Connection::SondeHub(connection));
+        } else {
+            log_messages.push((
+                chrono::Local::now(),
+                "SondeHub requires a list of callsigns".to_string(),
+                log::Level::Error,
+            ));
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    if let Some(database_credentials) = &configuration.connections.database {
+        connections.push(crate::connection::Connection::PacketDatabase(
+            crate::connection::postgres::PacketDatabase::from_credentials(database_credentials),
+        ));
+    }
+
+    connections
+}
+
+// detect the baud rate of serial connections configured with `baud_rate: auto`
+#[cfg(feature = "serial")]
+fn detect_baud_rates(
+    connections: &mut [crate::connection::Connection],
+    log_messages: &mut Vec<(chrono::DateTime<chrono::Local>, String, log::Level)>,
+) {
+    for connection in connections.iter_mut() {
+        if let crate::connection::Connection::AprsSerial(connection) = connection {
+            if connection.needs_detection() {
+                log_messages.push((
+                    chrono::Local::now(),
+                    match connection.detect_baud_rate() {
+                        Some(baud_rate) => {
+                            format!("detected baud rate {:} on {:}", baud_rate, connection.port)
+                        }
+                        None => format!(
+                            "could not detect baud rate on {:}; using {:}",
+                            connection.port,
+                            connection.address()
+                        ),
+                    },
+                    log::Level::Info,
+                ));
+            }
+        }
+    }
+}
+
+// whether the configured path refers to the current path, which may have had a file name added to a configured directory
+fn same_path(
+    configured: &Option<std::path::PathBuf>,
+    current: &Option<std::path::PathBuf>,
+) -> bool {
+    match (configured, current) {
+        (Some(configured), Some(current)) => {
+            configured == current || current.parent() == Some(configured.as_path())
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_view() {
+        let mut view = ChartView::default();
+        assert_eq!(view.window([0.0, 100.0]), [0.0, 100.0]);
+
+        view.zoom(0.5);
+        assert_eq!(view.window([0.0, 100.0]), [25.0, 75.0]);
+
+        view.pan(-0.25);
+        assert_eq!(view.window([0.0, 100.0]), [12.5, 62.5]);
+
+        // cannot pan past the start
+        view.pan(-1.0);
+        assert_eq!(view.window([0.0, 100.0]), [0.0, 50.0]);
+        assert_eq!(view.window([100.0, 300.0]), [100.0, 200.0]);
+
+        view.zoom(4.0);
+        assert_eq!(view, ChartView::default());
+    }
+
+    #[test]
+    fn test_track_filter() {
+        use crossterm::event::KeyCode;
+
+        let mut app = PacketravenApp::new(
+            crate::configuration::RunConfiguration::default(),
+            log::Level::Info,
+        );
+        for name in ["W3EAX-8", "KC3SKW-9", "W3EAX-11"] {
+            app.tracks
+                .push(crate::location::track::BalloonTrack::new(name.to_string()));
+        }
+
+        app.next_tab();
+        app.next_tab();
+        assert_eq!(app.tab_index, 2);
+
+        // the selected track is hidden, so the first match is selected
+        for key in [KeyCode::Char('/'), KeyCode::Char('w'), KeyCode::Char('3')] {
+            app.on_key(key);
+        }
+        assert!(app.editing_filter);
+        assert_eq!(app.visible_tracks(), vec![0, 2]);
+        assert_eq!(app.tab_index, 1);
+
+        // keys go to the filter until it is closed; with no matches, the log is selected
+        app.on_key(KeyCode::Char('q'));
+        assert!(!app.should_quit);
+        assert!(app.visible_tracks().is_empty());
+        assert_eq!(app.tab_index, 0);
+        app.on_key(KeyCode::Backspace);
+        app.on_key(KeyCode::Enter);
+        assert!(!app.editing_filter);
+
+        // hidden tracks are skipped
+        app.next_tab();
+        app.next_tab();
+        assert_eq!((app.tab_index, app.selected_tab()), (3, 2));
+        app.next_tab();
+        assert_eq!(app.tab_index, 0);
+        app.previous_tab();
+        assert_eq!(app.tab_index, 3);
+
+        app.on_key(KeyCode::Char('/'));
+        app.on_key(KeyCode::Esc);
+        assert!(app.track_filter.is_empty());
+        assert_eq!(app.visible_tracks().len(), 3);
+        assert_eq!(app.selected_tab(), 3);
+    }
+
+    #[test]
+    fn test_track_order() {
+        let mut app = PacketravenApp::new(
+            crate::configuration::RunConfiguration::default(),
+            log::Level::Info,
+        );
+        let start = chrono::Local::now();
+        for (seconds, name, altitude) in [
+            (0, "W3EAX-8", Some(20000.0)),
+            (60, "KC3SKW-9", None),
+            (30, "W3EAX-11", Some(3000.0)),
+        ] {
+            let mut track = crate::location::track::BalloonTrack::new(name.to_string());
+            track.push(crate::location::BalloonLocation {
+                location: crate::location::Location {
+                    time: start + chrono::Duration::seconds(seconds),
+                    coord: geo::coord! { x: -77.0, y: 39.0 },
+                    altitude,
+                },
+                data: crate::location::BalloonData::new(
+                    Some(name.to_string()),
+                    None,
+                    None,
+                    None,
+                    crate::location::LocationSource::None,
+                ),
+            });
+            app.tracks.push(track);
+        }
+
+        let mut orders = vec![];
+        for _ in 0..4 {
+            orders.push((app.track_order, app.visible_tracks()));
+            app.next_track_order();
+        }
+        assert_eq!(
+            orders,
+            vec![
+                (TrackOrder::Received, vec![0, 1, 2]),
+                (TrackOrder::Recent, vec![1, 2, 0]),
+                (TrackOrder::Altitude, vec![0, 2, 1]),
+                (TrackOrder::Name, vec![1, 2, 0]),
+            ]
+        );
+        assert_eq!(app.track_order, TrackOrder::Received);
+
+        app.configuration.primary_callsigns = vec!["W3EAX-11".to_string()];
+        assert_eq!(app.visible_tracks(), vec![2, 0, 1]);
     }
 }
@@ -1,12 +1,20 @@
 lazy_static::lazy_static! {
-    pub static ref CHARTS: Vec<String> = vec!["altitude / time".to_string(), "ascent rate / time".to_string(), "ground speed / altitude".to_string(), "coordinates (unprojected)".to_string()];
+    // minimum distance (degrees) to show around the track on the map
+    static ref MAP_PADDING: f64 = 0.5;
+    // how long to show a status message in the tab bar
+    static ref STATUS_DURATION: chrono::Duration = chrono::Duration::seconds(5);
+    pub static ref CHARTS: Vec<String> = vec!["altitude / time".to_string(), "ascent rate / time".to_string(), "ground speed / altitude".to_string(), "wind / altitude".to_string(), "coordinates (unprojected)".to_string()];
 }
 
+// chart data of a named profile prediction, with the index of the profile for its color
+type ProfileSeries = (usize, String, Vec<(f64, f64)>);
+
 pub fn draw<B: ratatui::backend::Backend>(
     frame: &mut ratatui::Frame<B>,
     app: &super::app::PacketravenApp,
 ) {
     let size = frame.size();
+    let palette = app.configuration.theme.palette();
 
     let areas = ratatui::layout::Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
@@ -20,12 +28,13 @@ pub fn draw<B: ratatui::backend::Backend>(
         .split(size);
 
     let mut titles: Vec<ratatui::text::Line> = app
-        .tracks
-        .iter()
+        .visible_tracks()
+        .into_iter()
+        .map(|index| &app.tracks[index])
         .map(|track| {
             ratatui::text::Line::from(vec![ratatui::text::Span::styled(
-                track.name.to_owned(),
-                ratatui::style::Style::default().fg(ratatui::style::Color::Green),
+                track.display_name(),
+                ratatui::style::Style::default().fg(palette.track_tab),
             )])
         })
         .collect();
@@ -33,10 +42,52 @@ pub fn draw<B: ratatui::backend::Backend>(
         0,
         ratatui::text::Line::from(vec![ratatui::text::Span::raw("Log")]),
     );
+    let mut tabs_block = ratatui::widgets::Block::default().borders(ratatui::widgets::Borders::ALL);
+    if app.paused {
+        tabs_block = tabs_block.title(ratatui::text::Span::styled(
+            "PAUSED",
+            ratatui::style::Style::default()
+                .fg(palette.warning)
+                .add_modifier(ratatui::style::Modifier::BOLD),
+        ));
+    }
+    if app.editing_filter || !app.track_filter.is_empty() {
+        tabs_block = tabs_block.title(ratatui::text::Span::styled(
+            format!(
+                " filter: {:}{:} ({:}/{:}) ",
+                app.track_filter,
+                if app.editing_filter { "_" } else { "" },
+                app.visible_tracks().len(),
+                app.tracks.len(),
+            ),
+            ratatui::style::Style::default().fg(palette.warning),
+        ));
+    }
+    if let Some((time, message)) = &app.status {
+        if chrono::Local::now() - *time < *STATUS_DURATION {
+            tabs_block = tabs_block.title(ratatui::text::Span::styled(
+                format!(" {:} ", message),
+                ratatui::style::Style::default().fg(palette.success),
+            ));
+        }
+    }
+    for track in &app.tracks {
+        if let Some(alert) = app.alerts.get(&track.name) {
+            if !track.landed() {
+                tabs_block = tabs_block.title(ratatui::text::Span::styled(
+                    format!(" {:} {:} ", track.name, alert),
+                    ratatui::style::Style::default()
+                        .fg(palette.alert_text)
+                        .bg(palette.alert_background)
+                        .add_modifier(ratatui::style::Modifier::BOLD),
+                ));
+            }
+        }
+    }
     let tabs = ratatui::widgets::Tabs::new(titles)
-        .block(ratatui::widgets::Block::default().borders(ratatui::widgets::Borders::ALL))
-        .select(app.tab_index)
-        .style(ratatui::style::Style::default().fg(ratatui::style::Color::Cyan))
+        .block(tabs_block)
+        .select(app.selected_tab())
+        .style(ratatui::style::Style::default().fg(palette.tab))
         .highlight_style(
             ratatui::style::Style::default()
                 .add_modifier(ratatui::style::Modifier::BOLD)
@@ -45,22 +96,47 @@ pub fn draw<B: ratatui::backend::Backend>(
     frame.render_widget(tabs, areas[0]);
 
     let bold_style = ratatui::style::Style::default().add_modifier(ratatui::style::Modifier::BOLD);
+    let units = &app.configuration.units;
+    let timezone = app.configuration.timezone;
 
     if app.tab_index == 0 {
+        // show the status of each connection below the log
+        let log_areas = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints(
+                [
+                    ratatui::layout::Constraint::Min(3),
+                    ratatui::layout::Constraint::Length(if app.connections.is_empty() {
+                        0
+                    } else {
+                        app.connections.len() as u16 + 2
+                    }),
+                ]
+                .as_ref(),
+            )
+            .split(areas[1]);
+
         let log = ratatui::widgets::Paragraph::new(
             app.log_messages
                 .iter()
                 .map(|(time, message, level)| {
                     let level_style = match level {
-                        log::Level::Error => bold_style.fg(ratatui::style::Color::Red),
-                        log::Level::Warn => bold_style.fg(ratatui::style::Color::Yellow),
-                        log::Level::Info => bold_style.fg(ratatui::style::Color::Blue),
+                        log::Level::Error => bold_style.fg(palette.error),
+                        log::Level::Warn => bold_style.fg(palette.warning),
+                        log::Level::Info => bold_style.fg(palette.info),
                         _ => bold_style,
                     };
 
                     ratatui::text::Line::from(vec![
                         ratatui::text::Span::styled(
-                            format!("{:} ", time.format(&crate::DATETIME_FORMAT)),
+                            format!(
+                                "{:} ",
+                                crate::utilities::display_time(
+                                    time,
+                                    timezone,
+                                    &crate::DATETIME_FORMAT
+                                )
+                            ),
                             bold_style,
                         ),
                         ratatui::text::Span::styled(format!("{:<5} ", level), level_style),
@@ -72,7 +148,42 @@ pub fn draw<B: ratatui::backend::Backend>(
         .scroll((app.log_messages_scroll_offset, 0))
         .wrap(ratatui::widgets::Wrap { trim: true })
         .block(ratatui::widgets::Block::default().borders(ratatui::widgets::Borders::ALL));
-        frame.render_widget(log, areas[1]);
+        frame.render_widget(log, log_areas[0]);
+
+        if !app.connections.is_empty() {
+            let now = chrono::Local::now();
+            let statuses = ratatui::widgets::Paragraph::new(
+                app.connections
+                    .iter()
+                    .map(|scheduled| {
+                        let status_style = if scheduled.status.failing() {
+                            bold_style.fg(palette.error)
+                        } else if scheduled.status.last_success.is_some() {
+                            bold_style.fg(palette.success)
+                        } else {
+                            bold_style
+                        };
+
+                        ratatui::text::Line::from(vec![
+                            ratatui::text::Span::styled(
+                                format!("{:}: ", scheduled.connection.name()),
+                                bold_style,
+                            ),
+                            ratatui::text::Span::styled(
+                                scheduled.status.summary(now),
+                                status_style,
+                            ),
+                        ])
+                    })
+                    .collect::<Vec<ratatui::text::Line>>(),
+            )
+            .block(
+                ratatui::widgets::Block::default()
+                    .title("Connections")
+                    .borders(ratatui::widgets::Borders::ALL),
+            );
+            frame.render_widget(statuses, log_areas[1]);
+        }
     } else {
         let track = &app.tracks[app.tab_index - 1];
         if !track.locations.is_empty() {
@@ -95,6 +206,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                         ratatui::layout::Constraint::Min(31),
                         ratatui::layout::Constraint::Min(30),
                         ratatui::layout::Constraint::Min(30),
+                        ratatui::layout::Constraint::Min(30),
                     ]
                     .as_ref(),
                 )
@@ -112,14 +224,14 @@ pub fn draw<B: ratatui::backend::Backend>(
 
             let start_time = track.locations.first().unwrap().location.time;
             let end_time = track.locations.last().unwrap().location.time;
-            let seconds_since_start: Vec<f64> = track
-                .locations
+            // leave outliers out of charts and statistics
+            let valid_locations = crate::location::track::without_outliers(&track.locations);
+            let seconds_since_start: Vec<f64> = valid_locations
                 .iter()
                 .map(|location| (location.location.time - start_time).num_seconds() as f64)
                 .collect();
 
-            let locations_with_altitude: Vec<&crate::location::BalloonLocation> = track
-                .locations
+            let locations_with_altitude: Vec<&crate::location::BalloonLocation> = valid_locations
                 .iter()
                 .filter(|location| location.location.altitude.is_some())
                 .collect();
@@ -184,7 +296,11 @@ pub fn draw<B: ratatui::backend::Backend>(
                     crate::utilities::duration_string(
                         &(last_location.location.time - chrono::Local::now())
                     ),
-                    last_location.location.time.format(&crate::DATETIME_FORMAT),
+                    crate::utilities::display_time(
+                        &last_location.location.time,
+                        timezone,
+                        &crate::DATETIME_FORMAT
+                    ),
                 )),
             ])];
 
@@ -208,25 +324,131 @@ pub fn draw<B: ratatui::backend::Backend>(
             if let Some(altitude) = last_location.location.altitude {
                 last_location_info.push(ratatui::text::Line::from(vec![
                     ratatui::text::Span::styled("altitude: ", bold_style),
-                    ratatui::text::Span::raw(format!("{:.2} m", altitude)),
+                    ratatui::text::Span::raw(units.length(altitude, 2)),
+                ]));
+            }
+
+            if let Some(heading) = track.heading() {
+                let speed = match last_location.data.speed {
+                    Some(speed) => format!(" at {:}", units.speed(speed, 2)),
+                    None => String::new(),
+                };
+                last_location_info.push(ratatui::text::Line::from(vec![
+                    ratatui::text::Span::styled("heading: ", bold_style),
+                    ratatui::text::Span::raw(format!(
+                        "{:} {:.0}°{:}",
+                        heading_arrow(heading),
+                        heading,
+                        speed
+                    )),
+                ]));
+            }
+
+            if let Some(ground_station) = &app.configuration.ground_station {
+                last_location_info.push(ratatui::text::Line::from(vec![
+                    ratatui::text::Span::styled("from station: ", bold_style),
+                    ratatui::text::Span::raw(format!(
+                        "{:.0}° at {:}",
+                        ground_station.bearing_to(&last_location.location),
+                        units.distance(ground_station.distance_to(&last_location.location), 2),
+                    )),
+                ]));
+            }
+
+            if let Some(voltage) = last_location
+                .data
+                .telemetry
+                .as_ref()
+                .and_then(|telemetry| telemetry.voltage)
+            {
+                let trend = match track.voltage_trend() {
+                    Some(rate) if rate < -0.05 => " ↓",
+                    Some(rate) if rate > 0.05 => " ↑",
+                    Some(_) => " →",
+                    None => "",
+                };
+                let voltage_style = if track
+                    .battery_warning(
+                        app.configuration.battery.minimum_voltage,
+                        app.configuration.battery.maximum_decline_rate,
+                    )
+                    .is_some()
+                {
+                    ratatui::style::Style::default().fg(palette.error)
+                } else {
+                    ratatui::style::Style::default()
+                };
+                last_location_info.push(ratatui::text::Line::from(vec![
+                    ratatui::text::Span::styled("voltage: ", bold_style),
+                    ratatui::text::Span::styled(
+                        format!("{:.2} V{:}", voltage, trend),
+                        voltage_style,
+                    ),
                 ]));
             }
 
+            if let Some(phase) = last_location.data.phase {
+                // the phase computed from the ascent rate, to show whether the two agree
+                let computed = if track.ascending() {
+                    Some(crate::location::phase::FlightPhase::Ascending)
+                } else if track.descending() {
+                    Some(crate::location::phase::FlightPhase::Descending)
+                } else if track.floating() {
+                    Some(crate::location::phase::FlightPhase::Floating)
+                } else {
+                    None
+                };
+                let mut spans = vec![
+                    ratatui::text::Span::styled("phase: ", bold_style),
+                    ratatui::text::Span::raw(phase.to_string()),
+                ];
+                if let Some(computed) = computed.filter(|computed| *computed != phase) {
+                    spans.push(ratatui::text::Span::styled(
+                        format!(" (computed {:})", computed),
+                        ratatui::style::Style::default().fg(palette.warning),
+                    ));
+                }
+                last_location_info.push(ratatui::text::Line::from(spans));
+            }
+
+            if let Some(telemetry) = &last_location.data.telemetry {
+                if let Some(temperature) = telemetry.temperature {
+                    last_location_info.push(ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("temperature: ", bold_style),
+                        ratatui::text::Span::raw(format!("{:.1} °C", temperature)),
+                    ]));
+                }
+                if let Some(pressure) = telemetry.pressure {
+                    last_location_info.push(ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("pressure: ", bold_style),
+                        ratatui::text::Span::raw(format!("{:.1} hPa", pressure)),
+                    ]));
+                }
+                if let Some(humidity) = telemetry.humidity {
+                    last_location_info.push(ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("humidity: ", bold_style),
+                        ratatui::text::Span::raw(format!("{:.1} %", humidity)),
+                    ]));
+                }
+                if let Some(satellites) = telemetry.satellites {
+                    last_location_info.push(ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("satellites: ", bold_style),
+                        ratatui::text::Span::raw(format!("{:}", satellites)),
+                    ]));
+                }
+            }
+
             if track.locations.len() > 1 {
                 last_location_info.extend([
                     ratatui::text::Line::from(vec![
                         ratatui::text::Span::styled("over ground: ", bold_style),
-                        ratatui::text::Span::raw(format!(
-                            "{:.2} m",
-                            overground_distances.last().unwrap(),
-                        )),
+                        ratatui::text::Span::raw(
+                            units.length(*overground_distances.last().unwrap(), 2),
+                        ),
                     ]),
                     ratatui::text::Line::from(vec![
                         ratatui::text::Span::styled("ground speed: ", bold_style),
-                        ratatui::text::Span::raw(format!(
-                            "{:.2} m/s",
-                            ground_speeds.last().unwrap(),
-                        )),
+                        ratatui::text::Span::raw(units.speed(*ground_speeds.last().unwrap(), 2)),
                     ]),
                 ]);
 
@@ -234,14 +456,13 @@ pub fn draw<B: ratatui::backend::Backend>(
                     last_location_info.extend([
                         ratatui::text::Line::from(vec![
                             ratatui::text::Span::styled("ascent: ", bold_style),
-                            ratatui::text::Span::raw(format!("{:.2} m", ascents.last().unwrap(),)),
+                            ratatui::text::Span::raw(units.length(*ascents.last().unwrap(), 2)),
                         ]),
                         ratatui::text::Line::from(vec![
                             ratatui::text::Span::styled("ascent rate: ", bold_style),
-                            ratatui::text::Span::raw(format!(
-                                "{:.2} m/s",
-                                ascent_rates.last().unwrap(),
-                            )),
+                            ratatui::text::Span::raw(
+                                units.vertical_speed(*ascent_rates.last().unwrap(), 2),
+                            ),
                         ]),
                     ]);
                 }
@@ -256,12 +477,56 @@ pub fn draw<B: ratatui::backend::Backend>(
                 .wrap(ratatui::widgets::Wrap { trim: true });
             frame.render_widget(last_location_info, track_info_areas[0]);
 
+            let landing = track.landing();
             let mut descent_info = vec![];
-            if track.descending() {
+            if let Some(landing) = &landing {
+                descent_info.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+                    format!(
+                        "LANDED at ({:.5}, {:.5}) {:}",
+                        landing.location.coord.y,
+                        landing.location.coord.x,
+                        crate::utilities::display_time(&landing.location.time, timezone, "%H:%M"),
+                    ),
+                    bold_style.fg(palette.success),
+                )));
+                if let Some(error) = track.landing_error {
+                    descent_info.push(ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("prediction error: ", bold_style),
+                        ratatui::text::Span::raw(units.distance(error, 1)),
+                    ]));
+                }
+            }
+
+            let floating = landing.is_none() && track.floating();
+            if floating {
+                if let Some(altitude) = last_location.location.altitude {
+                    descent_info.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+                        format!("FLOATING at {:}", units.length(altitude, 0)),
+                        bold_style.fg(palette.info),
+                    )));
+                }
+            }
+
+            if let Some(burst) = track.burst() {
+                descent_info.push(ratatui::text::Line::from(vec![
+                    ratatui::text::Span::styled("burst: ", bold_style),
+                    ratatui::text::Span::raw(format!(
+                        "{:} ({:})",
+                        units.length(burst.location.altitude.unwrap(), 2),
+                        crate::utilities::display_time(
+                            &burst.location.time,
+                            timezone,
+                            &crate::DATETIME_FORMAT
+                        ),
+                    )),
+                ]));
+            }
+
+            if landing.is_none() && track.descending() {
                 if has_altitude {
                     descent_info.push(ratatui::text::Line::from(vec![
                         ratatui::text::Span::styled("max altitude: ", bold_style),
-                        ratatui::text::Span::raw(format!("{:.2} m", altitude_range[1])),
+                        ratatui::text::Span::raw(units.length(altitude_range[1], 2)),
                     ]));
                 }
 
@@ -275,7 +540,11 @@ pub fn draw<B: ratatui::backend::Backend>(
                             crate::utilities::duration_string(
                                 &(landing_time - chrono::Local::now())
                             ),
-                            landing_time.format(&crate::DATETIME_FORMAT),
+                            crate::utilities::display_time(
+                                &landing_time,
+                                timezone,
+                                &crate::DATETIME_FORMAT
+                            ),
                         )),
                     ]));
                 }
@@ -287,11 +556,18 @@ pub fn draw<B: ratatui::backend::Backend>(
                     descent_info.push(ratatui::text::Line::from(vec![
                         ratatui::text::Span::styled("@ term. vel.: ", bold_style),
                         ratatui::text::Span::raw(format!(
-                            "{:} ({:})",
+                            "{:} ± {:} ({:})",
                             crate::utilities::duration_string(
                                 &(landing_time - chrono::Local::now())
                             ),
-                            landing_time.format(&crate::DATETIME_FORMAT),
+                            crate::utilities::duration_string(
+                                &freefall_estimate.time_to_ground_uncertainty
+                            ),
+                            crate::utilities::display_time(
+                                &landing_time,
+                                timezone,
+                                &crate::DATETIME_FORMAT
+                            ),
                         )),
                     ]));
                 }
@@ -308,10 +584,11 @@ pub fn draw<B: ratatui::backend::Backend>(
                                     &(predicted_landing_location.location.time
                                         - chrono::Local::now()),
                                 ),
-                                predicted_landing_location
-                                    .location
-                                    .time
-                                    .format(&crate::DATETIME_FORMAT)
+                                crate::utilities::display_time(
+                                    &predicted_landing_location.location.time,
+                                    timezone,
+                                    &crate::DATETIME_FORMAT
+                                )
                             )),
                         ]),
                         ratatui::text::Line::from(vec![
@@ -323,6 +600,62 @@ pub fn draw<B: ratatui::backend::Backend>(
                             )),
                         ]),
                     ]);
+
+                    // farthest landing of the perturbed predictions from the default prediction
+                    if let Some(spread) = track
+                        .predicted_landings
+                        .iter()
+                        .map(|landing| {
+                            predicted_landing_location
+                                .location
+                                .distance_to(&landing.location)
+                        })
+                        .max_by(|a, b| a.total_cmp(b))
+                    {
+                        descent_info.push(ratatui::text::Line::from(vec![
+                            ratatui::text::Span::styled("landing spread: ", bold_style),
+                            ratatui::text::Span::raw(format!(
+                                "{:} ({:} predictions)",
+                                units.distance(spread, 1),
+                                track.predicted_landings.len() + 1
+                            )),
+                        ]));
+                    }
+
+                    // forecast model runs are named in UTC
+                    if let Some(dataset_time) = track
+                        .prediction_metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.dataset_time)
+                    {
+                        descent_info.push(ratatui::text::Line::from(ratatui::text::Span::raw(
+                            format!(
+                                "prediction based on GFS run {:}",
+                                dataset_time.format("%Y-%m-%d %H:%M")
+                            ),
+                        )));
+                    }
+                }
+            }
+
+            for (index, (name, prediction)) in track.named_predictions().enumerate() {
+                if let Some(profile_landing) = prediction.last() {
+                    descent_info.push(ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled(
+                            format!("{:} landing: ", name),
+                            bold_style.fg(palette.profile(index)),
+                        ),
+                        ratatui::text::Span::raw(format!(
+                            "({:.2}, {:.2}) {:}",
+                            profile_landing.location.coord.x,
+                            profile_landing.location.coord.y,
+                            crate::utilities::display_time(
+                                &profile_landing.location.time,
+                                timezone,
+                                &crate::DATETIME_FORMAT
+                            )
+                        )),
+                    ]));
                 }
             }
 
@@ -331,7 +664,13 @@ pub fn draw<B: ratatui::backend::Backend>(
                     .block(
                         ratatui::widgets::Block::default()
                             .borders(ratatui::widgets::Borders::ALL)
-                            .title("Descent"),
+                            .title(if landing.is_some() {
+                                "Landed"
+                            } else if floating {
+                                "Float"
+                            } else {
+                                "Descent"
+                            }),
                     )
                     .wrap(ratatui::widgets::Wrap { trim: true });
                 frame.render_widget(descent_info, track_info_areas[1]);
@@ -361,8 +700,11 @@ pub fn draw<B: ratatui::backend::Backend>(
                                 ratatui::text::Span::styled("est. max alt.: ", bold_style),
                                 ratatui::text::Span::raw(format!(
                                     "{:} ({:})",
-                                    (chrono::Local::now() + estimated_time_to_max_altitude)
-                                        .format(&crate::DATETIME_FORMAT),
+                                    crate::utilities::display_time(
+                                        &(chrono::Local::now() + estimated_time_to_max_altitude),
+                                        timezone,
+                                        &crate::DATETIME_FORMAT
+                                    ),
                                     crate::utilities::duration_string(
                                         &estimated_time_to_max_altitude
                                     )
@@ -372,9 +714,11 @@ pub fn draw<B: ratatui::backend::Backend>(
                                 ratatui::text::Span::styled("pred. max alt.: ", bold_style),
                                 ratatui::text::Span::raw(format!(
                                     "{:} ({:})",
-                                    predicted_max_altitude_location
-                                        .time
-                                        .format(&crate::DATETIME_FORMAT),
+                                    crate::utilities::display_time(
+                                        &predicted_max_altitude_location.time,
+                                        timezone,
+                                        &crate::DATETIME_FORMAT
+                                    ),
                                     crate::utilities::duration_string(
                                         &(predicted_max_altitude_location.time
                                             - chrono::Local::now())
@@ -402,10 +746,10 @@ pub fn draw<B: ratatui::backend::Backend>(
                                 format!("{:<10}", "pos. ascent rate: "),
                                 bold_style,
                             ),
-                            ratatui::text::Span::raw(format!(
-                                "{:.2} m/s",
+                            ratatui::text::Span::raw(units.vertical_speed(
                                 positive_ascent_rates.iter().sum::<f64>()
-                                    / positive_ascent_rates.len() as f64
+                                    / positive_ascent_rates.len() as f64,
+                                2,
                             )),
                         ]),
                         ratatui::text::Line::from(vec![
@@ -413,10 +757,10 @@ pub fn draw<B: ratatui::backend::Backend>(
                                 format!("{:<10}", "neg. ascent rate: "),
                                 bold_style,
                             ),
-                            ratatui::text::Span::raw(format!(
-                                "{:.2} m/s",
+                            ratatui::text::Span::raw(units.vertical_speed(
                                 negative_ascent_rates.iter().sum::<f64>()
-                                    / negative_ascent_rates.len() as f64
+                                    / negative_ascent_rates.len() as f64,
+                                2,
                             )),
                         ]),
                     ]);
@@ -425,9 +769,9 @@ pub fn draw<B: ratatui::backend::Backend>(
                 track_info.extend([
                     ratatui::text::Line::from(vec![
                         ratatui::text::Span::styled("ground speed: ", bold_style),
-                        ratatui::text::Span::raw(format!(
-                            "{:.2} m/s",
+                        ratatui::text::Span::raw(units.speed(
                             ground_speeds.iter().sum::<f64>() / ground_speeds.len() as f64,
+                            2,
                         )),
                     ]),
                     ratatui::text::Line::from(vec![
@@ -449,6 +793,42 @@ pub fn draw<B: ratatui::backend::Backend>(
                     )
                     .wrap(ratatui::widgets::Wrap { trim: true });
                 frame.render_widget(track_info, track_info_areas[2]);
+
+                let mut totals_info = vec![
+                    ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("distance: ", bold_style),
+                        ratatui::text::Span::raw(
+                            units.distance(track.total_overground_distance(), 2),
+                        ),
+                    ]),
+                    ratatui::text::Line::from(vec![
+                        ratatui::text::Span::styled("ascent: ", bold_style),
+                        ratatui::text::Span::raw(units.distance(track.total_ascent(), 2)),
+                    ]),
+                ];
+                if let (Some(max_altitude), Some(min_altitude)) =
+                    (track.max_altitude(), track.min_altitude())
+                {
+                    totals_info.extend([
+                        ratatui::text::Line::from(vec![
+                            ratatui::text::Span::styled("max altitude: ", bold_style),
+                            ratatui::text::Span::raw(units.length(max_altitude, 2)),
+                        ]),
+                        ratatui::text::Line::from(vec![
+                            ratatui::text::Span::styled("min altitude: ", bold_style),
+                            ratatui::text::Span::raw(units.length(min_altitude, 2)),
+                        ]),
+                    ]);
+                }
+
+                let totals_info = ratatui::widgets::Paragraph::new(totals_info)
+                    .block(
+                        ratatui::widgets::Block::default()
+                            .borders(ratatui::widgets::Borders::ALL)
+                            .title("Flight totals"),
+                    )
+                    .wrap(ratatui::widgets::Wrap { trim: true });
+                frame.render_widget(totals_info, track_info_areas[3]);
             }
 
             let mut datasets = vec![];
@@ -468,12 +848,24 @@ pub fn draw<B: ratatui::backend::Backend>(
                 end_time,
             ]
             .iter()
-            .map(|value| ratatui::text::Span::raw(value.format(time_format).to_string()))
+            .map(|value| {
+                ratatui::text::Span::raw(crate::utilities::display_time(
+                    value,
+                    timezone,
+                    time_format,
+                ))
+            })
             .collect();
 
             let chart_name = CHARTS.get(app.chart_index).unwrap();
+            let remaining_prediction = track.remaining_prediction();
+            let remaining_predictions = track.remaining_predictions();
             let telemetry_data: Vec<(f64, f64)>;
             let predicted_data: Vec<(f64, f64)>;
+            let profile_data: Vec<ProfileSeries>;
+            let landing_data: Vec<(f64, f64)>;
+            let landing_zone_data: Vec<(f64, f64)>;
+            let mut drift_data: Vec<Vec<(f64, f64)>> = vec![];
 
             let mut draw_chart = true;
             if chart_name == "altitude / time" && has_altitude {
@@ -485,7 +877,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                 datasets.push(
                     ratatui::widgets::Dataset::default()
                         .marker(ratatui::symbols::Marker::Braille)
-                        .style(ratatui::style::Style::default().fg(ratatui::style::Color::Blue))
+                        .style(ratatui::style::Style::default().fg(palette.telemetry))
                         .data(&telemetry_data)
                         .name("telemetry")
                         .graph_type(ratatui::widgets::GraphType::Scatter),
@@ -494,7 +886,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                 x_range = [0.0, (end_time - start_time).num_seconds() as f64];
                 y_range = altitude_range;
 
-                if let Some(prediction) = &track.prediction {
+                if let Some(prediction) = &remaining_prediction {
                     let with_altitude = crate::location::track::with_altitude(prediction);
                     let seconds_since_start: Vec<f64> = with_altitude
                         .iter()
@@ -543,13 +935,32 @@ pub fn draw<B: ratatui::backend::Backend>(
                     datasets.push(
                         ratatui::widgets::Dataset::default()
                             .marker(ratatui::symbols::Marker::Braille)
-                            .style(ratatui::style::Style::default().fg(ratatui::style::Color::Red))
+                            .style(ratatui::style::Style::default().fg(palette.prediction))
                             .data(&predicted_data)
                             .name("prediction")
                             .graph_type(ratatui::widgets::GraphType::Scatter),
                     );
                 }
 
+                profile_data = profile_series(&remaining_predictions, |prediction| {
+                    crate::location::track::with_altitude(prediction)
+                        .iter()
+                        .map(|location| {
+                            (
+                                (location.location.time - start_time).num_seconds() as f64,
+                                location.location.altitude.unwrap(),
+                            )
+                        })
+                        .collect()
+                });
+                push_profiles(
+                    &profile_data,
+                    &palette,
+                    &mut datasets,
+                    &mut x_range,
+                    &mut y_range,
+                );
+
                 x_labels = time_labels;
                 y_labels = [
                     y_range[0],
@@ -557,12 +968,13 @@ pub fn draw<B: ratatui::backend::Backend>(
                     y_range[1],
                 ]
                 .iter()
-                .map(|value| ratatui::text::Span::raw(format!("{:.1} m", value)))
+                .map(|value| ratatui::text::Span::raw(units.length(*value, 1)))
                 .collect();
             } else if chart_name == "ascent rate / time"
                 && has_altitude
                 && locations_with_altitude.len() > 1
             {
+                let ascent_rates = track.smoothed_ascent_rates();
                 telemetry_data = seconds_since_start
                     .iter()
                     .zip(ascent_rates.iter())
@@ -571,7 +983,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                 datasets.push(
                     ratatui::widgets::Dataset::default()
                         .marker(ratatui::symbols::Marker::Braille)
-                        .style(ratatui::style::Style::default().fg(ratatui::style::Color::Blue))
+                        .style(ratatui::style::Style::default().fg(palette.telemetry))
                         .data(&telemetry_data)
                         .name("telemetry")
                         .graph_type(ratatui::widgets::GraphType::Scatter),
@@ -591,7 +1003,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                         .to_owned(),
                 ];
 
-                if let Some(prediction) = &track.prediction {
+                if let Some(prediction) = &remaining_prediction {
                     let with_altitude = crate::location::track::with_altitude(prediction);
                     let seconds_since_start: Vec<f64> = with_altitude
                         .iter()
@@ -634,20 +1046,33 @@ pub fn draw<B: ratatui::backend::Backend>(
                         y_range[1] = max_y;
                     }
 
-                    predicted_data = seconds_since_start
-                        .into_iter()
-                        .zip(ascent_rates.into_iter())
-                        .collect();
+                    predicted_data = seconds_since_start.into_iter().zip(ascent_rates).collect();
                     datasets.push(
                         ratatui::widgets::Dataset::default()
                             .marker(ratatui::symbols::Marker::Braille)
-                            .style(ratatui::style::Style::default().fg(ratatui::style::Color::Red))
+                            .style(ratatui::style::Style::default().fg(palette.prediction))
                             .data(&predicted_data)
                             .name("prediction")
                             .graph_type(ratatui::widgets::GraphType::Scatter),
                     );
                 }
 
+                profile_data = profile_series(&remaining_predictions, |prediction| {
+                    let with_altitude = crate::location::track::with_altitude(prediction);
+                    with_altitude
+                        .iter()
+                        .map(|location| (location.location.time - start_time).num_seconds() as f64)
+                        .zip(crate::location::track::ascent_rates(&with_altitude))
+                        .collect()
+                });
+                push_profiles(
+                    &profile_data,
+                    &palette,
+                    &mut datasets,
+                    &mut x_range,
+                    &mut y_range,
+                );
+
                 x_labels = time_labels;
                 y_labels = [
                     y_range[0],
@@ -655,20 +1080,17 @@ pub fn draw<B: ratatui::backend::Backend>(
                     y_range[1],
                 ]
                 .iter()
-                .map(|value| ratatui::text::Span::raw(format!("{:.1} m/s", value)))
+                .map(|value| ratatui::text::Span::raw(units.vertical_speed(*value, 1)))
                 .collect();
             } else if chart_name == "ground speed / altitude"
                 && has_altitude
                 && locations_with_altitude.len() > 1
             {
-                telemetry_data = altitudes
-                    .into_iter()
-                    .zip(ground_speeds.clone().into_iter())
-                    .collect();
+                telemetry_data = altitudes.into_iter().zip(ground_speeds.clone()).collect();
                 datasets.push(
                     ratatui::widgets::Dataset::default()
                         .marker(ratatui::symbols::Marker::Braille)
-                        .style(ratatui::style::Style::default().fg(ratatui::style::Color::Blue))
+                        .style(ratatui::style::Style::default().fg(palette.telemetry))
                         .data(&telemetry_data)
                         .name("telemetry")
                         .graph_type(ratatui::widgets::GraphType::Scatter),
@@ -688,7 +1110,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                         .to_owned(),
                 ];
 
-                if let Some(prediction) = &track.prediction {
+                if let Some(prediction) = &remaining_prediction {
                     let with_altitude = crate::location::track::with_altitude(prediction);
                     let altitudes = crate::location::track::altitudes(&with_altitude);
                     let ground_speeds = crate::location::track::ground_speeds(&with_altitude);
@@ -726,27 +1148,39 @@ pub fn draw<B: ratatui::backend::Backend>(
                         y_range[1] = max_y;
                     }
 
-                    predicted_data = altitudes
-                        .into_iter()
-                        .zip(ground_speeds.into_iter())
-                        .collect();
+                    predicted_data = altitudes.into_iter().zip(ground_speeds).collect();
                     datasets.push(
                         ratatui::widgets::Dataset::default()
                             .marker(ratatui::symbols::Marker::Braille)
-                            .style(ratatui::style::Style::default().fg(ratatui::style::Color::Red))
+                            .style(ratatui::style::Style::default().fg(palette.prediction))
                             .data(&predicted_data)
                             .name("prediction")
                             .graph_type(ratatui::widgets::GraphType::Scatter),
                     );
                 }
 
+                profile_data = profile_series(&remaining_predictions, |prediction| {
+                    let with_altitude = crate::location::track::with_altitude(prediction);
+                    crate::location::track::altitudes(&with_altitude)
+                        .into_iter()
+                        .zip(crate::location::track::ground_speeds(&with_altitude))
+                        .collect()
+                });
+                push_profiles(
+                    &profile_data,
+                    &palette,
+                    &mut datasets,
+                    &mut x_range,
+                    &mut y_range,
+                );
+
                 x_labels = [
                     x_range[0],
                     x_range[0] + ((x_range[1] - x_range[0]) / 2.0),
                     x_range[1],
                 ]
                 .iter()
-                .map(|value| ratatui::text::Span::raw(format!("{:.1} m", value)))
+                .map(|value| ratatui::text::Span::raw(units.length(*value, 1)))
                 .collect();
                 y_labels = [
                     y_range[0],
@@ -754,7 +1188,108 @@ pub fn draw<B: ratatui::backend::Backend>(
                     y_range[1],
                 ]
                 .iter()
-                .map(|value| ratatui::text::Span::raw(format!("{:.1} m/s", value)))
+                .map(|value| ratatui::text::Span::raw(units.speed(*value, 1)))
+                .collect();
+            } else if chart_name == "wind / altitude"
+                && has_altitude
+                && locations_with_altitude.len() > 1
+            {
+                let mut drift_velocities = vec![crate::location::track::drift_velocities(
+                    track.kinematic_locations(),
+                )];
+                if let Some(prediction) = &remaining_prediction {
+                    drift_velocities.push(crate::location::track::drift_velocities(prediction));
+                }
+
+                for velocities in &drift_velocities {
+                    drift_data.push(
+                        velocities
+                            .iter()
+                            .map(|(altitude, east, _)| (*altitude, *east))
+                            .collect(),
+                    );
+                    drift_data.push(
+                        velocities
+                            .iter()
+                            .map(|(altitude, _, north)| (*altitude, *north))
+                            .collect(),
+                    );
+                }
+
+                x_range = altitude_range;
+                y_range = [0.0, 0.0];
+                for (altitude, east, north) in drift_velocities.iter().flatten() {
+                    x_range[0] = x_range[0].min(*altitude);
+                    x_range[1] = x_range[1].max(*altitude);
+                    y_range[0] = y_range[0].min(east.min(*north));
+                    y_range[1] = y_range[1].max(east.max(*north));
+                }
+
+                for (data, (name, color)) in drift_data.iter().zip([
+                    ("east", palette.telemetry),
+                    ("north", palette.telemetry_secondary),
+                    ("predicted east", palette.prediction),
+                    ("predicted north", palette.prediction_secondary),
+                ]) {
+                    datasets.push(
+                        ratatui::widgets::Dataset::default()
+                            .marker(ratatui::symbols::Marker::Braille)
+                            .style(ratatui::style::Style::default().fg(color))
+                            .data(data)
+                            .name(name)
+                            .graph_type(ratatui::widgets::GraphType::Scatter),
+                    );
+                }
+
+                // east and north components of each profile share its color
+                profile_data = remaining_predictions
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, (name, prediction))| {
+                        let velocities = crate::location::track::drift_velocities(prediction);
+                        [
+                            (
+                                index,
+                                format!("{:} east", name),
+                                velocities
+                                    .iter()
+                                    .map(|(altitude, east, _)| (*altitude, *east))
+                                    .collect(),
+                            ),
+                            (
+                                index,
+                                format!("{:} north", name),
+                                velocities
+                                    .iter()
+                                    .map(|(altitude, _, north)| (*altitude, *north))
+                                    .collect(),
+                            ),
+                        ]
+                    })
+                    .collect();
+                push_profiles(
+                    &profile_data,
+                    &palette,
+                    &mut datasets,
+                    &mut x_range,
+                    &mut y_range,
+                );
+
+                x_labels = [
+                    x_range[0],
+                    x_range[0] + ((x_range[1] - x_range[0]) / 2.0),
+                    x_range[1],
+                ]
+                .iter()
+                .map(|value| ratatui::text::Span::raw(units.length(*value, 1)))
+                .collect();
+                y_labels = [
+                    y_range[0],
+                    y_range[0] + ((y_range[1] - y_range[0]) / 2.0),
+                    y_range[1],
+                ]
+                .iter()
+                .map(|value| ratatui::text::Span::raw(units.speed(*value, 1)))
                 .collect();
             } else if chart_name == "coordinates (unprojected)" {
                 telemetry_data = track
@@ -765,7 +1300,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                 datasets.push(
                     ratatui::widgets::Dataset::default()
                         .marker(ratatui::symbols::Marker::Braille)
-                        .style(ratatui::style::Style::default().fg(ratatui::style::Color::Blue))
+                        .style(ratatui::style::Style::default().fg(palette.telemetry))
                         .data(&telemetry_data)
                         .name("telemetry")
                         .graph_type(ratatui::widgets::GraphType::Scatter),
@@ -796,7 +1331,7 @@ pub fn draw<B: ratatui::backend::Backend>(
                         .unwrap(),
                 ];
 
-                if let Some(prediction) = &track.prediction {
+                if let Some(prediction) = &remaining_prediction {
                     let predicted_x: Vec<f64> = prediction
                         .iter()
                         .map(|location| location.location.coord.x)
@@ -839,20 +1374,68 @@ pub fn draw<B: ratatui::backend::Backend>(
                         y_range[1] = max_y;
                     }
 
-                    predicted_data = predicted_x
-                        .into_iter()
-                        .zip(predicted_y.into_iter())
-                        .collect();
+                    predicted_data = predicted_x.into_iter().zip(predicted_y).collect();
                     datasets.push(
                         ratatui::widgets::Dataset::default()
                             .marker(ratatui::symbols::Marker::Braille)
-                            .style(ratatui::style::Style::default().fg(ratatui::style::Color::Red))
+                            .style(ratatui::style::Style::default().fg(palette.prediction))
                             .data(&predicted_data)
                             .name("prediction")
                             .graph_type(ratatui::widgets::GraphType::Scatter),
                     );
                 }
 
+                profile_data = profile_series(&remaining_predictions, |prediction| {
+                    prediction
+                        .iter()
+                        .map(|location| location.location.coord.x_y())
+                        .collect()
+                });
+                push_profiles(
+                    &profile_data,
+                    &palette,
+                    &mut datasets,
+                    &mut x_range,
+                    &mut y_range,
+                );
+
+                if !track.predicted_landings.is_empty() {
+                    landing_data = track
+                        .predicted_landings
+                        .iter()
+                        .map(|location| location.location.coord.x_y())
+                        .collect();
+                    for (x, y) in &landing_data {
+                        x_range = [x_range[0].min(*x), x_range[1].max(*x)];
+                        y_range = [y_range[0].min(*y), y_range[1].max(*y)];
+                    }
+
+                    datasets.push(
+                        ratatui::widgets::Dataset::default()
+                            .marker(ratatui::symbols::Marker::Dot)
+                            .style(ratatui::style::Style::default().fg(palette.landing))
+                            .data(&landing_data)
+                            .name("landing spread")
+                            .graph_type(ratatui::widgets::GraphType::Scatter),
+                    );
+                }
+
+                if let Some(landing_zone) = track.landing_zone() {
+                    landing_zone_data = landing_zone
+                        .exterior()
+                        .coords()
+                        .map(|coord| coord.x_y())
+                        .collect();
+                    datasets.push(
+                        ratatui::widgets::Dataset::default()
+                            .marker(ratatui::symbols::Marker::Braille)
+                            .style(ratatui::style::Style::default().fg(palette.landing_zone))
+                            .data(&landing_zone_data)
+                            .name("landing zone")
+                            .graph_type(ratatui::widgets::GraphType::Line),
+                    );
+                }
+
                 x_labels = [
                     x_range[0],
                     x_range[0] + ((x_range[1] - x_range[0]) / 2.0),
@@ -873,34 +1456,66 @@ pub fn draw<B: ratatui::backend::Backend>(
                 draw_chart = false;
             }
 
-            if draw_chart {
+            let mut chart_title = chart_name.to_owned();
+            if let Some(view) = &app.chart_view {
+                x_range = view.window(x_range);
+                // the unprojected coordinates keep their aspect ratio
+                if chart_name == "coordinates (unprojected)" {
+                    let center = (y_range[0] + y_range[1]) / 2.0;
+                    let half_height = (y_range[1] - y_range[0]) * view.width / 2.0;
+                    y_range = [center - half_height, center + half_height];
+                    y_labels = [y_range[0], center, y_range[1]]
+                        .iter()
+                        .map(|value| ratatui::text::Span::raw(format!("{:.1}", value)))
+                        .collect();
+                }
+                x_labels = [
+                    x_range[0],
+                    x_range[0] + ((x_range[1] - x_range[0]) / 2.0),
+                    x_range[1],
+                ]
+                .iter()
+                .map(|value| {
+                    ratatui::text::Span::raw(match chart_name.as_str() {
+                        "altitude / time" | "ascent rate / time" => crate::utilities::display_time(
+                            &(start_time + chrono::Duration::seconds(*value as i64)),
+                            timezone,
+                            time_format,
+                        ),
+                        "ground speed / altitude" | "wind / altitude" => units.length(*value, 1),
+                        _ => format!("{:.1}", value),
+                    })
+                })
+                .collect();
+                chart_title += &format!(" (zoom {:.0}x)", 1.0 / view.width);
+            }
+
+            if app.show_raw {
+                draw_packet(frame, track_areas[1], app, track);
+            } else if app.show_map {
+                draw_map(frame, track_areas[1], app, track);
+            } else if draw_chart {
                 let chart = ratatui::widgets::Chart::new(datasets)
                     .block(
                         ratatui::widgets::Block::default()
                             .title(ratatui::text::Span::styled(
-                                chart_name,
+                                chart_title,
                                 ratatui::style::Style::default()
-                                    .fg(ratatui::style::Color::Cyan)
+                                    .fg(palette.title)
                                     .add_modifier(ratatui::style::Modifier::BOLD),
                             ))
                             .borders(ratatui::widgets::Borders::ALL),
                     )
                     .x_axis(
                         ratatui::widgets::Axis::default()
-                            .style(
-                                ratatui::style::Style::default()
-                                    .fg(ratatui::style::Color::DarkGray),
-                            )
+                            .style(ratatui::style::Style::default().fg(palette.axis))
                             .labels(x_labels)
                             .labels_alignment(ratatui::layout::Alignment::Right)
                             .bounds(x_range),
                     )
                     .y_axis(
                         ratatui::widgets::Axis::default()
-                            .style(
-                                ratatui::style::Style::default()
-                                    .fg(ratatui::style::Color::DarkGray),
-                            )
+                            .style(ratatui::style::Style::default().fg(palette.axis))
                             .labels(y_labels)
                             .labels_alignment(ratatui::layout::Alignment::Right)
                             .bounds(y_range),
@@ -911,4 +1526,396 @@ pub fn draw<B: ratatui::backend::Backend>(
             frame.render_widget(block, areas[1]);
         }
     }
+
+    if app.show_help {
+        draw_help(frame, size, app);
+    }
+}
+
+// arrow pointing in the given direction (degrees clockwise from north)
+fn heading_arrow(heading: f64) -> &'static str {
+    const ARROWS: [&str; 8] = ["↑", "↗", "→", "↘", "↓", "↙", "←", "↖"];
+    ARROWS[((heading.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+// keybindings, filters, and connections, centered over the current view
+fn draw_help<B: ratatui::backend::Backend>(
+    frame: &mut ratatui::Frame<B>,
+    area: ratatui::layout::Rect,
+    app: &super::app::PacketravenApp,
+) {
+    let bold_style = ratatui::style::Style::default().add_modifier(ratatui::style::Modifier::BOLD);
+    let configuration = &app.configuration;
+    let palette = configuration.theme.palette();
+    let timezone = configuration.timezone;
+
+    let mut lines = vec![ratatui::text::Line::from(ratatui::text::Span::styled(
+        "keys", bold_style,
+    ))];
+    for (name, keys, _) in configuration.keybindings.bindings() {
+        lines.push(ratatui::text::Line::from(format!(
+            "  {:<16}{:}",
+            name,
+            keys.iter()
+                .map(crate::configuration::keybindings::key_name)
+                .collect::<Vec<String>>()
+                .join(", ")
+        )));
+    }
+
+    lines.push(ratatui::text::Line::from(""));
+    lines.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+        "filters", bold_style,
+    )));
+    lines.push(ratatui::text::Line::from(format!(
+        "  callsigns   {:}",
+        match &configuration.callsigns {
+            Some(callsigns) if !callsigns.is_empty() => callsigns.join(", "),
+            _ => String::from("any"),
+        }
+    )));
+    let time_string = |time: &Option<chrono::DateTime<chrono::Local>>| match time {
+        Some(time) => crate::utilities::display_time(time, timezone, &crate::DATETIME_FORMAT),
+        None => String::from("..."),
+    };
+    lines.push(ratatui::text::Line::from(format!(
+        "  time        {:} to {:}",
+        time_string(&configuration.time.start),
+        time_string(&configuration.time.end),
+    )));
+
+    lines.push(ratatui::text::Line::from(""));
+    lines.push(ratatui::text::Line::from(ratatui::text::Span::styled(
+        "connections",
+        bold_style,
+    )));
+    if app.connections.is_empty() {
+        lines.push(ratatui::text::Line::from("  none"));
+    }
+    let now = chrono::Local::now();
+    for scheduled in &app.connections {
+        lines.push(ratatui::text::Line::from(format!(
+            "  {:} every {:}, {:}",
+            scheduled.connection.name(),
+            crate::utilities::duration_string(&scheduled.interval),
+            scheduled.status.summary(now),
+        )));
+    }
+
+    let width =
+        (lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16 + 4).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let help_area = ratatui::layout::Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let help = ratatui::widgets::Paragraph::new(lines).block(
+        ratatui::widgets::Block::default()
+            .title(ratatui::text::Span::styled(
+                "Help (press any key to close)",
+                bold_style.fg(palette.title),
+            ))
+            .borders(ratatui::widgets::Borders::ALL),
+    );
+    frame.render_widget(ratatui::widgets::Clear, help_area);
+    frame.render_widget(help, help_area);
+}
+
+// raw frame and decoded fields of the latest location, in place of the chart
+fn draw_packet<B: ratatui::backend::Backend>(
+    frame: &mut ratatui::Frame<B>,
+    area: ratatui::layout::Rect,
+    app: &super::app::PacketravenApp,
+    track: &crate::location::track::BalloonTrack,
+) {
+    let units = app.configuration.units;
+    let palette = app.configuration.theme.palette();
+    let bold_style = ratatui::style::Style::default().add_modifier(ratatui::style::Modifier::BOLD);
+    let field = |name: &str, value: String| {
+        ratatui::text::Line::from(vec![
+            ratatui::text::Span::styled(format!("{:<10}", name), bold_style),
+            ratatui::text::Span::raw(value),
+        ])
+    };
+
+    let mut lines = vec![];
+    if let Some(location) = track.locations.last() {
+        lines.push(field("source", format!("{:?}", location.data.source)));
+        lines.push(field(
+            "raw",
+            location
+                .data
+                .raw
+                .to_owned()
+                .unwrap_or_else(|| String::from("none")),
+        ));
+
+        if let Some(packet) = &location.data.aprs_packet {
+            lines.push(field("from", packet.from.to_string()));
+            if let Some(to) = packet.data.to() {
+                lines.push(field("to", to.to_string()));
+            }
+            lines.push(field(
+                "via",
+                packet
+                    .via
+                    .iter()
+                    .map(|via| match via {
+                        aprs_parser::Via::Callsign(callsign, heard) => {
+                            format!("{:}{:}", callsign, if *heard { "*" } else { "" })
+                        }
+                        aprs_parser::Via::QConstruct(construct) => format!("{:?}", construct),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ));
+
+            let (kind, symbol, comment) = match &packet.data {
+                aprs_parser::AprsData::Position(position) => (
+                    "position",
+                    Some(format!(
+                        "{:}{:}",
+                        position.symbol_table, position.symbol_code
+                    )),
+                    Some(&position.comment),
+                ),
+                aprs_parser::AprsData::MicE(mic_e) => (
+                    "Mic-E",
+                    Some(format!(
+                        "{:}{:}",
+                        mic_e.symbol_table as char, mic_e.symbol_code as char
+                    )),
+                    Some(&mic_e.comment),
+                ),
+                aprs_parser::AprsData::Message(_) => ("message", None, None),
+                aprs_parser::AprsData::Status(_) => ("status", None, None),
+                aprs_parser::AprsData::Unknown(_) => ("unknown", None, None),
+            };
+            lines.push(field("type", kind.to_string()));
+            if let Some(symbol) = symbol {
+                lines.push(field("symbol", symbol));
+            }
+            if let Some(comment) = comment {
+                lines.push(field(
+                    "comment",
+                    String::from_utf8_lossy(comment).to_string(),
+                ));
+            }
+        }
+
+        lines.push(field(
+            "decoded",
+            format!(
+                "({:.5}, {:.5}){:}",
+                location.location.coord.x,
+                location.location.coord.y,
+                match location.location.altitude {
+                    Some(altitude) => format!(", {:}", units.length(altitude, 2)),
+                    None => String::new(),
+                },
+            ),
+        ));
+        if let Some(telemetry) = &location.data.telemetry {
+            lines.push(field("telemetry", format!("{:?}", telemetry)));
+        }
+        if let Some(ais) = &location.data.ais {
+            lines.push(field("MMSI", ais.mmsi.to_owned()));
+            if let Some(vessel_name) = &ais.vessel_name {
+                lines.push(field("vessel", vessel_name.to_owned()));
+            }
+            if let Some(heading) = &ais.heading {
+                lines.push(field("heading", format!("{:}°", heading)));
+            }
+            if let Some(navstat) = &ais.navstat {
+                lines.push(field("status", navstat.to_owned()));
+            }
+        }
+    } else {
+        lines.push(ratatui::text::Line::from("no packets received"));
+    }
+
+    let packet = ratatui::widgets::Paragraph::new(lines)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .block(
+            ratatui::widgets::Block::default()
+                .title(ratatui::text::Span::styled(
+                    "latest packet",
+                    bold_style.fg(palette.title),
+                ))
+                .borders(ratatui::widgets::Borders::ALL),
+        );
+    frame.render_widget(packet, area);
+}
+
+// chart data of each named profile prediction
+fn profile_series(
+    remaining_predictions: &[(&str, crate::location::track::LocationTrack)],
+    data: impl Fn(&crate::location::track::LocationTrack) -> Vec<(f64, f64)>,
+) -> Vec<ProfileSeries> {
+    remaining_predictions
+        .iter()
+        .enumerate()
+        .map(|(index, (name, prediction))| (index, name.to_string(), data(prediction)))
+        .collect()
+}
+
+// add a dataset for each series of the named profile predictions, in the color of its profile,
+// expanding the chart ranges to fit
+fn push_profiles<'a>(
+    profile_data: &'a [ProfileSeries],
+    palette: &crate::configuration::theme::Palette,
+    datasets: &mut Vec<ratatui::widgets::Dataset<'a>>,
+    x_range: &mut [f64; 2],
+    y_range: &mut [f64; 2],
+) {
+    for (index, name, data) in profile_data {
+        for (x, y) in data {
+            *x_range = [x_range[0].min(*x), x_range[1].max(*x)];
+            *y_range = [y_range[0].min(*y), y_range[1].max(*y)];
+        }
+        datasets.push(
+            ratatui::widgets::Dataset::default()
+                .marker(ratatui::symbols::Marker::Braille)
+                .style(ratatui::style::Style::default().fg(palette.profile(*index)))
+                .data(data)
+                .name(name.as_str())
+                .graph_type(ratatui::widgets::GraphType::Scatter),
+        );
+    }
+}
+
+// telemetry and predictions over a world map, in place of the chart
+fn draw_map<B: ratatui::backend::Backend>(
+    frame: &mut ratatui::Frame<B>,
+    area: ratatui::layout::Rect,
+    app: &super::app::PacketravenApp,
+    track: &crate::location::track::BalloonTrack,
+) {
+    let palette = app.configuration.theme.palette();
+    let telemetry: Vec<(f64, f64)> = crate::location::track::without_outliers(&track.locations)
+        .iter()
+        .map(|location| location.location.coord.x_y())
+        .collect();
+    let filtered: Vec<(f64, f64)> = track
+        .filtered
+        .iter()
+        .map(|location| location.location.coord.x_y())
+        .collect();
+    let predicted: Vec<(f64, f64)> = match &track.remaining_prediction() {
+        Some(prediction) => prediction
+            .iter()
+            .map(|location| location.location.coord.x_y())
+            .collect(),
+        None => vec![],
+    };
+    let profiles: Vec<Vec<(f64, f64)>> = track
+        .remaining_predictions()
+        .iter()
+        .map(|(_, prediction)| {
+            prediction
+                .iter()
+                .map(|location| location.location.coord.x_y())
+                .collect()
+        })
+        .collect();
+    let ground_station = app
+        .configuration
+        .ground_station
+        .as_ref()
+        .map(|location| location.coord.x_y());
+
+    let mut x_range = [f64::MAX, f64::MIN];
+    let mut y_range = [f64::MAX, f64::MIN];
+    for (x, y) in telemetry
+        .iter()
+        .chain(&predicted)
+        .chain(profiles.iter().flatten())
+        .chain(&ground_station)
+    {
+        x_range = [x_range[0].min(*x), x_range[1].max(*x)];
+        y_range = [y_range[0].min(*y), y_range[1].max(*y)];
+    }
+    // pad the extent so that nearby coastlines are visible
+    let padding = ((x_range[1] - x_range[0]).max(y_range[1] - y_range[0]) * 0.2).max(*MAP_PADDING);
+    let x_range = [
+        (x_range[0] - padding).max(-180.0),
+        (x_range[1] + padding).min(180.0),
+    ];
+    let y_range = [
+        (y_range[0] - padding).max(-90.0),
+        (y_range[1] + padding).min(90.0),
+    ];
+
+    let landing_zone = track.landing_zone();
+    let map = ratatui::widgets::canvas::Canvas::default()
+        .block(
+            ratatui::widgets::Block::default()
+                .title(ratatui::text::Span::styled(
+                    "map",
+                    ratatui::style::Style::default()
+                        .fg(palette.title)
+                        .add_modifier(ratatui::style::Modifier::BOLD),
+                ))
+                .borders(ratatui::widgets::Borders::ALL),
+        )
+        .marker(ratatui::symbols::Marker::Braille)
+        .x_bounds(x_range)
+        .y_bounds(y_range)
+        .paint(|context| {
+            context.draw(&ratatui::widgets::canvas::Map {
+                resolution: ratatui::widgets::canvas::MapResolution::High,
+                color: palette.axis,
+            });
+            context.layer();
+
+            if let Some(landing_zone) = &landing_zone {
+                for line in landing_zone.exterior().lines() {
+                    context.draw(&ratatui::widgets::canvas::Line {
+                        x1: line.start.x,
+                        y1: line.start.y,
+                        x2: line.end.x,
+                        y2: line.end.y,
+                        color: palette.landing_zone,
+                    });
+                }
+            }
+            context.draw(&ratatui::widgets::canvas::Points {
+                coords: &predicted,
+                color: palette.prediction,
+            });
+            for (index, profile) in profiles.iter().enumerate() {
+                context.draw(&ratatui::widgets::canvas::Points {
+                    coords: profile,
+                    color: palette.profile(index),
+                });
+            }
+            context.draw(&ratatui::widgets::canvas::Points {
+                coords: &telemetry,
+                color: palette.telemetry,
+            });
+            // smoothed path through the telemetry
+            for (start, end) in filtered.iter().zip(filtered.iter().skip(1)) {
+                context.draw(&ratatui::widgets::canvas::Line {
+                    x1: start.0,
+                    y1: start.1,
+                    x2: end.0,
+                    y2: end.1,
+                    color: palette.filtered,
+                });
+            }
+            if let Some((x, y)) = ground_station {
+                context.print(
+                    x,
+                    y,
+                    ratatui::text::Span::styled(
+                        "x",
+                        ratatui::style::Style::default().fg(palette.ground_station),
+                    ),
+                );
+            }
+        });
+    frame.render_widget(map, area);
 }
@@ -1,8 +1,10 @@
 mod app;
 mod draw;
+mod state;
 
 pub fn run(
     configuration: crate::configuration::RunConfiguration,
+    configuration_path: &std::path::Path,
     log_level: log::Level,
 ) -> Result<(), Box<dyn std::error::Error>> {
     crossterm::terminal::enable_raw_mode()?;
@@ -23,8 +25,10 @@ pub fn run(
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend)?;
 
-    let app = app::PacketravenApp::new(configuration, log_level);
-    let result = run_app(&mut terminal, app);
+    let mut app = app::PacketravenApp::new(configuration, log_level);
+    app.watch_configuration(configuration_path);
+    let result = run_app(&mut terminal, &mut app);
+    app.save_state();
 
     // restore terminal
     crossterm::terminal::disable_raw_mode()?;
@@ -44,20 +48,24 @@ pub fn run(
 
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut ratatui::Terminal<B>,
-    mut app: app::PacketravenApp,
+    app: &mut app::PacketravenApp,
 ) -> std::io::Result<()> {
-    let tick_rate = app.configuration.time.interval.to_std().unwrap();
-
     // set the first tick to be in the past to update immediately
-    let mut last_tick = std::time::Instant::now() - tick_rate;
+    let mut last_tick = std::time::Instant::now() - app.tick_rate();
 
     loop {
-        terminal.draw(|frame| draw::draw(frame, &app))?;
+        app.reload_configuration_if_changed();
+        // the interval may change when the configuration is reloaded
+        let tick_rate = app.tick_rate();
+
+        terminal.draw(|frame| draw::draw(frame, app))?;
 
+        // redraw at least every second, so that status messages expire
         if crossterm::event::poll(
             tick_rate
                 .checked_sub(last_tick.elapsed())
-                .unwrap_or_else(|| std::time::Duration::from_secs(1)),
+                .unwrap_or_else(|| std::time::Duration::from_secs(1))
+                .min(std::time::Duration::from_secs(1)),
         )? {
             if let crossterm::event::Event::Key(key) = crossterm::event::read()? {
                 if key.kind == crossterm::event::KeyEventKind::Press {
@@ -67,7 +75,10 @@ fn run_app<B: ratatui::backend::Backend>(
         }
 
         if last_tick.elapsed() >= tick_rate {
-            app.on_tick();
+            // keep drawing and handling keys while paused, but do not retrieve new packets
+            if !app.paused {
+                app.on_tick();
+            }
             last_tick = std::time::Instant::now();
         }
 
@@ -77,6 +88,104 @@ fn run_app<B: ratatui::backend::Backend>(
     }
 }
 
+// run the same update loop without a terminal interface, printing log messages to stdout
+pub fn run_headless(
+    configuration: crate::configuration::RunConfiguration,
+    configuration_path: &std::path::Path,
+    log_level: log::Level,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = app::PacketravenApp::new(configuration, log_level);
+    app.watch_configuration(configuration_path);
+
+    let mut num_printed_messages = 0;
+    loop {
+        app.reload_configuration_if_changed();
+        app.on_tick();
+
+        let end_reached = match app.configuration.time.end {
+            Some(end) => chrono::Local::now() > end,
+            None => false,
+        };
+        if end_reached {
+            app.add_log_message(
+                "reached configured end time; stopping".to_string(),
+                log::Level::Info,
+            );
+        }
+
+        for (time, message, level) in &app.log_messages[num_printed_messages..] {
+            println!(
+                "{:}",
+                app::log_line(time, message, level, app.configuration.timezone)
+            );
+        }
+        num_printed_messages = app.log_messages.len();
+
+        if end_reached || app.should_quit {
+            return Ok(());
+        }
+
+        std::thread::sleep(app.tick_rate());
+    }
+}
+
+// retrieve from every connection and predict once, without a terminal interface, then print the state of each track
+pub fn run_once(
+    configuration: crate::configuration::RunConfiguration,
+    log_level: log::Level,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = app::PacketravenApp::new(configuration, log_level);
+    app.on_tick();
+
+    for (time, message, level) in &app.log_messages {
+        println!(
+            "{:}",
+            app::log_line(time, message, level, app.configuration.timezone)
+        );
+    }
+
+    let timezone = app.configuration.timezone;
+    for track in &app.tracks {
+        let mut summary = format!("{:}: {:} location(s)", track.name, track.locations.len());
+        if let Some(latest) = track.locations.last() {
+            summary.push_str(&format!(
+                ", latest {:.5}, {:.5}",
+                latest.location.coord.y, latest.location.coord.x
+            ));
+            if let Some(altitude) = latest.location.altitude {
+                summary.push_str(&format!(" at {:.0} m", altitude));
+            }
+            summary.push_str(&format!(
+                " ({:})",
+                crate::utilities::display_time(
+                    &latest.location.time,
+                    timezone,
+                    &crate::DATETIME_FORMAT
+                )
+            ));
+        }
+        if let Some(landing) = track
+            .prediction
+            .as_ref()
+            .and_then(|prediction| prediction.last())
+        {
+            summary.push_str(&format!(
+                ", predicted landing {:.5}, {:.5} ({:})",
+                landing.location.coord.y,
+                landing.location.coord.x,
+                crate::utilities::display_time(
+                    &landing.location.time,
+                    timezone,
+                    &crate::DATETIME_FORMAT
+                )
+            ));
+        }
+        println!("{:}", summary);
+    }
+
+    Ok(())
+}
+
 fn reset_terminal() -> Result<(), Box<dyn std::error::Error>> {
     crossterm::terminal::disable_raw_mode()?;
     crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen)?;
@@ -0,0 +1,73 @@
+custom_error::custom_error! {pub StateError
+    ReadFailure { path: String, message: String } = "cannot read state file {path} - {message}",
+    WriteFailure { path: String, message: String } = "cannot write state file {path} - {message}",
+}
+
+// what the user was looking at, kept between runs so that restarting mid-flight returns to the same view
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default, PartialEq)]
+pub struct SessionState {
+    /// name of the selected track, or `None` for the log tab
+    pub track: Option<String>,
+    /// name of the selected chart
+    pub chart: Option<String>,
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(default)]
+    pub log_scroll: u16,
+}
+
+impl SessionState {
+    pub fn read(path: &std::path::Path) -> Result<Self, StateError> {
+        let failed = |message: String| StateError::ReadFailure {
+            path: path.to_string_lossy().to_string(),
+            message,
+        };
+        let file = std::fs::File::open(path).map_err(|error| failed(error.to_string()))?;
+        serde_yaml::from_reader(file).map_err(|error| failed(error.to_string()))
+    }
+
+    pub fn write(&self, path: &std::path::Path) -> Result<(), StateError> {
+        let failed = |message: String| StateError::WriteFailure {
+            path: path.to_string_lossy().to_string(),
+            message,
+        };
+        let file = std::fs::File::create(path).map_err(|error| failed(error.to_string()))?;
+        serde_yaml::to_writer(file, self).map_err(|error| failed(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_file() {
+        let path =
+            std::env::temp_dir().join(format!("packetraven_state_{:}.yaml", std::process::id()));
+
+        let state = SessionState {
+            track: Some("W3EAX-8".to_string()),
+            chart: Some("ascent rate / time".to_string()),
+            paused: true,
+            log_scroll: 4,
+        };
+        state.write(&path).unwrap();
+        assert_eq!(SessionState::read(&path).unwrap(), state);
+
+        // fields added later default when missing
+        std::fs::write(&path, "track: W3EAX-8\n").unwrap();
+        assert_eq!(
+            SessionState::read(&path).unwrap(),
+            SessionState {
+                track: Some("W3EAX-8".to_string()),
+                ..Default::default()
+            }
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            SessionState::read(&path),
+            Err(StateError::ReadFailure { .. })
+        ));
+    }
+}
@@ -183,6 +183,18 @@ pub mod optional_u64_string {
     }
 }
 
+// format a time in the given timezone, or in the local timezone if none is given
+pub fn display_time(
+    time: &chrono::DateTime<chrono::Local>,
+    timezone: Option<chrono_tz::Tz>,
+    format: &str,
+) -> String {
+    match timezone {
+        Some(timezone) => time.with_timezone(&timezone).format(format).to_string(),
+        None => time.format(format).to_string(),
+    }
+}
+
 pub fn duration_string(duration: &chrono::Duration) -> String {
     let mut parts = vec![];
 